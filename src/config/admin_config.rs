@@ -0,0 +1,79 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use mongodb::error::Error as MongoError;
+use std::fmt;
+
+use super::error_response::{public_message, ErrorResponse};
+
+#[derive(Debug)]
+pub enum AdminError {
+    MongoError(MongoError),
+    UserNotFound(String),
+    PollNotFound(String),
+    ReportNotFound(String),
+    InvalidTransition(String),
+    AuditLogError(String),
+    InvalidRequest(String),
+    GeneralError(String),
+}
+
+impl AdminError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AdminError::MongoError(_) => "database_error",
+            AdminError::UserNotFound(_) => "user_not_found",
+            AdminError::PollNotFound(_) => "poll_not_found",
+            AdminError::ReportNotFound(_) => "report_not_found",
+            AdminError::InvalidTransition(_) => "invalid_transition",
+            AdminError::AuditLogError(_) => "audit_log_failed",
+            AdminError::InvalidRequest(_) => "invalid_request",
+            AdminError::GeneralError(_) => "internal_error",
+        }
+    }
+}
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminError::MongoError(err) => write!(f, "MongoDB Error: {}", err),
+            AdminError::UserNotFound(username) => write!(f, "User '{}' not found", username),
+            AdminError::PollNotFound(poll_id) => {
+                write!(f, "Poll with ID '{}' not found", poll_id)
+            }
+            AdminError::ReportNotFound(report_id) => {
+                write!(f, "Report with ID '{}' not found", report_id)
+            }
+            AdminError::InvalidTransition(msg) => write!(f, "Conflict : {}", msg),
+            AdminError::AuditLogError(msg) => write!(f, "Audit log error: {}", msg),
+            AdminError::InvalidRequest(msg) => write!(f, "Invalid request : {}", msg),
+            AdminError::GeneralError(msg) => write!(f, "Error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+impl ResponseError for AdminError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AdminError::UserNotFound(_)
+            | AdminError::PollNotFound(_)
+            | AdminError::ReportNotFound(_) => StatusCode::NOT_FOUND,
+            AdminError::InvalidTransition(_) => StatusCode::CONFLICT,
+            AdminError::InvalidRequest(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AdminError::MongoError(_)
+            | AdminError::AuditLogError(_)
+            | AdminError::GeneralError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ErrorResponse::new(self.code(), public_message(self.status_code(), self))
+            .into_response(self.status_code())
+    }
+}
+
+impl From<MongoError> for AdminError {
+    fn from(err: MongoError) -> Self {
+        AdminError::MongoError(err)
+    }
+}
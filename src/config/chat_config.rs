@@ -0,0 +1,67 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use mongodb::error::Error as MongoError;
+use std::fmt;
+
+use super::error_response::{public_message, ErrorResponse};
+
+// Errors of the chat integration itself. Problems with the poll or the vote are answered
+// to the chat user as a message instead, since chat platforms only show a generic
+// failure for error statuses.
+#[derive(Debug)]
+pub enum ChatError {
+    MongoError(MongoError),
+    IntegrationDisabled(String),
+    InvalidSignature(String),
+    BadRequest(String),
+    GeneralError(String),
+}
+
+impl ChatError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ChatError::MongoError(_) => "database_error",
+            ChatError::IntegrationDisabled(_) => "integration_disabled",
+            ChatError::InvalidSignature(_) => "invalid_signature",
+            ChatError::BadRequest(_) => "bad_request",
+            ChatError::GeneralError(_) => "internal_error",
+        }
+    }
+}
+
+impl fmt::Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatError::MongoError(err) => write!(f, "MongoDB Error: {}", err),
+            ChatError::IntegrationDisabled(msg) => write!(f, "Integration disabled : {}", msg),
+            ChatError::InvalidSignature(msg) => write!(f, "Invalid signature : {}", msg),
+            ChatError::BadRequest(msg) => write!(f, "Bad request : {}", msg),
+            ChatError::GeneralError(msg) => write!(f, "Error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ChatError {}
+
+impl ResponseError for ChatError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ChatError::IntegrationDisabled(_) => StatusCode::NOT_FOUND,
+            ChatError::InvalidSignature(_) => StatusCode::UNAUTHORIZED,
+            ChatError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ChatError::MongoError(_) | ChatError::GeneralError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        ErrorResponse::new(self.code(), public_message(self.status_code(), self))
+            .into_response(self.status_code())
+    }
+}
+
+impl From<MongoError> for ChatError {
+    fn from(err: MongoError) -> Self {
+        ChatError::MongoError(err)
+    }
+}
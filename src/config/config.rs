@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, str::FromStr};
 
 use dotenv::dotenv;
 
@@ -9,7 +9,190 @@ use serde::Deserialize;
 pub struct AppConfig {
     pub mongodb_uri: String,
     pub database_name: String,
+    // Signs session and guest tokens. Required, see `check_secrets`.
     pub jwt_secret: String,
+    // Keys the participation markers of anonymous polls. Required, see `check_secrets`.
+    pub ballot_secret: String,
+    pub poll_retention_days: i64,
+    // Account promoted to site admin when it signs in while no admin exists, so a fresh
+    // deployment has one.
+    pub admin_username: Option<String>,
+    // Reports a single user may file per hour.
+    pub reports_per_hour: u64,
+    // Lifetime of a guest token, after which a guest would count as a new voter.
+    pub guest_token_days: i64,
+    pub poll_limits: PollLimits,
+    pub content_filter: ContentFilterConfig,
+    pub rate_limit: RateLimitConfig,
+    pub proof_of_work: PowConfig,
+    pub webhooks: WebhookConfig,
+    pub chat: ChatConfig,
+}
+
+// Limits enforced when polls are created or edited
+#[derive(Clone, Debug, Deserialize)]
+pub struct PollLimits {
+    pub title_max_length: usize,
+    pub description_max_length: usize,
+    pub min_options: usize,
+    pub max_options: usize,
+    pub option_max_length: usize,
+    pub answer_max_length: usize,
+}
+
+// Content checks run on poll titles, descriptions, options and write-ins
+#[derive(Clone, Debug, Deserialize)]
+pub struct ContentFilterConfig {
+    pub deny_list: Vec<String>,
+    pub max_urls: usize,
+    pub policy: FilterPolicy,
+}
+
+// What happens to text caught by the content filter: refused outright, or accepted
+// and sent to the moderation queue.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterPolicy {
+    Reject,
+    Flag,
+}
+
+impl FromStr for FilterPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "reject" => Ok(FilterPolicy::Reject),
+            "flag" => Ok(FilterPolicy::Flag),
+            other => Err(format!("Unknown content filter policy '{}'", other)),
+        }
+    }
+}
+
+// Token buckets applied per user, or per IP for anonymous callers, to each group of
+// routes
+#[derive(Clone, Debug, Deserialize)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub store: RateLimitStoreKind,
+    // Take the client address from `X-Forwarded-For` when running behind a proxy.
+    pub trust_proxy: bool,
+    pub auth: BucketLimit,
+    pub create: BucketLimit,
+    pub vote: BucketLimit,
+    pub results: BucketLimit,
+}
+
+// A bucket holding up to `capacity` requests, refilled by `refill_per_minute` tokens a
+// minute. Written as `capacity/refill_per_minute` in the environment, e.g. `30/60`.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct BucketLimit {
+    pub capacity: u32,
+    pub refill_per_minute: u32,
+}
+
+impl FromStr for BucketLimit {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (capacity, refill) = value
+            .split_once('/')
+            .ok_or_else(|| format!("Expected 'capacity/refill_per_minute', got '{}'", value))?;
+
+        Ok(BucketLimit {
+            capacity: capacity.trim().parse().map_err(|_| capacity.to_string())?,
+            refill_per_minute: refill.trim().parse().map_err(|_| refill.to_string())?,
+        })
+    }
+}
+
+// Hashcash-style challenges solved before voting in polls that require them. Difficulty
+// is the number of leading zero bits the solution hash needs, starting at
+// `base_difficulty` and growing with the rate of votes on the poll.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PowConfig {
+    pub secret: String,
+    pub base_difficulty: u32,
+    pub max_difficulty: u32,
+    // Votes a minute on a poll that add the first bit of difficulty, doubling the work.
+    pub votes_per_step: u64,
+    pub challenge_ttl_secs: i64,
+}
+
+// Delivery of outgoing webhooks. Failed deliveries are retried after
+// `retry_base_secs`, doubling each time up to `retry_max_secs`, until `max_attempts`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct WebhookConfig {
+    pub max_attempts: u32,
+    pub retry_base_secs: i64,
+    pub retry_max_secs: i64,
+    pub timeout_secs: u64,
+    // How often the dispatcher looks for deliveries that are due.
+    pub poll_interval_secs: u64,
+    // Webhooks a single user may register.
+    pub max_per_user: u64,
+    // Lets webhooks call private and loopback addresses, for deployments whose receivers
+    // live on the internal network. Off by default.
+    pub allow_private_targets: bool,
+}
+
+// Slash commands and vote buttons from a team chat. Requests are signed by the chat
+// platform with `signing_secret`; without one the integration is turned off.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChatConfig {
+    pub signing_secret: Option<String>,
+    // Oldest request timestamp accepted, so captured requests cannot be replayed.
+    pub max_request_age_secs: i64,
+}
+
+// Where buckets are kept: in process memory, or in MongoDB so that every instance of a
+// deployment shares them.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitStoreKind {
+    Memory,
+    MongoDB,
+}
+
+impl FromStr for RateLimitStoreKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "memory" => Ok(RateLimitStoreKind::Memory),
+            "mongodb" => Ok(RateLimitStoreKind::MongoDB),
+            other => Err(format!("Unknown rate limit store '{}'", other)),
+        }
+    }
+}
+
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+impl AppConfig {
+    // Secrets that have no safe default: anyone knowing a published default could forge
+    // what they protect, so the server refuses to start until they are set.
+    pub fn check_secrets(&self) -> Result<(), String> {
+        let missing: Vec<&str> = [
+            ("JWT_SECRET", &self.jwt_secret),
+            ("BALLOT_SECRET", &self.ballot_secret),
+            ("POW_SECRET", &self.proof_of_work.secret),
+        ]
+        .into_iter()
+        .filter(|(_, secret)| secret.trim().is_empty())
+        .map(|(name, _)| name)
+        .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Missing required secrets: {}", missing.join(", ")))
+        }
+    }
 }
 
 impl Default for AppConfig {
@@ -21,8 +204,104 @@ impl Default for AppConfig {
                 .unwrap_or_else(|_| "mongodb://localhost:27017".to_string()),
             database_name: env::var("DATABASE_NAME")
                 .unwrap_or_else(|_| "polling_application".to_string()),
-            jwt_secret: env::var("JWT_SECRET")
-                .unwrap_or_else(|_| "thisisthemostsecuresecret".to_string()),
+            jwt_secret: env::var("JWT_SECRET").unwrap_or_default(),
+            ballot_secret: env::var("BALLOT_SECRET").unwrap_or_default(),
+            poll_retention_days: env_or("POLL_RETENTION_DAYS", 30),
+            admin_username: env::var("ADMIN_USERNAME")
+                .ok()
+                .filter(|username| !username.trim().is_empty()),
+            reports_per_hour: env_or("REPORTS_PER_HOUR", 10),
+            guest_token_days: env_or("GUEST_TOKEN_DAYS", 30),
+            poll_limits: PollLimits::default(),
+            content_filter: ContentFilterConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            proof_of_work: PowConfig::default(),
+            webhooks: WebhookConfig::default(),
+            chat: ChatConfig::default(),
+        }
+    }
+}
+
+impl Default for PollLimits {
+    fn default() -> Self {
+        Self {
+            title_max_length: env_or("POLL_TITLE_MAX_LENGTH", 200),
+            description_max_length: env_or("POLL_DESCRIPTION_MAX_LENGTH", 1000),
+            min_options: env_or("POLL_MIN_OPTIONS", 2),
+            max_options: env_or("POLL_MAX_OPTIONS", 20),
+            option_max_length: env_or("POLL_OPTION_MAX_LENGTH", 100),
+            answer_max_length: env_or("POLL_ANSWER_MAX_LENGTH", 500),
+        }
+    }
+}
+
+impl Default for ContentFilterConfig {
+    fn default() -> Self {
+        Self {
+            deny_list: env::var("CONTENT_DENY_LIST")
+                .unwrap_or_default()
+                .split(',')
+                .map(|term| term.trim().to_string())
+                .filter(|term| !term.is_empty())
+                .collect(),
+            max_urls: env_or("CONTENT_MAX_URLS", 3),
+            policy: env_or("CONTENT_FILTER_POLICY", FilterPolicy::Reject),
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        let limit = |capacity, refill_per_minute| BucketLimit {
+            capacity,
+            refill_per_minute,
+        };
+
+        Self {
+            enabled: env_or("RATE_LIMIT_ENABLED", true),
+            store: env_or("RATE_LIMIT_STORE", RateLimitStoreKind::Memory),
+            trust_proxy: env_or("RATE_LIMIT_TRUST_PROXY", false),
+            auth: env_or("RATE_LIMIT_AUTH", limit(10, 10)),
+            create: env_or("RATE_LIMIT_CREATE", limit(10, 5)),
+            vote: env_or("RATE_LIMIT_VOTE", limit(30, 60)),
+            results: env_or("RATE_LIMIT_RESULTS", limit(120, 120)),
+        }
+    }
+}
+
+impl Default for PowConfig {
+    fn default() -> Self {
+        Self {
+            secret: env::var("POW_SECRET").unwrap_or_default(),
+            base_difficulty: env_or("POW_BASE_DIFFICULTY", 16),
+            max_difficulty: env_or("POW_MAX_DIFFICULTY", 24),
+            votes_per_step: env_or("POW_VOTES_PER_STEP", 20),
+            challenge_ttl_secs: env_or("POW_CHALLENGE_TTL_SECS", 300),
+        }
+    }
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: env_or("WEBHOOK_MAX_ATTEMPTS", 8),
+            retry_base_secs: env_or("WEBHOOK_RETRY_BASE_SECS", 30),
+            retry_max_secs: env_or("WEBHOOK_RETRY_MAX_SECS", 6 * 60 * 60),
+            timeout_secs: env_or("WEBHOOK_TIMEOUT_SECS", 10),
+            poll_interval_secs: env_or("WEBHOOK_POLL_INTERVAL_SECS", 5),
+            max_per_user: env_or("WEBHOOK_MAX_PER_USER", 20),
+            allow_private_targets: env_or("WEBHOOK_ALLOW_PRIVATE_TARGETS", false),
+        }
+    }
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            signing_secret: env::var("CHAT_SIGNING_SECRET")
+                .ok()
+                .filter(|secret| !secret.trim().is_empty()),
+            max_request_age_secs: env_or("CHAT_MAX_REQUEST_AGE_SECS", 300),
         }
     }
 }
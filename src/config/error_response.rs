@@ -0,0 +1,81 @@
+use actix_web::{
+    error::{InternalError, JsonPayloadError, QueryPayloadError},
+    http::StatusCode,
+    HttpRequest, HttpResponse,
+};
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use utoipa::ToSchema;
+
+// Stable JSON envelope returned by every failing request.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorResponse {
+    pub code: String,
+    pub message: String,
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<Value>,
+}
+
+impl ErrorResponse {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        ErrorResponse {
+            code: code.to_string(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn into_response(self, status: StatusCode) -> HttpResponse {
+        HttpResponse::build(status).json(self)
+    }
+}
+
+// Server errors can carry database and driver details, so those are logged and the
+// client only gets a generic message.
+pub fn public_message(status: StatusCode, err: &impl fmt::Display) -> String {
+    if status.is_server_error() {
+        error!("Request failed: {}", err);
+        "Something went wrong, please try again.".to_string()
+    } else {
+        err.to_string()
+    }
+}
+
+// A single invalid field reported inside the details of a validation error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        FieldError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+pub fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let response =
+        ErrorResponse::new("invalid_body", err.to_string()).into_response(StatusCode::BAD_REQUEST);
+
+    InternalError::from_response(err, response).into()
+}
+
+pub fn query_error_handler(err: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let response =
+        ErrorResponse::new("invalid_query", err.to_string()).into_response(StatusCode::BAD_REQUEST);
+
+    InternalError::from_response(err, response).into()
+}
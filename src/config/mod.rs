@@ -1,3 +1,11 @@
+pub mod admin_config;
+pub mod chat_config;
+#[allow(clippy::module_inception)]
 pub mod config;
+pub mod error_response;
+pub mod organization_config;
 pub mod poll_config;
+pub mod session_config;
+pub mod survey_config;
 pub mod user_config;
+pub mod webhook_config;
@@ -0,0 +1,105 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use mongodb::error::Error as MongoError;
+use std::fmt;
+
+use super::error_response::{public_message, ErrorResponse, FieldError};
+
+#[derive(Debug)]
+pub enum OrganizationError {
+    MongoError(MongoError),
+    OrganizationNotFound(String),
+    MemberNotFound(String),
+    OrganizationCreationError(String),
+    OrganizationUpdateError(String),
+    OrganizationUnauthorizedAccess(String),
+    LastAdmin(String),
+    InvalidRequest(String),
+    GeneralError(String),
+    ValidationError(Vec<FieldError>),
+}
+
+impl OrganizationError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            OrganizationError::MongoError(_) => "database_error",
+            OrganizationError::OrganizationNotFound(_) => "organization_not_found",
+            OrganizationError::MemberNotFound(_) => "member_not_found",
+            OrganizationError::OrganizationCreationError(_) => "organization_creation_failed",
+            OrganizationError::OrganizationUpdateError(_) => "organization_update_failed",
+            OrganizationError::OrganizationUnauthorizedAccess(_) => "forbidden",
+            OrganizationError::LastAdmin(_) => "last_admin",
+            OrganizationError::InvalidRequest(_) => "invalid_request",
+            OrganizationError::GeneralError(_) => "internal_error",
+            OrganizationError::ValidationError(_) => "validation_failed",
+        }
+    }
+}
+
+impl fmt::Display for OrganizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrganizationError::MongoError(err) => write!(f, "MongoDB Error: {}", err),
+            OrganizationError::OrganizationNotFound(org_id) => {
+                write!(f, "Organization with ID '{}' not found", org_id)
+            }
+            OrganizationError::MemberNotFound(username) => {
+                write!(f, "'{}' is not a member of this organization", username)
+            }
+            OrganizationError::OrganizationCreationError(msg) => {
+                write!(f, "Organization creation error: {}", msg)
+            }
+            OrganizationError::OrganizationUpdateError(msg) => {
+                write!(f, "Organization update error: {}", msg)
+            }
+            OrganizationError::OrganizationUnauthorizedAccess(msg) => {
+                write!(f, "Unauthorized Access : {}", msg)
+            }
+            OrganizationError::LastAdmin(msg) => write!(f, "Conflict : {}", msg),
+            OrganizationError::InvalidRequest(msg) => write!(f, "Invalid request : {}", msg),
+            OrganizationError::GeneralError(msg) => write!(f, "Error: {}", msg),
+            OrganizationError::ValidationError(errors) => {
+                write!(f, "Validation failed for {} field(s)", errors.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrganizationError {}
+
+impl ResponseError for OrganizationError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            OrganizationError::OrganizationNotFound(_) | OrganizationError::MemberNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            OrganizationError::OrganizationUnauthorizedAccess(_) => StatusCode::FORBIDDEN,
+            OrganizationError::LastAdmin(_) => StatusCode::CONFLICT,
+            OrganizationError::InvalidRequest(_) | OrganizationError::ValidationError(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            OrganizationError::MongoError(_)
+            | OrganizationError::OrganizationCreationError(_)
+            | OrganizationError::OrganizationUpdateError(_)
+            | OrganizationError::GeneralError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let response = ErrorResponse::new(self.code(), public_message(self.status_code(), self));
+
+        let response = match self {
+            OrganizationError::ValidationError(errors) => {
+                response.with_details(serde_json::json!({ "fields": errors }))
+            }
+            _ => response,
+        };
+
+        response.into_response(self.status_code())
+    }
+}
+
+impl From<MongoError> for OrganizationError {
+    fn from(err: MongoError) -> Self {
+        OrganizationError::MongoError(err)
+    }
+}
@@ -1,10 +1,14 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
 use mongodb::error::Error as MongoError;
 use std::fmt;
 
+use super::error_response::{public_message, ErrorResponse, FieldError};
+
 #[derive(Debug)]
 pub enum PollError {
     MongoError(MongoError),
     PollNotFound(String),
+    UserNotFound(String),
     PollAlreadyExists(String),
     PollCreationError(String),
     PollVoteError(String),
@@ -13,6 +17,44 @@ pub enum PollError {
     PollDeletionError(String),
     AlreadyVotedError(String),
     PollUnauthorizedAccess(String),
+    VotedOptionConflict(String),
+    PollClosed(String),
+    ConcurrentModification(String),
+    InvalidRequest(String),
+    BadRequest(String),
+    PollHidden(String),
+    AlreadyReported(String),
+    TooManyReports(String),
+    ProofOfWorkFailed(String),
+    ValidationError(Vec<FieldError>),
+}
+
+impl PollError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PollError::MongoError(_) => "database_error",
+            PollError::PollNotFound(_) => "poll_not_found",
+            PollError::UserNotFound(_) => "user_not_found",
+            PollError::PollAlreadyExists(_) => "poll_already_exists",
+            PollError::PollCreationError(_) => "poll_creation_failed",
+            PollError::PollVoteError(_) => "invalid_vote",
+            PollError::PollUpdateError(_) => "poll_update_failed",
+            PollError::GeneralError(_) => "internal_error",
+            PollError::PollDeletionError(_) => "poll_deletion_failed",
+            PollError::AlreadyVotedError(_) => "already_voted",
+            PollError::PollUnauthorizedAccess(_) => "forbidden",
+            PollError::VotedOptionConflict(_) => "voted_option_conflict",
+            PollError::PollClosed(_) => "poll_closed",
+            PollError::ConcurrentModification(_) => "concurrent_modification",
+            PollError::InvalidRequest(_) => "invalid_request",
+            PollError::BadRequest(_) => "bad_request",
+            PollError::PollHidden(_) => "poll_hidden",
+            PollError::AlreadyReported(_) => "already_reported",
+            PollError::TooManyReports(_) => "too_many_reports",
+            PollError::ProofOfWorkFailed(_) => "proof_of_work_failed",
+            PollError::ValidationError(_) => "validation_failed",
+        }
+    }
 }
 
 impl fmt::Display for PollError {
@@ -20,6 +62,7 @@ impl fmt::Display for PollError {
         match self {
             PollError::MongoError(err) => write!(f, "MongoDB Error: {}", err),
             PollError::PollNotFound(poll_id) => write!(f, "Poll with ID '{}' not found", poll_id),
+            PollError::UserNotFound(username) => write!(f, "User '{}' not found", username),
             PollError::PollAlreadyExists(title) => {
                 write!(f, "Poll with title '{}' already exists", title)
             }
@@ -30,8 +73,65 @@ impl fmt::Display for PollError {
             PollError::PollDeletionError(msg) => write!(f, "Poll deletion error: {}", msg),
             PollError::AlreadyVotedError(msg) => write!(f, "Conflict : {}", msg),
             PollError::PollUnauthorizedAccess(msg) => write!(f, "Unauthorized Access : {}", msg),
+            PollError::VotedOptionConflict(msg) => write!(f, "Conflict : {}", msg),
+            PollError::PollClosed(msg) => write!(f, "Poll closed : {}", msg),
+            PollError::ConcurrentModification(msg) => write!(f, "Conflict : {}", msg),
+            PollError::InvalidRequest(msg) => write!(f, "Invalid request : {}", msg),
+            PollError::BadRequest(msg) => write!(f, "Bad request : {}", msg),
+            PollError::PollHidden(poll_id) => {
+                write!(f, "Poll with ID '{}' was hidden by moderators", poll_id)
+            }
+            PollError::AlreadyReported(msg) => write!(f, "Conflict : {}", msg),
+            PollError::TooManyReports(msg) => write!(f, "Too many reports : {}", msg),
+            PollError::ProofOfWorkFailed(msg) => write!(f, "Proof of work failed : {}", msg),
+            PollError::ValidationError(errors) => {
+                write!(f, "Validation failed for {} field(s)", errors.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PollError {}
+
+impl ResponseError for PollError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PollError::PollNotFound(_) | PollError::UserNotFound(_) => StatusCode::NOT_FOUND,
+            PollError::PollUnauthorizedAccess(_) | PollError::ProofOfWorkFailed(_) => {
+                StatusCode::FORBIDDEN
+            }
+            PollError::PollAlreadyExists(_)
+            | PollError::AlreadyVotedError(_)
+            | PollError::VotedOptionConflict(_)
+            | PollError::PollClosed(_)
+            | PollError::ConcurrentModification(_)
+            | PollError::AlreadyReported(_) => StatusCode::CONFLICT,
+            PollError::PollHidden(_) => StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+            PollError::TooManyReports(_) => StatusCode::TOO_MANY_REQUESTS,
+            PollError::PollVoteError(_)
+            | PollError::InvalidRequest(_)
+            | PollError::ValidationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            PollError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            PollError::MongoError(_)
+            | PollError::PollCreationError(_)
+            | PollError::PollUpdateError(_)
+            | PollError::GeneralError(_)
+            | PollError::PollDeletionError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        let response = ErrorResponse::new(self.code(), public_message(self.status_code(), self));
+
+        let response = match self {
+            PollError::ValidationError(errors) => {
+                response.with_details(serde_json::json!({ "fields": errors }))
+            }
+            _ => response,
+        };
+
+        response.into_response(self.status_code())
+    }
 }
 
 impl From<MongoError> for PollError {
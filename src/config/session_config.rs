@@ -0,0 +1,94 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use mongodb::error::Error as MongoError;
+use std::fmt;
+
+use super::error_response::{public_message, ErrorResponse, FieldError};
+
+#[derive(Debug)]
+pub enum SessionError {
+    MongoError(MongoError),
+    SessionNotFound(String),
+    SessionCreationError(String),
+    SessionUpdateError(String),
+    SessionUnauthorizedAccess(String),
+    SessionClosed(String),
+    InvalidRequest(String),
+    GeneralError(String),
+    ValidationError(Vec<FieldError>),
+}
+
+impl SessionError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SessionError::MongoError(_) => "database_error",
+            SessionError::SessionNotFound(_) => "session_not_found",
+            SessionError::SessionCreationError(_) => "session_creation_failed",
+            SessionError::SessionUpdateError(_) => "session_update_failed",
+            SessionError::SessionUnauthorizedAccess(_) => "forbidden",
+            SessionError::SessionClosed(_) => "session_closed",
+            SessionError::InvalidRequest(_) => "invalid_request",
+            SessionError::GeneralError(_) => "internal_error",
+            SessionError::ValidationError(_) => "validation_failed",
+        }
+    }
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::MongoError(err) => write!(f, "MongoDB Error: {}", err),
+            SessionError::SessionNotFound(session_id) => {
+                write!(f, "Session with ID '{}' not found", session_id)
+            }
+            SessionError::SessionCreationError(msg) => write!(f, "Session creation error: {}", msg),
+            SessionError::SessionUpdateError(msg) => write!(f, "Session update error: {}", msg),
+            SessionError::SessionUnauthorizedAccess(msg) => {
+                write!(f, "Unauthorized Access : {}", msg)
+            }
+            SessionError::SessionClosed(msg) => write!(f, "Session closed : {}", msg),
+            SessionError::InvalidRequest(msg) => write!(f, "Invalid request : {}", msg),
+            SessionError::GeneralError(msg) => write!(f, "Error: {}", msg),
+            SessionError::ValidationError(errors) => {
+                write!(f, "Validation failed for {} field(s)", errors.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl ResponseError for SessionError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SessionError::SessionNotFound(_) => StatusCode::NOT_FOUND,
+            SessionError::SessionUnauthorizedAccess(_) => StatusCode::FORBIDDEN,
+            SessionError::SessionClosed(_) => StatusCode::CONFLICT,
+            SessionError::InvalidRequest(_) | SessionError::ValidationError(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            SessionError::MongoError(_)
+            | SessionError::SessionCreationError(_)
+            | SessionError::SessionUpdateError(_)
+            | SessionError::GeneralError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let response = ErrorResponse::new(self.code(), public_message(self.status_code(), self));
+
+        let response = match self {
+            SessionError::ValidationError(errors) => {
+                response.with_details(serde_json::json!({ "fields": errors }))
+            }
+            _ => response,
+        };
+
+        response.into_response(self.status_code())
+    }
+}
+
+impl From<MongoError> for SessionError {
+    fn from(err: MongoError) -> Self {
+        SessionError::MongoError(err)
+    }
+}
@@ -0,0 +1,92 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use mongodb::error::Error as MongoError;
+use std::fmt;
+
+use super::error_response::{public_message, ErrorResponse, FieldError};
+
+#[derive(Debug)]
+pub enum SurveyError {
+    MongoError(MongoError),
+    SurveyNotFound(String),
+    SurveyCreationError(String),
+    SurveyUpdateError(String),
+    SurveyUnauthorizedAccess(String),
+    SurveyClosed(String),
+    AlreadyResponded(String),
+    GeneralError(String),
+    ValidationError(Vec<FieldError>),
+}
+
+impl SurveyError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SurveyError::MongoError(_) => "database_error",
+            SurveyError::SurveyNotFound(_) => "survey_not_found",
+            SurveyError::SurveyCreationError(_) => "survey_creation_failed",
+            SurveyError::SurveyUpdateError(_) => "survey_update_failed",
+            SurveyError::SurveyUnauthorizedAccess(_) => "forbidden",
+            SurveyError::SurveyClosed(_) => "survey_closed",
+            SurveyError::AlreadyResponded(_) => "already_responded",
+            SurveyError::GeneralError(_) => "internal_error",
+            SurveyError::ValidationError(_) => "validation_failed",
+        }
+    }
+}
+
+impl fmt::Display for SurveyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SurveyError::MongoError(err) => write!(f, "MongoDB Error: {}", err),
+            SurveyError::SurveyNotFound(survey_id) => {
+                write!(f, "Survey with ID '{}' not found", survey_id)
+            }
+            SurveyError::SurveyCreationError(msg) => write!(f, "Survey creation error: {}", msg),
+            SurveyError::SurveyUpdateError(msg) => write!(f, "Survey update error: {}", msg),
+            SurveyError::SurveyUnauthorizedAccess(msg) => {
+                write!(f, "Unauthorized Access : {}", msg)
+            }
+            SurveyError::SurveyClosed(msg) => write!(f, "Survey closed : {}", msg),
+            SurveyError::AlreadyResponded(msg) => write!(f, "Conflict : {}", msg),
+            SurveyError::GeneralError(msg) => write!(f, "Error: {}", msg),
+            SurveyError::ValidationError(errors) => {
+                write!(f, "Validation failed for {} field(s)", errors.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SurveyError {}
+
+impl ResponseError for SurveyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SurveyError::SurveyNotFound(_) => StatusCode::NOT_FOUND,
+            SurveyError::SurveyUnauthorizedAccess(_) => StatusCode::FORBIDDEN,
+            SurveyError::SurveyClosed(_) | SurveyError::AlreadyResponded(_) => StatusCode::CONFLICT,
+            SurveyError::ValidationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            SurveyError::MongoError(_)
+            | SurveyError::SurveyCreationError(_)
+            | SurveyError::SurveyUpdateError(_)
+            | SurveyError::GeneralError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let response = ErrorResponse::new(self.code(), public_message(self.status_code(), self));
+
+        let response = match self {
+            SurveyError::ValidationError(errors) => {
+                response.with_details(serde_json::json!({ "fields": errors }))
+            }
+            _ => response,
+        };
+
+        response.into_response(self.status_code())
+    }
+}
+
+impl From<MongoError> for SurveyError {
+    fn from(err: MongoError) -> Self {
+        SurveyError::MongoError(err)
+    }
+}
@@ -1,6 +1,9 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
 use mongodb::error::Error as MongoError;
 use std::fmt;
 
+use super::error_response::{public_message, ErrorResponse};
+
 #[derive(Debug)]
 pub enum Error {
     MongoError(MongoError),
@@ -9,6 +12,25 @@ pub enum Error {
     RegistrationStateError(String),
     LoginStateError(String),
     GeneralError(String),
+    Unauthorized(String),
+    AuthenticationFailed(String),
+    AccountDisabled(String),
+}
+
+impl Error {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::MongoError(_) => "database_error",
+            Error::UserNotFound(_) => "user_not_found",
+            Error::UserAlreadyExists(_) => "user_already_exists",
+            Error::RegistrationStateError(_) => "registration_state_error",
+            Error::LoginStateError(_) => "login_state_error",
+            Error::GeneralError(_) => "internal_error",
+            Error::Unauthorized(_) => "unauthorized",
+            Error::AuthenticationFailed(_) => "authentication_failed",
+            Error::AccountDisabled(_) => "account_disabled",
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -20,8 +42,34 @@ impl fmt::Display for Error {
             Error::RegistrationStateError(msg) => write!(f, "Registration state error: {}", msg),
             Error::LoginStateError(msg) => write!(f, "Login state error: {}", msg),
             Error::GeneralError(msg) => write!(f, "Error: {}", msg),
+            Error::Unauthorized(msg) => write!(f, "Unauthorized : {}", msg),
+            Error::AuthenticationFailed(msg) => write!(f, "Authentication failed : {}", msg),
+            Error::AccountDisabled(username) => write!(f, "Account '{}' is disabled", username),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::UserNotFound(_) => StatusCode::NOT_FOUND,
+            Error::UserAlreadyExists(_) => StatusCode::CONFLICT,
+            Error::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Error::AuthenticationFailed(_) => StatusCode::BAD_REQUEST,
+            Error::AccountDisabled(_) => StatusCode::FORBIDDEN,
+            Error::MongoError(_)
+            | Error::RegistrationStateError(_)
+            | Error::LoginStateError(_)
+            | Error::GeneralError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        ErrorResponse::new(self.code(), public_message(self.status_code(), self))
+            .into_response(self.status_code())
+    }
 }
 
 impl From<MongoError> for Error {
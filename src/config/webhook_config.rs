@@ -0,0 +1,95 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use mongodb::error::Error as MongoError;
+use std::fmt;
+
+use super::error_response::{public_message, ErrorResponse, FieldError};
+
+#[derive(Debug)]
+pub enum WebhookError {
+    MongoError(MongoError),
+    WebhookNotFound(String),
+    DeliveryNotFound(String),
+    PollNotFound(String),
+    WebhookUnauthorizedAccess(String),
+    InvalidRequest(String),
+    GeneralError(String),
+    ValidationError(Vec<FieldError>),
+}
+
+impl WebhookError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            WebhookError::MongoError(_) => "database_error",
+            WebhookError::WebhookNotFound(_) => "webhook_not_found",
+            WebhookError::DeliveryNotFound(_) => "delivery_not_found",
+            WebhookError::PollNotFound(_) => "poll_not_found",
+            WebhookError::WebhookUnauthorizedAccess(_) => "forbidden",
+            WebhookError::InvalidRequest(_) => "invalid_request",
+            WebhookError::GeneralError(_) => "internal_error",
+            WebhookError::ValidationError(_) => "validation_failed",
+        }
+    }
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebhookError::MongoError(err) => write!(f, "MongoDB Error: {}", err),
+            WebhookError::WebhookNotFound(webhook_id) => {
+                write!(f, "Webhook with ID '{}' not found", webhook_id)
+            }
+            WebhookError::DeliveryNotFound(delivery_id) => {
+                write!(f, "Delivery with ID '{}' not found", delivery_id)
+            }
+            WebhookError::PollNotFound(poll_id) => {
+                write!(f, "Poll with ID '{}' not found", poll_id)
+            }
+            WebhookError::WebhookUnauthorizedAccess(msg) => {
+                write!(f, "Unauthorized Access : {}", msg)
+            }
+            WebhookError::InvalidRequest(msg) => write!(f, "Invalid request : {}", msg),
+            WebhookError::GeneralError(msg) => write!(f, "Error: {}", msg),
+            WebhookError::ValidationError(errors) => {
+                write!(f, "Validation failed for {} field(s)", errors.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+impl ResponseError for WebhookError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            WebhookError::WebhookNotFound(_)
+            | WebhookError::DeliveryNotFound(_)
+            | WebhookError::PollNotFound(_) => StatusCode::NOT_FOUND,
+            WebhookError::WebhookUnauthorizedAccess(_) => StatusCode::FORBIDDEN,
+            WebhookError::InvalidRequest(_) | WebhookError::ValidationError(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            WebhookError::MongoError(_) | WebhookError::GeneralError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let response = ErrorResponse::new(self.code(), public_message(self.status_code(), self));
+
+        let response = match self {
+            WebhookError::ValidationError(errors) => {
+                response.with_details(serde_json::json!({ "fields": errors }))
+            }
+            _ => response,
+        };
+
+        response.into_response(self.status_code())
+    }
+}
+
+impl From<MongoError> for WebhookError {
+    fn from(err: MongoError) -> Self {
+        WebhookError::MongoError(err)
+    }
+}
@@ -0,0 +1,73 @@
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::{Collection, IndexModel};
+
+use crate::config::admin_config::AdminError;
+use crate::models::audit_model::AuditEntry;
+
+pub struct AuditRepository {
+    audit_collection: Collection<AuditEntry>,
+}
+
+impl AuditRepository {
+    pub fn init(audit_collection: Collection<AuditEntry>) -> Result<Self, AdminError> {
+        Ok(AuditRepository { audit_collection })
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), AdminError> {
+        let created_index = IndexModel::builder()
+            .keys(doc! { "createdAt": -1 })
+            .options(
+                IndexOptions::builder()
+                    .name("audit_created_at".to_string())
+                    .build(),
+            )
+            .build();
+
+        self.audit_collection
+            .create_index(created_index, None)
+            .await
+            .map_err(AdminError::MongoError)?;
+
+        Ok(())
+    }
+
+    pub async fn record(&self, entry: &AuditEntry) -> Result<(), AdminError> {
+        self.audit_collection
+            .insert_one(entry, None)
+            .await
+            .map_err(|e| AdminError::AuditLogError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    // Newest entries first.
+    pub async fn get_entries(
+        &self,
+        skip: u64,
+        limit: i64,
+    ) -> Result<(u64, Vec<AuditEntry>), AdminError> {
+        let total = self
+            .audit_collection
+            .count_documents(None, None)
+            .await
+            .map_err(AdminError::MongoError)?;
+
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .skip(skip)
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .audit_collection
+            .find(None, options)
+            .await
+            .map_err(AdminError::MongoError)?;
+
+        let entries = cursor.try_collect().await.map_err(AdminError::MongoError)?;
+
+        Ok((total, entries))
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::{doc, to_bson, Bson};
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
+use serde::{Deserialize, Serialize};
+
+use crate::config::poll_config::PollError;
+
+use super::poll_repository::is_duplicate_key;
+
+// A proof-of-work challenge that has been used for a vote. Kept until the challenge
+// expires so it cannot be replayed, and counted to measure the recent vote rate.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpentChallenge {
+    pub challenge_hash: String,
+    pub poll_id: String,
+    pub spent_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+fn challenge_bson(value: &DateTime<Utc>) -> Result<Bson, PollError> {
+    to_bson(value).map_err(|e| PollError::GeneralError(e.to_string()))
+}
+
+pub struct ChallengeRepository {
+    challenge_collection: Collection<SpentChallenge>,
+}
+
+impl ChallengeRepository {
+    pub fn init(challenge_collection: Collection<SpentChallenge>) -> Result<Self, PollError> {
+        Ok(ChallengeRepository {
+            challenge_collection,
+        })
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), PollError> {
+        let hash_index = IndexModel::builder()
+            .keys(doc! { "challengeHash": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("challenge_hash".to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+
+        let rate_index = IndexModel::builder()
+            .keys(doc! { "pollId": 1, "spentAt": -1 })
+            .options(
+                IndexOptions::builder()
+                    .name("challenge_rate".to_string())
+                    .build(),
+            )
+            .build();
+
+        let expiry_index = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("challenge_expiry".to_string())
+                    .expire_after(std::time::Duration::from_secs(0))
+                    .build(),
+            )
+            .build();
+
+        self.challenge_collection
+            .create_indexes(vec![hash_index, rate_index, expiry_index], None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        Ok(())
+    }
+
+    // Returns false when the challenge was already spent.
+    pub async fn spend(&self, spent: &SpentChallenge) -> Result<bool, PollError> {
+        match self.challenge_collection.insert_one(spent, None).await {
+            Ok(_) => Ok(true),
+            Err(err) if is_duplicate_key(&err) => Ok(false),
+            Err(err) => Err(PollError::MongoError(err)),
+        }
+    }
+
+    pub async fn count_spent_since(
+        &self,
+        poll_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<u64, PollError> {
+        let filter = doc! {
+            "pollId": poll_id,
+            "spentAt": { "$gte": challenge_bson(&since)? }
+        };
+
+        self.challenge_collection
+            .count_documents(filter, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
+}
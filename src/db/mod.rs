@@ -1,3 +1,11 @@
+pub mod audit_repository;
+pub mod challenge_repository;
 pub mod mongodb_repository;
+pub mod organization_repository;
 pub mod poll_repository;
+pub mod rate_limit_repository;
+pub mod report_repository;
+pub mod session_repository;
+pub mod survey_repository;
 pub mod user_repository;
+pub mod webhook_repository;
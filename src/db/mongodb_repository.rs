@@ -3,25 +3,53 @@ use std::error::Error;
 use dotenv::dotenv;
 use mongodb::Client;
 
-use super::{poll_repository::PollRepository, user_repository::UserRepository};
+use super::{
+    audit_repository::AuditRepository, challenge_repository::ChallengeRepository,
+    organization_repository::OrganizationRepository, poll_repository::PollRepository,
+    rate_limit_repository::RateLimitRepository, report_repository::ReportRepository,
+    session_repository::SessionRepository, survey_repository::SurveyRepository,
+    user_repository::UserRepository, webhook_repository::WebhookRepository,
+};
 
 pub struct MongoDB {
     pub user_repository: UserRepository,
+    pub organization_repository: OrganizationRepository,
     pub poll_repository: PollRepository,
+    pub survey_repository: SurveyRepository,
+    pub session_repository: SessionRepository,
+    pub audit_repository: AuditRepository,
+    pub report_repository: ReportRepository,
+    pub rate_limit_repository: RateLimitRepository,
+    pub challenge_repository: ChallengeRepository,
+    pub webhook_repository: WebhookRepository,
 }
 
 impl MongoDB {
     pub async fn init(mongo_uri: &str, database_name: &str) -> Result<Self, Box<dyn Error>> {
         dotenv().ok();
 
-        let client = Client::with_uri_str(mongo_uri)
-            .await
-            .map_err(|e| Box::new(e))?;
+        let client = Client::with_uri_str(mongo_uri).await.map_err(Box::new)?;
 
         let database = client.database(database_name);
 
         let user_collection = database.collection("user");
+        let organization_collection = database.collection("organization");
         let poll_collection = database.collection("poll");
+        let poll_revision_collection = database.collection("revision");
+        let poll_invite_collection = database.collection("invite");
+        let participation_collection = database.collection("participation");
+        let text_answer_collection = database.collection("textanswer");
+        let quiz_score_collection = database.collection("quizscore");
+        let guest_ballot_collection = database.collection("guestballot");
+        let survey_collection = database.collection("survey");
+        let survey_response_collection = database.collection("surveyresponse");
+        let session_collection = database.collection("session");
+        let audit_collection = database.collection("audit");
+        let report_collection = database.collection("report");
+        let rate_limit_collection = database.collection("ratelimit");
+        let challenge_collection = database.collection("powchallenge");
+        let webhook_collection = database.collection("webhook");
+        let webhook_delivery_collection = database.collection("webhookdelivery");
         let user_reg_state_collection = database.collection("regstate");
         let user_login_state_collection = database.collection("loginstate");
 
@@ -32,11 +60,63 @@ impl MongoDB {
         )
         .unwrap();
 
-        let poll_repository = PollRepository::init(poll_collection).unwrap();
+        let organization_repository =
+            OrganizationRepository::init(organization_collection).unwrap();
+
+        organization_repository.create_indexes().await?;
+
+        let poll_repository = PollRepository::init(
+            poll_collection,
+            poll_revision_collection,
+            poll_invite_collection,
+            participation_collection,
+            text_answer_collection,
+            quiz_score_collection,
+            guest_ballot_collection,
+        )
+        .unwrap();
+
+        poll_repository.create_indexes().await?;
+
+        let survey_repository =
+            SurveyRepository::init(survey_collection, survey_response_collection).unwrap();
+
+        let session_repository = SessionRepository::init(session_collection).unwrap();
+
+        session_repository.create_indexes().await?;
+
+        let audit_repository = AuditRepository::init(audit_collection).unwrap();
+
+        audit_repository.create_indexes().await?;
+
+        let report_repository = ReportRepository::init(report_collection).unwrap();
+
+        report_repository.create_indexes().await?;
+
+        let rate_limit_repository = RateLimitRepository::init(rate_limit_collection).unwrap();
+
+        rate_limit_repository.create_indexes().await?;
+
+        let challenge_repository = ChallengeRepository::init(challenge_collection).unwrap();
+
+        challenge_repository.create_indexes().await?;
+
+        let webhook_repository =
+            WebhookRepository::init(webhook_collection, webhook_delivery_collection).unwrap();
+
+        webhook_repository.create_indexes().await?;
 
         Ok(MongoDB {
             user_repository,
+            organization_repository,
             poll_repository,
+            survey_repository,
+            session_repository,
+            audit_repository,
+            report_repository,
+            rate_limit_repository,
+            challenge_repository,
+            webhook_repository,
         })
     }
 }
@@ -0,0 +1,202 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, to_bson, Bson, Document};
+use mongodb::options::IndexOptions;
+use mongodb::results::InsertOneResult;
+use mongodb::{Collection, IndexModel};
+use serde::Serialize;
+
+use crate::config::organization_config::OrganizationError;
+use crate::models::organization_model::{Organization, OrganizationMember, OrganizationRole};
+
+fn update_bson<T: Serialize>(value: &T) -> Result<Bson, OrganizationError> {
+    to_bson(value).map_err(|e| OrganizationError::OrganizationUpdateError(e.to_string()))
+}
+
+pub struct OrganizationRepository {
+    organization_collection: Collection<Organization>,
+}
+impl OrganizationRepository {
+    pub fn init(
+        organization_collection: Collection<Organization>,
+    ) -> Result<Self, OrganizationError> {
+        Ok(OrganizationRepository {
+            organization_collection,
+        })
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), OrganizationError> {
+        let member_index = IndexModel::builder()
+            .keys(doc! { "members.username": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("organization_members".to_string())
+                    .build(),
+            )
+            .build();
+
+        self.organization_collection
+            .create_index(member_index, None)
+            .await
+            .map_err(OrganizationError::MongoError)?;
+
+        Ok(())
+    }
+
+    // Matches organizations where someone other than `username` is an admin, so changes
+    // to that user never leave an organization without admins.
+    fn other_admin_filter(username: &str) -> Document {
+        doc! {
+            "members": {
+                "$elemMatch": {
+                    "role": "admin",
+                    "username": { "$ne": username }
+                }
+            }
+        }
+    }
+
+    pub async fn create_organization(
+        &self,
+        organization: &Organization,
+    ) -> Result<InsertOneResult, OrganizationError> {
+        self.organization_collection
+            .insert_one(organization, None)
+            .await
+            .map_err(|e| OrganizationError::OrganizationCreationError(e.to_string()))
+    }
+
+    pub async fn get_organization_by_id(
+        &self,
+        org_id: &str,
+    ) -> Result<Option<Organization>, OrganizationError> {
+        self.organization_collection
+            .find_one(doc! { "orgId": org_id }, None)
+            .await
+            .map_err(OrganizationError::MongoError)
+    }
+
+    pub async fn get_organizations_for_user(
+        &self,
+        username: &str,
+    ) -> Result<Vec<Organization>, OrganizationError> {
+        let cursor = self
+            .organization_collection
+            .find(doc! { "members.username": username }, None)
+            .await
+            .map_err(OrganizationError::MongoError)?;
+
+        cursor
+            .try_collect()
+            .await
+            .map_err(OrganizationError::MongoError)
+    }
+
+    pub async fn get_organization_ids_for_user(
+        &self,
+        username: &str,
+    ) -> Result<Vec<String>, OrganizationError> {
+        Ok(self
+            .get_organizations_for_user(username)
+            .await?
+            .into_iter()
+            .map(|organization| organization.org_id)
+            .collect())
+    }
+
+    pub async fn get_member_role(
+        &self,
+        org_id: &str,
+        username: &str,
+    ) -> Result<Option<OrganizationRole>, OrganizationError> {
+        Ok(self
+            .get_organization_by_id(org_id)
+            .await?
+            .and_then(|organization| organization.role_of(username)))
+    }
+
+    // Adds the member, or gives them the new role if they already belong to the
+    // organization. Demoting the last admin is refused.
+    pub async fn set_member(
+        &self,
+        org_id: &str,
+        member: &OrganizationMember,
+    ) -> Result<(), OrganizationError> {
+        let mut filter = doc! { "orgId": org_id };
+        if member.role != OrganizationRole::Admin {
+            filter.extend(Self::other_admin_filter(&member.username));
+        }
+
+        let pipeline = vec![doc! {
+            "$set": {
+                "members": {
+                    "$concatArrays": [
+                        {
+                            "$filter": {
+                                "input": "$members",
+                                "cond": {
+                                    "$ne": ["$$this.username", { "$literal": &member.username }]
+                                }
+                            }
+                        },
+                        { "$literal": [update_bson(member)?] }
+                    ]
+                },
+                "updatedAt": update_bson(&Utc::now())?
+            }
+        }];
+
+        let update_result = self
+            .organization_collection
+            .update_one(filter, pipeline, None)
+            .await
+            .map_err(|e| OrganizationError::OrganizationUpdateError(e.to_string()))?;
+
+        if update_result.matched_count == 0 {
+            return Err(self.membership_conflict(org_id).await);
+        }
+
+        Ok(())
+    }
+
+    pub async fn remove_member(
+        &self,
+        org_id: &str,
+        username: &str,
+    ) -> Result<(), OrganizationError> {
+        let mut filter = doc! { "orgId": org_id, "members.username": username };
+        filter.extend(Self::other_admin_filter(username));
+
+        let update = doc! {
+            "$pull": { "members": { "username": username } },
+            "$set": { "updatedAt": update_bson(&Utc::now())? }
+        };
+
+        let update_result = self
+            .organization_collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|e| OrganizationError::OrganizationUpdateError(e.to_string()))?;
+
+        if update_result.matched_count == 0 {
+            return match self.get_member_role(org_id, username).await? {
+                None => Err(OrganizationError::MemberNotFound(username.to_string())),
+                Some(_) => Err(self.membership_conflict(org_id).await),
+            };
+        }
+
+        Ok(())
+    }
+
+    // Tells why a membership update matched nothing: the organization is gone, or the
+    // change would have removed its last admin.
+    async fn membership_conflict(&self, org_id: &str) -> OrganizationError {
+        match self.get_organization_by_id(org_id).await {
+            Ok(Some(_)) => OrganizationError::LastAdmin(
+                "An organization needs at least one admin.".to_string(),
+            ),
+            Ok(None) => OrganizationError::OrganizationNotFound(org_id.to_string()),
+            Err(err) => err,
+        }
+    }
+}
@@ -1,18 +1,101 @@
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
-use mongodb::bson::doc;
-use mongodb::options::UpdateOptions;
+use mongodb::bson::{doc, from_document, to_bson, Bson, Document};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::{
+    FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument, UpdateOptions,
+};
 use mongodb::results::{DeleteResult, InsertOneResult, UpdateResult};
-use mongodb::Collection;
+use mongodb::{Collection, IndexModel};
+use serde::Serialize;
 
 use crate::config::poll_config::PollError;
-use crate::models::poll_model::Poll;
+use crate::models::poll_model::{
+    AvailabilityBallot, Collaborator, GuestBallot, InviteKind, OptionItem, ParticipationMarker,
+    Poll, PollInvite, PollRevision, PollRole, QuizScore, QuizSettings, TextAnswer,
+};
+use crate::utils::poll_edit_utility::PollEditOutcome;
+use crate::utils::write_in_utility::option_text_pattern;
+
+fn update_bson<T: Serialize>(value: &T) -> Result<Bson, PollError> {
+    to_bson(value).map_err(|e| PollError::PollUpdateError(e.to_string()))
+}
+
+pub(crate) fn is_duplicate_key(err: &mongodb::error::Error) -> bool {
+    matches!(
+        err.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(write_error)) if write_error.code == 11000
+    )
+}
+
+fn guest_ballot_id(poll_id: &str, guest_id: &str) -> String {
+    format!("{}:{}", poll_id, guest_id)
+}
 
 pub struct PollRepository {
     poll_collection: Collection<Poll>,
+    revision_collection: Collection<PollRevision>,
+    invite_collection: Collection<PollInvite>,
+    participation_collection: Collection<ParticipationMarker>,
+    text_answer_collection: Collection<TextAnswer>,
+    quiz_score_collection: Collection<QuizScore>,
+    guest_ballot_collection: Collection<GuestBallot>,
 }
 impl PollRepository {
-    pub fn init(poll_collection: Collection<Poll>) -> Result<Self, PollError> {
-        Ok(PollRepository { poll_collection })
+    pub fn init(
+        poll_collection: Collection<Poll>,
+        revision_collection: Collection<PollRevision>,
+        invite_collection: Collection<PollInvite>,
+        participation_collection: Collection<ParticipationMarker>,
+        text_answer_collection: Collection<TextAnswer>,
+        quiz_score_collection: Collection<QuizScore>,
+        guest_ballot_collection: Collection<GuestBallot>,
+    ) -> Result<Self, PollError> {
+        Ok(PollRepository {
+            poll_collection,
+            revision_collection,
+            invite_collection,
+            participation_collection,
+            text_answer_collection,
+            quiz_score_collection,
+            guest_ballot_collection,
+        })
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), PollError> {
+        let text_index = IndexModel::builder()
+            .keys(doc! {
+                "title": "text",
+                "description": "text",
+                "options.text": "text"
+            })
+            .options(
+                IndexOptions::builder()
+                    .name("poll_text_search".to_string())
+                    .weights(doc! { "title": 10, "options.text": 5, "description": 3 })
+                    .build(),
+            )
+            .build();
+
+        self.poll_collection
+            .create_index(text_index, None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        Ok(())
+    }
+
+    // Polls that have been soft deleted are hidden from every listing and lookup.
+    fn active_filter() -> Document {
+        doc! { "deletedAt": null }
+    }
+
+    // Unlisted and private polls are reachable by id but never listed or searched.
+    fn listing_filter() -> Document {
+        let mut filter = Self::active_filter();
+        filter.insert("visibility", doc! { "$in": ["public", null] });
+        filter.insert("hidden", doc! { "$ne": true });
+        filter
     }
 
     pub async fn create_poll(&self, poll: &Poll) -> Result<InsertOneResult, PollError> {
@@ -23,25 +106,103 @@ impl PollRepository {
     }
 
     pub async fn get_poll_by_id(&self, poll_id: &str) -> Result<Option<Poll>, PollError> {
-        let filter = doc! {"pollId" : poll_id};
+        let mut filter = doc! {"pollId" : poll_id};
+        filter.extend(Self::active_filter());
 
         self.poll_collection
             .find_one(filter, None)
             .await
-            .map_err(|e| PollError::MongoError(e))
+            .map_err(PollError::MongoError)
+    }
+
+    pub async fn get_all_polls(&self) -> Result<Vec<Poll>, PollError> {
+        let cursor = self
+            .poll_collection
+            .find(Self::listing_filter(), None)
+            .await
+            .map_err(PollError::from)?;
+
+        cursor.try_collect().await.map_err(PollError::from)
     }
 
-    pub async fn get_all_polls(&self) -> Result<Vec<Poll>, mongodb::error::Error> {
+    pub async fn get_polls_by_organization(&self, org_id: &str) -> Result<Vec<Poll>, PollError> {
+        let mut filter = doc! { "orgId": org_id };
+        filter.extend(Self::active_filter());
+
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .build();
+
         let cursor = self
             .poll_collection
-            .find(None, None)
+            .find(filter, options)
+            .await
+            .map_err(PollError::from)?;
+
+        cursor.try_collect().await.map_err(PollError::from)
+    }
+
+    pub async fn search_polls(
+        &self,
+        query: &str,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(u64, Vec<(Poll, f64)>), PollError> {
+        let mut text_filter = doc! { "$text": { "$search": query } };
+        text_filter.extend(Self::listing_filter());
+
+        let pipeline = vec![
+            doc! { "$match": text_filter },
+            doc! { "$addFields": { "score": { "$meta": "textScore" } } },
+            doc! { "$sort": { "score": -1, "createdAt": -1 } },
+            doc! {
+                "$facet": {
+                    "total": [{ "$count": "count" }],
+                    "results": [{ "$skip": skip as i64 }, { "$limit": limit as i64 }]
+                }
+            },
+        ];
+
+        let mut cursor = self
+            .poll_collection
+            .aggregate(pipeline, None)
             .await
-            .map_err(|e| PollError::from(e))
-            .unwrap();
+            .map_err(PollError::MongoError)?;
+
+        let facet = match cursor.try_next().await.map_err(PollError::MongoError)? {
+            Some(facet) => facet,
+            None => return Ok((0, vec![])),
+        };
+
+        let total = facet
+            .get_array("total")
+            .ok()
+            .and_then(|total| total.first())
+            .and_then(|count| count.as_document())
+            .and_then(|count| count.get_i32("count").ok())
+            .unwrap_or(0) as u64;
+
+        let mut results = Vec::new();
+
+        for result in facet
+            .get_array("results")
+            .map_err(|err| PollError::GeneralError(format!("Malformed search results: {}", err)))?
+        {
+            let document: Document = match result.as_document() {
+                Some(document) => document.clone(),
+                None => continue,
+            };
+
+            let score = document.get_f64("score").unwrap_or(0.0);
+
+            let poll: Poll = from_document(document).map_err(|err| {
+                PollError::GeneralError(format!("Failed to decode poll: {}", err))
+            })?;
 
-        let polls: Result<Vec<Poll>, mongodb::error::Error> = cursor.try_collect().await;
+            results.push((poll, score));
+        }
 
-        polls
+        Ok((total, results))
     }
 
     pub async fn check_user_vote_in_poll(
@@ -65,15 +226,13 @@ impl PollRepository {
                     .any(|vote_history| vote_history.username == username);
 
                 if user_voted {
-                    return Ok(true);
+                    Ok(true)
                 } else {
-                    return Ok(false);
+                    Ok(false)
                 }
             }
-            None => {
-                return Err(PollError::GeneralError("Poll not found".to_string()));
-            }
-        };
+            None => Err(PollError::PollNotFound(poll_id.to_string())),
+        }
     }
 
     pub async fn cast_vote_to_poll_by_id(
@@ -96,7 +255,14 @@ impl PollRepository {
 
         let array_filters = vec![doc! { "option.optionId": option_id }];
 
-        let filter = doc! { "pollId": poll_id };
+        // The poll may have closed, or the user voted from another request, since it was
+        // read; the filter only matches while the ballot is still allowed.
+        let filter = doc! {
+            "pollId": poll_id,
+            "isActive": true,
+            "options.optionId": option_id,
+            "voters.username": { "$ne": username },
+        };
 
         let update_result = self
             .poll_collection
@@ -112,16 +278,472 @@ impl PollRepository {
             .await
             .map_err(|err| PollError::GeneralError(format!("Failed to cast vote: {}", err)))?;
 
+        if update_result.matched_count == 0 {
+            return Err(PollError::ConcurrentModification(
+                "Poll was modified while casting the vote, please try again.".to_string(),
+            ));
+        }
+
         Ok(update_result)
     }
 
+    // Anonymous ballots only bump the option counter. The participation marker is stored
+    // first, in a separate collection, and its unique id rejects a second ballot.
+    pub async fn cast_anonymous_vote_to_poll_by_id(
+        &self,
+        poll_id: &str,
+        option_id: &str,
+        marker: &str,
+    ) -> Result<UpdateResult, PollError> {
+        self.record_participation(poll_id, marker).await?;
+
+        let update_poll = doc! { "$inc": { "options.$[option].votes": 1 } };
+        let array_filters = vec![doc! { "option.optionId": option_id }];
+        // The poll may have closed since it was read, the marker is then removed again.
+        let filter = doc! {
+            "pollId": poll_id,
+            "isActive": true,
+            "options.optionId": option_id,
+        };
+
+        let update_result = self
+            .poll_collection
+            .update_one(
+                filter,
+                update_poll,
+                Some(
+                    UpdateOptions::builder()
+                        .array_filters(Some(array_filters))
+                        .build(),
+                ),
+            )
+            .await;
+
+        match update_result {
+            Ok(update_result) if update_result.matched_count > 0 => Ok(update_result),
+            Ok(_) => {
+                self.delete_participation(marker).await?;
+                Err(PollError::ConcurrentModification(
+                    "Poll was modified while casting the vote, please try again.".to_string(),
+                ))
+            }
+            Err(err) => {
+                self.delete_participation(marker).await?;
+                Err(PollError::GeneralError(format!(
+                    "Failed to cast vote: {}",
+                    err
+                )))
+            }
+        }
+    }
+
+    async fn record_participation(&self, poll_id: &str, marker: &str) -> Result<(), PollError> {
+        let participation = ParticipationMarker {
+            marker: marker.to_string(),
+            poll_id: poll_id.to_string(),
+        };
+
+        match self
+            .participation_collection
+            .insert_one(&participation, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) if is_duplicate_key(&err) => Err(PollError::AlreadyVotedError(
+                "Already voted in this anonymous poll, votes cannot be changed.".to_string(),
+            )),
+            Err(err) => Err(PollError::GeneralError(format!(
+                "Failed to record participation: {}",
+                err
+            ))),
+        }
+    }
+
+    pub async fn has_participated(&self, marker: &str) -> Result<bool, PollError> {
+        let marker = self
+            .participation_collection
+            .find_one(doc! { "_id": marker }, None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        Ok(marker.is_some())
+    }
+
+    async fn delete_participation(&self, marker: &str) -> Result<DeleteResult, PollError> {
+        self.participation_collection
+            .delete_one(doc! { "_id": marker }, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
+
+    // Guest ballots count towards the option like any other vote and are also tallied in
+    // `guestVotes`. The ballot record is stored first so a guest can only vote once.
+    pub async fn cast_guest_vote_to_poll_by_id(
+        &self,
+        poll_id: &str,
+        option_id: &str,
+        guest_id: &str,
+    ) -> Result<UpdateResult, PollError> {
+        let ballot = GuestBallot {
+            ballot_id: guest_ballot_id(poll_id, guest_id),
+            poll_id: poll_id.to_string(),
+            cast_at: Utc::now(),
+        };
+
+        match self.guest_ballot_collection.insert_one(&ballot, None).await {
+            Ok(_) => {}
+            Err(err) if is_duplicate_key(&err) => {
+                return Err(PollError::AlreadyVotedError(
+                    "Guests can vote only once and cannot change their vote.".to_string(),
+                ))
+            }
+            Err(err) => {
+                return Err(PollError::GeneralError(format!(
+                    "Failed to record guest ballot: {}",
+                    err
+                )))
+            }
+        }
+
+        let update_poll = doc! {
+            "$inc": {
+                "options.$[option].votes": 1,
+                "options.$[option].guestVotes": 1
+            }
+        };
+        let array_filters = vec![doc! { "option.optionId": option_id }];
+        // The poll may have closed since it was read, the ballot is then removed again.
+        let filter = doc! {
+            "pollId": poll_id,
+            "isActive": true,
+            "options.optionId": option_id,
+        };
+
+        let update_result = self
+            .poll_collection
+            .update_one(
+                filter,
+                update_poll,
+                Some(
+                    UpdateOptions::builder()
+                        .array_filters(Some(array_filters))
+                        .build(),
+                ),
+            )
+            .await;
+
+        match update_result {
+            Ok(update_result) if update_result.matched_count > 0 => Ok(update_result),
+            Ok(_) => {
+                self.delete_guest_ballot(&ballot.ballot_id).await?;
+                Err(PollError::ConcurrentModification(
+                    "Poll was modified while casting the vote, please try again.".to_string(),
+                ))
+            }
+            Err(err) => {
+                self.delete_guest_ballot(&ballot.ballot_id).await?;
+                Err(PollError::GeneralError(format!(
+                    "Failed to cast vote: {}",
+                    err
+                )))
+            }
+        }
+    }
+
+    pub async fn has_guest_voted(&self, poll_id: &str, guest_id: &str) -> Result<bool, PollError> {
+        let ballot = self
+            .guest_ballot_collection
+            .find_one(doc! { "_id": guest_ballot_id(poll_id, guest_id) }, None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        Ok(ballot.is_some())
+    }
+
+    async fn delete_guest_ballot(&self, ballot_id: &str) -> Result<DeleteResult, PollError> {
+        self.guest_ballot_collection
+            .delete_one(doc! { "_id": ballot_id }, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
+
+    // Stores an answer to a free-text poll. The author is recorded in `voters`, with the
+    // answer id in place of an option id, in the same update that checks they have not
+    // answered yet; anonymous polls use a participation marker instead and keep no name.
+    pub async fn submit_text_answer(
+        &self,
+        answer: &TextAnswer,
+        username: &str,
+        marker: Option<&str>,
+    ) -> Result<(), PollError> {
+        match marker {
+            Some(marker) => self.record_participation(&answer.poll_id, marker).await?,
+            None => {
+                let filter = doc! {
+                    "pollId": &answer.poll_id,
+                    "isActive": true,
+                    "voters.username": { "$ne": username }
+                };
+                let update = doc! {
+                    "$push": {
+                        "voters": { "username": username, "optionId": &answer.answer_id }
+                    },
+                    "$set": { "updatedAt": update_bson(&Utc::now())? }
+                };
+
+                let update_result = self
+                    .poll_collection
+                    .update_one(filter, update, None)
+                    .await
+                    .map_err(|err| {
+                        PollError::GeneralError(format!("Failed to submit answer: {}", err))
+                    })?;
+
+                if update_result.matched_count == 0 {
+                    return Err(PollError::AlreadyVotedError(
+                        "Already answered this poll.".to_string(),
+                    ));
+                }
+            }
+        }
+
+        self.text_answer_collection
+            .insert_one(answer, None)
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to submit answer: {}", err)))?;
+
+        Ok(())
+    }
+
+    // Pinned answers come first, then the newest. Returns the total alongside the page.
+    pub async fn get_text_answers(
+        &self,
+        poll_id: &str,
+        include_hidden: bool,
+        skip: u64,
+        limit: i64,
+    ) -> Result<(u64, Vec<TextAnswer>), PollError> {
+        let mut filter = doc! { "pollId": poll_id };
+        if !include_hidden {
+            filter.insert("hidden", doc! { "$ne": true });
+        }
+
+        let total = self
+            .text_answer_collection
+            .count_documents(filter.clone(), None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        let options = FindOptions::builder()
+            .sort(doc! { "pinned": -1, "createdAt": -1 })
+            .skip(skip)
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .text_answer_collection
+            .find(filter, options)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        let answers = cursor.try_collect().await.map_err(PollError::MongoError)?;
+
+        Ok((total, answers))
+    }
+
+    pub async fn get_visible_text_answer_texts(
+        &self,
+        poll_id: &str,
+    ) -> Result<Vec<String>, PollError> {
+        let cursor = self
+            .text_answer_collection
+            .find(doc! { "pollId": poll_id, "hidden": { "$ne": true } }, None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        let answers: Vec<TextAnswer> = cursor.try_collect().await.map_err(PollError::MongoError)?;
+
+        Ok(answers.into_iter().map(|answer| answer.text).collect())
+    }
+
+    pub async fn update_text_answer_moderation(
+        &self,
+        poll_id: &str,
+        answer_id: &str,
+        hidden: Option<bool>,
+        pinned: Option<bool>,
+    ) -> Result<Option<TextAnswer>, PollError> {
+        let mut set = Document::new();
+        if let Some(hidden) = hidden {
+            set.insert("hidden", hidden);
+        }
+        if let Some(pinned) = pinned {
+            set.insert("pinned", pinned);
+        }
+
+        self.text_answer_collection
+            .find_one_and_update(
+                doc! { "pollId": poll_id, "answerId": answer_id },
+                doc! { "$set": set },
+                Some(
+                    FindOneAndUpdateOptions::builder()
+                        .return_document(ReturnDocument::After)
+                        .build(),
+                ),
+            )
+            .await
+            .map_err(|err| PollError::PollUpdateError(err.to_string()))
+    }
+
+    // Quiz answers are final: the vote is only counted if the question is still open and
+    // the player has not answered it yet, and the score is stored once it was counted.
+    pub async fn cast_quiz_answer(&self, score: &QuizScore) -> Result<(), PollError> {
+        let filter = doc! {
+            "pollId": &score.poll_id,
+            "isActive": true,
+            "options.optionId": &score.option_id,
+            "voters.username": { "$ne": &score.username }
+        };
+        let update = doc! {
+            "$inc": { "options.$[option].votes": 1 },
+            "$push": {
+                "voters": { "username": &score.username, "optionId": &score.option_id }
+            }
+        };
+        let array_filters = vec![doc! { "option.optionId": &score.option_id }];
+
+        let update_result = self
+            .poll_collection
+            .update_one(
+                filter,
+                update,
+                Some(
+                    UpdateOptions::builder()
+                        .array_filters(Some(array_filters))
+                        .build(),
+                ),
+            )
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to cast vote: {}", err)))?;
+
+        if update_result.matched_count == 0 {
+            return Err(PollError::AlreadyVotedError(
+                "Already answered this question, quiz answers cannot be changed.".to_string(),
+            ));
+        }
+
+        self.quiz_score_collection
+            .insert_one(score, None)
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to store score: {}", err)))?;
+
+        Ok(())
+    }
+
+    // Replaces the participant's previous ballot, if any, in a single pipeline update so
+    // concurrent submissions never leave two ballots for the same person.
+    pub async fn submit_availability(
+        &self,
+        poll_id: &str,
+        ballot: &AvailabilityBallot,
+    ) -> Result<(), PollError> {
+        // On polls with final votes only a first ballot is accepted.
+        let filter = doc! {
+            "pollId": poll_id,
+            "isActive": true,
+            "$or": [
+                { "lockVotes": { "$ne": true } },
+                { "availability.username": { "$ne": &ballot.username } },
+            ],
+        };
+        let pipeline = vec![doc! {
+            "$set": {
+                "availability": {
+                    "$concatArrays": [
+                        {
+                            "$filter": {
+                                "input": { "$ifNull": ["$availability", []] },
+                                "cond": {
+                                    "$ne": ["$$this.username", { "$literal": &ballot.username }]
+                                }
+                            }
+                        },
+                        { "$literal": [update_bson(ballot)?] }
+                    ]
+                },
+                "updatedAt": update_bson(&ballot.updated_at)?
+            }
+        }];
+
+        let update_result = self
+            .poll_collection
+            .update_one(filter, pipeline, None)
+            .await
+            .map_err(|err| {
+                PollError::GeneralError(format!("Failed to submit availability: {}", err))
+            })?;
+
+        if update_result.matched_count == 0 {
+            return Err(PollError::PollClosed(
+                "Cannot answer a closed poll".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn choose_slot(
+        &self,
+        poll_id: &str,
+        option_id: &str,
+    ) -> Result<UpdateResult, PollError> {
+        self.poll_collection
+            .update_one(
+                doc! { "pollId": poll_id },
+                doc! {
+                    "$set": {
+                        "chosenOptionId": option_id,
+                        "updatedAt": update_bson(&Utc::now())?
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|err| PollError::PollUpdateError(err.to_string()))
+    }
+
+    pub async fn get_quiz_scores(&self, quiz_id: &str) -> Result<Vec<QuizScore>, PollError> {
+        let cursor = self
+            .quiz_score_collection
+            .find(doc! { "quizId": quiz_id }, None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        cursor.try_collect().await.map_err(PollError::MongoError)
+    }
+
+    pub async fn get_quiz_questions(&self, quiz_id: &str) -> Result<Vec<Poll>, PollError> {
+        let mut filter = doc! { "quiz.quizId": quiz_id };
+        filter.extend(Self::active_filter());
+
+        let options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = self
+            .poll_collection
+            .find(filter, options)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        cursor.try_collect().await.map_err(PollError::MongoError)
+    }
+
     pub async fn change_vote_in_poll_by_id(
         &self,
         poll_id: &str,
         new_option_id: &str,
         username: &str,
     ) -> Result<UpdateResult, PollError> {
-        let poll = self.get_poll_by_id(poll_id).await.unwrap();
+        let poll = self.get_poll_by_id(poll_id).await?;
 
         if let Some(poll) = poll {
             let previous_vote = poll
@@ -155,7 +777,17 @@ impl PollRepository {
                     doc! { "elem.username": username },
                 ];
 
-                let filter = doc! { "pollId": poll_id };
+                // Pins the ballot that was read, so a concurrent change cannot move the
+                // same vote twice, and refuses closed polls and final votes.
+                let filter = doc! {
+                    "pollId": poll_id,
+                    "isActive": true,
+                    "lockVotes": { "$ne": true },
+                    "options.optionId": new_option_id,
+                    "voters": {
+                        "$elemMatch": { "username": username, "optionId": previous_option_id }
+                    },
+                };
 
                 let update_result = self
                     .poll_collection
@@ -173,15 +805,148 @@ impl PollRepository {
                         PollError::GeneralError(format!("Failed to change vote: {}", err))
                     })?;
 
-                return Ok(update_result);
+                if update_result.matched_count == 0 {
+                    return Err(PollError::ConcurrentModification(
+                        "Poll was modified while changing the vote, please try again.".to_string(),
+                    ));
+                }
+
+                return Ok(update_result);
             }
         }
 
-        Err(PollError::GeneralError(
-            "Poll not found or user did not vote".to_string(),
+        Err(PollError::PollVoteError(
+            "User has not voted in this poll.".to_string(),
         ))
     }
 
+    // Removes the voter's ballot and gives back the vote on the option it counted for.
+    // The filter pins the ballot that was read, so a concurrent change or retraction
+    // cannot decrement the wrong counter twice.
+    pub async fn retract_vote_in_poll_by_id(
+        &self,
+        poll_id: &str,
+        username: &str,
+    ) -> Result<UpdateResult, PollError> {
+        let poll = self
+            .get_poll_by_id(poll_id)
+            .await?
+            .ok_or_else(|| PollError::PollNotFound(poll_id.to_string()))?;
+
+        let option_id = poll
+            .voters
+            .iter()
+            .find(|vote_history| vote_history.username == username)
+            .map(|vote_history| vote_history.option_id.clone())
+            .ok_or_else(|| {
+                PollError::InvalidRequest("You have not voted in this poll.".to_string())
+            })?;
+
+        let filter = doc! {
+            "pollId": poll_id,
+            "isActive": true,
+            "lockVotes": { "$ne": true },
+            "voters": { "$elemMatch": { "username": username, "optionId": &option_id } },
+        };
+        let update_poll = doc! {
+            "$inc": { "options.$[option].votes": -1 },
+            "$pull": { "voters": { "username": username } },
+            "$set": { "updatedAt": update_bson(&Utc::now())? }
+        };
+        let array_filters = vec![doc! { "option.optionId": &option_id }];
+
+        let update_result = self
+            .poll_collection
+            .update_one(
+                filter,
+                update_poll,
+                Some(
+                    UpdateOptions::builder()
+                        .array_filters(Some(array_filters))
+                        .build(),
+                ),
+            )
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to retract vote: {}", err)))?;
+
+        if update_result.matched_count == 0 {
+            return Err(PollError::ConcurrentModification(
+                "Poll was modified while retracting the vote, please try again.".to_string(),
+            ));
+        }
+
+        Ok(update_result)
+    }
+
+    pub async fn retract_availability(
+        &self,
+        poll_id: &str,
+        username: &str,
+    ) -> Result<(), PollError> {
+        let filter = doc! {
+            "pollId": poll_id,
+            "isActive": true,
+            "lockVotes": { "$ne": true },
+            "availability.username": username,
+        };
+        let update = doc! {
+            "$pull": { "availability": { "username": username } },
+            "$set": { "updatedAt": update_bson(&Utc::now())? }
+        };
+
+        let update_result = self
+            .poll_collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|err| {
+                PollError::GeneralError(format!("Failed to retract availability: {}", err))
+            })?;
+
+        if update_result.matched_count == 0 {
+            return Err(PollError::InvalidRequest(
+                "You have not answered this poll.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Appends a write-in unless an option with the same text (ignoring case) already
+    // exists or the poll is full. Returns whether the option was added.
+    pub async fn add_write_in_option(
+        &self,
+        poll_id: &str,
+        option: &OptionItem,
+        max_options: usize,
+    ) -> Result<bool, PollError> {
+        let mut filter = doc! {
+            "pollId": poll_id,
+            "allowWriteIns": true,
+            "options": {
+                "$not": {
+                    "$elemMatch": {
+                        "text": { "$regex": option_text_pattern(&option.text), "$options": "i" }
+                    }
+                }
+            },
+        };
+        filter.insert(
+            format!("options.{}", max_options.saturating_sub(1)),
+            doc! { "$exists": false },
+        );
+        filter.extend(Self::active_filter());
+
+        let update = doc! { "$push": { "options": update_bson(option)? } };
+
+        let update_result = self
+            .poll_collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        Ok(update_result.modified_count > 0)
+    }
+
     pub async fn remove_poll_by_id(&self, poll_id: &str) -> Result<DeleteResult, PollError> {
         let query = doc! {"pollId":poll_id};
         self.poll_collection
@@ -190,84 +955,548 @@ impl PollRepository {
             .map_err(|e| PollError::PollDeletionError(e.to_string()))
     }
 
-    pub async fn close_poll_by_id(
+    // Writes the edited contents only if the options and voters are still exactly what
+    // the edit was computed from, so concurrent votes are never silently overwritten.
+    pub async fn update_poll_contents(
         &self,
-        poll_id: &str,
-        username: &str,
+        poll: &Poll,
+        outcome: &PollEditOutcome,
     ) -> Result<UpdateResult, PollError> {
-        if self
-            .check_user_ownership_on_poll(poll_id, username)
+        let query = doc! {
+            "pollId": &poll.poll_id,
+            "deletedAt": null,
+            "options": update_bson(&poll.options)?,
+            "voters": update_bson(&poll.voters)?,
+        };
+
+        let update = doc! {
+            "$set": {
+                "title": &outcome.title,
+                "description": &outcome.description,
+                "visibility": update_bson(&outcome.visibility)?,
+                "resultsVisibility": update_bson(&outcome.results_visibility)?,
+                "lockVotes": outcome.lock_votes,
+                "options": update_bson(&outcome.options)?,
+                "voters": update_bson(&outcome.voters)?,
+                "updatedAt": update_bson(&Utc::now())?,
+            }
+        };
+
+        let update_result = self
+            .poll_collection
+            .update_one(query, update, None)
             .await
-            .unwrap()
-        {
-            let query = doc! { "pollId": poll_id };
-            let update = doc! {
-                "$set": {
-                    "isActive": false
-                }
-            };
-            self.poll_collection
-                .update_one(query, update, None)
-                .await
-                .map_err(|e| PollError::PollUpdateError(e.to_string()))
-        } else {
-            return Err(PollError::PollUnauthorizedAccess(
-                "Poll can be deleted only by the creator.".to_string(),
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        if update_result.matched_count == 0 {
+            return Err(PollError::ConcurrentModification(
+                "Poll was modified while editing, please try again.".to_string(),
             ));
         }
+
+        if outcome.clear_participation {
+            self.participation_collection
+                .delete_many(doc! { "pollId": &poll.poll_id }, None)
+                .await
+                .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+        }
+
+        if outcome.clear_guest_ballots {
+            self.guest_ballot_collection
+                .delete_many(doc! { "pollId": &poll.poll_id }, None)
+                .await
+                .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+        }
+
+        Ok(update_result)
     }
 
-    pub async fn reset_poll_by_id(
+    pub async fn insert_revision(
+        &self,
+        revision: &PollRevision,
+    ) -> Result<InsertOneResult, PollError> {
+        self.revision_collection
+            .insert_one(revision, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
+
+    pub async fn get_revisions_by_poll_id(
+        &self,
+        poll_id: &str,
+    ) -> Result<Vec<PollRevision>, PollError> {
+        let options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = self
+            .revision_collection
+            .find(doc! { "pollId": poll_id }, options)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        cursor.try_collect().await.map_err(PollError::MongoError)
+    }
+
+    pub async fn add_participant(
         &self,
         poll_id: &str,
         username: &str,
     ) -> Result<UpdateResult, PollError> {
-        if self
-            .check_user_ownership_on_poll(poll_id, username)
+        let query = doc! { "pollId": poll_id, "deletedAt": null };
+        let update = doc! { "$addToSet": { "participants": username } };
+
+        self.poll_collection
+            .update_one(query, update, None)
             .await
-            .unwrap()
-        {
-            let filter = doc! {"pollId":poll_id};
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))
+    }
 
-            let update = doc! {
-                "$set":{
-                    "options.$[].votes": 0,
-                    "voters":[]
-                }
-            };
+    pub async fn create_invite(&self, invite: &PollInvite) -> Result<InsertOneResult, PollError> {
+        self.invite_collection
+            .insert_one(invite, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
 
-            let update_result = self
-                .poll_collection
-                .update_one(filter, update, None)
-                .await
-                .map_err(|err| {
-                    PollError::GeneralError(format!("Failed to reset votes: {}", err))
-                })?;
-
-            Ok(update_result)
-        } else {
-            return Err(PollError::PollUnauthorizedAccess(
-                "Only the creator can reset the votes.".to_string(),
-            ));
-        }
+    pub async fn get_invites_by_poll_id(
+        &self,
+        poll_id: &str,
+    ) -> Result<Vec<PollInvite>, PollError> {
+        let options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = self
+            .invite_collection
+            .find(doc! { "pollId": poll_id }, options)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        cursor.try_collect().await.map_err(PollError::MongoError)
     }
 
-    async fn check_user_ownership_on_poll(
+    pub async fn delete_invite(
         &self,
         poll_id: &str,
+        secret: &str,
+    ) -> Result<DeleteResult, PollError> {
+        self.invite_collection
+            .delete_one(doc! { "pollId": poll_id, "secret": secret }, None)
+            .await
+            .map_err(|e| PollError::PollDeletionError(e.to_string()))
+    }
+
+    // A poll has at most one access code, so issuing a new one revokes the previous.
+    pub async fn replace_access_code(&self, invite: &PollInvite) -> Result<(), PollError> {
+        self.delete_access_code(&invite.poll_id).await?;
+        self.create_invite(invite).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_access_code(&self, poll_id: &str) -> Result<DeleteResult, PollError> {
+        let kind = update_bson(&InviteKind::AccessCode)?;
+
+        self.invite_collection
+            .delete_many(doc! { "pollId": poll_id, "kind": kind }, None)
+            .await
+            .map_err(|e| PollError::PollDeletionError(e.to_string()))
+    }
+
+    // Looks up an invite token or access code that has not expired yet.
+    pub async fn find_valid_invite(
+        &self,
+        poll_id: &str,
+        secret: &str,
+        kind: InviteKind,
+    ) -> Result<Option<PollInvite>, PollError> {
+        let filter = doc! {
+            "pollId": poll_id,
+            "secret": secret,
+            "kind": update_bson(&kind)?,
+            "$or": [
+                { "expiresAt": null },
+                { "expiresAt": { "$gt": update_bson(&Utc::now())? } }
+            ]
+        };
+
+        self.invite_collection
+            .find_one(filter, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
+
+    // Links the polls to a live session. Only polls of the presenter that are not part of
+    // another session are linked; returns how many were.
+    pub async fn attach_polls_to_session(
+        &self,
+        poll_ids: &[String],
         username: &str,
-    ) -> Result<bool, PollError> {
-        let poll = match self.get_poll_by_id(poll_id).await.unwrap() {
+        session_id: &str,
+    ) -> Result<u64, PollError> {
+        let mut filter = doc! {
+            "pollId": { "$in": poll_ids },
+            "username": username,
+            "sessionId": null
+        };
+        filter.extend(Self::active_filter());
+
+        let update_result = self
+            .poll_collection
+            .update_many(filter, doc! { "$set": { "sessionId": session_id } }, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        // Quiz questions of the session open once the presenter reaches them.
+        self.poll_collection
+            .update_many(
+                doc! {
+                    "pollId": { "$in": poll_ids },
+                    "sessionId": session_id,
+                    "quiz": { "$ne": null }
+                },
+                doc! { "$set": { "quiz.openedAt": null, "quiz.closesAt": null } },
+                None,
+            )
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        Ok(update_result.modified_count)
+    }
+
+    // Starts the clock of a quiz question that has not been opened yet; a question the
+    // presenter comes back to keeps its first opening.
+    pub async fn open_quiz_question(
+        &self,
+        poll_id: &str,
+        quiz: &QuizSettings,
+    ) -> Result<(), PollError> {
+        let filter = doc! {
+            "pollId": poll_id,
+            "quiz": { "$ne": null },
+            "quiz.openedAt": null
+        };
+        let update = doc! {
+            "$set": {
+                "quiz.openedAt": update_bson(&quiz.opened_at)?,
+                "quiz.closesAt": update_bson(&quiz.closes_at)?
+            }
+        };
+
+        self.poll_collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn get_deleted_poll_by_id(&self, poll_id: &str) -> Result<Option<Poll>, PollError> {
+        let filter = doc! { "pollId": poll_id, "deletedAt": { "$ne": null } };
+
+        self.poll_collection
+            .find_one(filter, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
+
+    // Access is checked by the caller, since organization admins manage polls they have
+    // no role on.
+    pub async fn soft_delete_poll_by_id(&self, poll_id: &str) -> Result<UpdateResult, PollError> {
+        let now = to_bson(&Utc::now()).map_err(|e| PollError::PollDeletionError(e.to_string()))?;
+
+        let query = doc! { "pollId": poll_id, "deletedAt": null };
+        let update = doc! {
+            "$set": {
+                "deletedAt": &now,
+                "updatedAt": &now
+            }
+        };
+
+        self.poll_collection
+            .update_one(query, update, None)
+            .await
+            .map_err(|e| PollError::PollDeletionError(e.to_string()))
+    }
+
+    pub async fn restore_poll_by_id(
+        &self,
+        poll_id: &str,
+        deleted_after: DateTime<Utc>,
+    ) -> Result<UpdateResult, PollError> {
+        let poll = match self.get_deleted_poll_by_id(poll_id).await? {
             Some(poll) => poll,
             None => {
-                return Err(PollError::PollNotFound("Poll not found".to_string()));
+                return Err(PollError::PollNotFound(poll_id.to_string()));
+            }
+        };
+
+        match poll.deleted_at {
+            Some(deleted_at) if deleted_at >= deleted_after => {}
+            _ => {
+                return Err(PollError::PollNotFound(poll_id.to_string()));
+            }
+        }
+
+        let now = update_bson(&Utc::now())?;
+
+        let query = doc! { "pollId": poll_id, "deletedAt": { "$ne": null } };
+        let update = doc! {
+            "$set": {
+                "deletedAt": null,
+                "updatedAt": now
+            }
+        };
+
+        self.poll_collection
+            .update_one(query, update, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))
+    }
+
+    // Polls soft deleted before `deleted_before`, which are due to be purged.
+    pub async fn get_expired_poll_ids(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<Vec<String>, PollError> {
+        let cutoff =
+            to_bson(&deleted_before).map_err(|e| PollError::PollDeletionError(e.to_string()))?;
+
+        let query = doc! { "deletedAt": { "$ne": null, "$lt": cutoff } };
+
+        let poll_ids = self
+            .poll_collection
+            .distinct("pollId", query, None)
+            .await
+            .map_err(|e| PollError::PollDeletionError(e.to_string()))?;
+
+        Ok(poll_ids
+            .into_iter()
+            .filter_map(|poll_id| poll_id.as_str().map(str::to_string))
+            .collect())
+    }
+
+    // Permanently removes the polls together with what is stored alongside them in the
+    // poll collections. The polls themselves go last, so a purge that fails halfway is
+    // completed by the next pass instead of leaving orphans behind.
+    pub async fn purge_polls(&self, poll_ids: &[String]) -> Result<DeleteResult, PollError> {
+        let query = doc! { "pollId": { "$in": poll_ids } };
+
+        let map_err = |e: mongodb::error::Error| PollError::PollDeletionError(e.to_string());
+        self.revision_collection
+            .delete_many(query.clone(), None)
+            .await
+            .map_err(map_err)?;
+        self.invite_collection
+            .delete_many(query.clone(), None)
+            .await
+            .map_err(map_err)?;
+        self.participation_collection
+            .delete_many(query.clone(), None)
+            .await
+            .map_err(map_err)?;
+        self.text_answer_collection
+            .delete_many(query.clone(), None)
+            .await
+            .map_err(map_err)?;
+        self.quiz_score_collection
+            .delete_many(query.clone(), None)
+            .await
+            .map_err(map_err)?;
+        self.guest_ballot_collection
+            .delete_many(query.clone(), None)
+            .await
+            .map_err(map_err)?;
+
+        self.poll_collection
+            .delete_many(query, None)
+            .await
+            .map_err(map_err)
+    }
+
+    pub async fn set_poll_hidden(&self, poll_id: &str, hidden: bool) -> Result<(), PollError> {
+        let now = to_bson(&Utc::now()).map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        self.poll_collection
+            .update_one(
+                doc! { "pollId": poll_id },
+                doc! { "$set": { "hidden": hidden, "updatedAt": now } },
+                None,
+            )
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn close_poll_by_id(&self, poll_id: &str) -> Result<UpdateResult, PollError> {
+        let query = doc! { "pollId": poll_id };
+        let update = doc! {
+            "$set": {
+                "isActive": false
+            }
+        };
+        self.poll_collection
+            .update_one(query, update, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))
+    }
+
+    pub async fn reset_poll_by_id(&self, poll_id: &str) -> Result<UpdateResult, PollError> {
+        let filter = doc! {"pollId":poll_id};
+
+        let update = doc! {
+            "$set":{
+                "options.$[].votes": 0,
+                "options.$[].guestVotes": 0,
+                "voters":[],
+                "availability":[]
+            }
+        };
+
+        let update_result = self
+            .poll_collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to reset votes: {}", err)))?;
+
+        self.participation_collection
+            .delete_many(doc! { "pollId": poll_id }, None)
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to reset votes: {}", err)))?;
+
+        self.guest_ballot_collection
+            .delete_many(doc! { "pollId": poll_id }, None)
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to reset votes: {}", err)))?;
+
+        self.text_answer_collection
+            .delete_many(doc! { "pollId": poll_id }, None)
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to reset votes: {}", err)))?;
+
+        self.quiz_score_collection
+            .delete_many(doc! { "pollId": poll_id }, None)
+            .await
+            .map_err(|err| PollError::GeneralError(format!("Failed to reset votes: {}", err)))?;
+
+        Ok(update_result)
+    }
+
+    // Adds the collaborator, or gives them the new role if they already collaborate. The
+    // owner is never listed as a collaborator of their own poll.
+    pub async fn set_collaborator(
+        &self,
+        poll_id: &str,
+        collaborator: &Collaborator,
+    ) -> Result<(), PollError> {
+        let filter = doc! {
+            "pollId": poll_id,
+            "deletedAt": null,
+            "username": { "$ne": &collaborator.username },
+        };
+        let pipeline = vec![doc! {
+            "$set": {
+                "collaborators": {
+                    "$concatArrays": [
+                        {
+                            "$filter": {
+                                "input": { "$ifNull": ["$collaborators", []] },
+                                "cond": {
+                                    "$ne": ["$$this.username", { "$literal": &collaborator.username }]
+                                }
+                            }
+                        },
+                        { "$literal": [update_bson(collaborator)?] }
+                    ]
+                },
+                "updatedAt": update_bson(&collaborator.added_at)?
             }
+        }];
+
+        let update_result = self
+            .poll_collection
+            .update_one(filter, pipeline, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        if update_result.matched_count == 0 {
+            return Err(PollError::ConcurrentModification(
+                "Poll was modified while updating collaborators, please try again.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Returns whether the user was a collaborator of the poll.
+    pub async fn remove_collaborator(
+        &self,
+        poll_id: &str,
+        username: &str,
+    ) -> Result<bool, PollError> {
+        let filter = doc! { "pollId": poll_id, "deletedAt": null };
+        let update = doc! {
+            "$pull": { "collaborators": { "username": username } },
+            "$set": { "updatedAt": update_bson(&Utc::now())? }
         };
 
-        if poll.username == username {
-            Ok(true)
-        } else {
-            Ok(false)
+        let update_result = self
+            .poll_collection
+            .update_one(filter, update, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        Ok(update_result.modified_count > 0)
+    }
+
+    // Hands the poll to the new owner. The previous owner stays on as a co-owner and the
+    // new owner's collaborator entry, if any, is dropped. The filter on the current owner
+    // makes concurrent transfers fail instead of both succeeding.
+    pub async fn transfer_poll_ownership(
+        &self,
+        poll_id: &str,
+        owner: &str,
+        new_owner: &str,
+    ) -> Result<(), PollError> {
+        let previous_owner = Collaborator {
+            username: owner.to_string(),
+            role: PollRole::Owner,
+            added_at: Utc::now(),
+        };
+
+        let filter = doc! { "pollId": poll_id, "deletedAt": null, "username": owner };
+        let pipeline = vec![doc! {
+            "$set": {
+                "username": { "$literal": new_owner },
+                "collaborators": {
+                    "$concatArrays": [
+                        {
+                            "$filter": {
+                                "input": { "$ifNull": ["$collaborators", []] },
+                                "cond": {
+                                    "$not": [{
+                                        "$in": ["$$this.username", { "$literal": [owner, new_owner] }]
+                                    }]
+                                }
+                            }
+                        },
+                        { "$literal": [update_bson(&previous_owner)?] }
+                    ]
+                },
+                "updatedAt": update_bson(&previous_owner.added_at)?
+            }
+        }];
+
+        let update_result = self
+            .poll_collection
+            .update_one(filter, pipeline, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        if update_result.matched_count == 0 {
+            return Err(PollError::ConcurrentModification(
+                "Poll ownership changed meanwhile, please try again.".to_string(),
+            ));
         }
+
+        Ok(())
     }
 }
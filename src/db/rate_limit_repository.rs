@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use mongodb::bson::doc;
+use mongodb::options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+use serde::{Deserialize, Serialize};
+
+use crate::config::config::BucketLimit;
+use crate::utils::rate_limit_utility::{retry_after_secs, RateDecision, RateLimitStore};
+
+// Buckets untouched for a day are removed by MongoDB, they would be full again anyway.
+const BUCKET_EXPIRY_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitBucket {
+    pub key: String,
+    pub tokens: f64,
+    pub allowed: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Shared token buckets for deployments running several instances. Each request refills
+// and takes from its bucket in a single update, using the database clock so instances
+// with skewed clocks agree.
+#[derive(Clone)]
+pub struct RateLimitRepository {
+    bucket_collection: Collection<RateLimitBucket>,
+}
+
+impl RateLimitRepository {
+    pub fn init(bucket_collection: Collection<RateLimitBucket>) -> Result<Self, String> {
+        Ok(RateLimitRepository { bucket_collection })
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), mongodb::error::Error> {
+        let key_index = IndexModel::builder()
+            .keys(doc! { "key": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("rate_limit_key".to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+
+        let expiry_index = IndexModel::builder()
+            .keys(doc! { "updatedAt": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("rate_limit_expiry".to_string())
+                    .expire_after(std::time::Duration::from_secs(BUCKET_EXPIRY_SECS))
+                    .build(),
+            )
+            .build();
+
+        self.bucket_collection
+            .create_indexes(vec![key_index, expiry_index], None)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn take_token(
+        &self,
+        key: &str,
+        limit: &BucketLimit,
+    ) -> Result<RateDecision, mongodb::error::Error> {
+        let capacity = limit.capacity as f64;
+        let tokens_per_ms = limit.refill_per_minute as f64 / 60_000.0;
+
+        let pipeline = vec![
+            doc! {
+                "$set": {
+                    "tokens": {
+                        "$min": [
+                            capacity,
+                            {
+                                "$add": [
+                                    { "$ifNull": ["$tokens", capacity] },
+                                    {
+                                        "$multiply": [
+                                            {
+                                                "$subtract": [
+                                                    "$$NOW",
+                                                    { "$ifNull": ["$updatedAt", "$$NOW"] }
+                                                ]
+                                            },
+                                            tokens_per_ms
+                                        ]
+                                    }
+                                ]
+                            }
+                        ]
+                    },
+                    "updatedAt": "$$NOW"
+                }
+            },
+            doc! { "$set": { "allowed": { "$gte": ["$tokens", 1.0] } } },
+            doc! {
+                "$set": {
+                    "tokens": {
+                        "$cond": ["$allowed", { "$subtract": ["$tokens", 1.0] }, "$tokens"]
+                    }
+                }
+            },
+        ];
+
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let bucket = self
+            .bucket_collection
+            .find_one_and_update(doc! { "key": key }, pipeline, options)
+            .await?;
+
+        Ok(match bucket {
+            Some(bucket) if !bucket.allowed => RateDecision::Limited {
+                retry_after_secs: retry_after_secs(bucket.tokens, limit),
+            },
+            _ => RateDecision::Allowed,
+        })
+    }
+}
+
+impl RateLimitStore for RateLimitRepository {
+    fn take<'a>(
+        &'a self,
+        key: &'a str,
+        limit: &'a BucketLimit,
+    ) -> BoxFuture<'a, Result<RateDecision, String>> {
+        Box::pin(async move {
+            self.take_token(key, limit)
+                .await
+                .map_err(|err| err.to_string())
+        })
+    }
+}
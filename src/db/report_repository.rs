@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::{doc, to_bson, Bson, Document};
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::{Collection, IndexModel};
+use serde::Serialize;
+
+use crate::config::poll_config::PollError;
+use crate::models::report_model::{Report, ReportStatus, ReportTransition};
+
+fn report_bson<T: Serialize>(value: &T) -> Result<Bson, PollError> {
+    to_bson(value).map_err(|e| PollError::PollUpdateError(e.to_string()))
+}
+
+pub struct ReportRepository {
+    report_collection: Collection<Report>,
+}
+
+impl ReportRepository {
+    pub fn init(report_collection: Collection<Report>) -> Result<Self, PollError> {
+        Ok(ReportRepository { report_collection })
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), PollError> {
+        let queue_index = IndexModel::builder()
+            .keys(doc! { "status": 1, "createdAt": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("report_queue".to_string())
+                    .build(),
+            )
+            .build();
+
+        let reporter_index = IndexModel::builder()
+            .keys(doc! { "reporter": 1, "createdAt": -1 })
+            .options(
+                IndexOptions::builder()
+                    .name("report_reporter".to_string())
+                    .build(),
+            )
+            .build();
+
+        self.report_collection
+            .create_indexes(vec![queue_index, reporter_index], None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        Ok(())
+    }
+
+    pub async fn create_report(&self, report: &Report) -> Result<(), PollError> {
+        self.report_collection
+            .insert_one(report, None)
+            .await
+            .map_err(|e| PollError::PollCreationError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn delete_poll_reports(&self, poll_ids: &[String]) -> Result<(), PollError> {
+        self.report_collection
+            .delete_many(doc! { "pollId": { "$in": poll_ids } }, None)
+            .await
+            .map_err(|e| PollError::PollDeletionError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub async fn count_reports_since(
+        &self,
+        reporter: &str,
+        since: DateTime<Utc>,
+    ) -> Result<u64, PollError> {
+        let filter = doc! {
+            "reporter": reporter,
+            "createdAt": { "$gte": report_bson(&since)? }
+        };
+
+        self.report_collection
+            .count_documents(filter, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
+
+    pub async fn has_open_report(&self, poll_id: &str, reporter: &str) -> Result<bool, PollError> {
+        let filter = doc! {
+            "pollId": poll_id,
+            "reporter": reporter,
+            "status": report_bson(&ReportStatus::Open)?
+        };
+
+        Ok(self
+            .report_collection
+            .find_one(filter, None)
+            .await
+            .map_err(PollError::MongoError)?
+            .is_some())
+    }
+
+    pub async fn get_report_by_id(&self, report_id: &str) -> Result<Option<Report>, PollError> {
+        self.report_collection
+            .find_one(doc! { "reportId": report_id }, None)
+            .await
+            .map_err(PollError::MongoError)
+    }
+
+    // Oldest reports first, so the queue is worked through in order.
+    pub async fn get_reports(
+        &self,
+        status: Option<ReportStatus>,
+        skip: u64,
+        limit: i64,
+    ) -> Result<(u64, Vec<Report>), PollError> {
+        let filter = match status {
+            Some(status) => doc! { "status": report_bson(&status)? },
+            None => doc! {},
+        };
+
+        let total = self
+            .report_collection
+            .count_documents(filter.clone(), None)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": 1 })
+            .skip(skip)
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .report_collection
+            .find(filter, options)
+            .await
+            .map_err(PollError::MongoError)?;
+
+        let reports = cursor.try_collect().await.map_err(PollError::MongoError)?;
+
+        Ok((total, reports))
+    }
+
+    // Applies the transition only if the report is still in its `from` status, so two
+    // moderators acting at once cannot both resolve it. Returns whether it applied.
+    pub async fn transition_report(
+        &self,
+        report_id: &str,
+        transition: &ReportTransition,
+    ) -> Result<bool, PollError> {
+        let filter = doc! {
+            "reportId": report_id,
+            "status": report_bson(&transition.from)?
+        };
+
+        let update_result = self
+            .report_collection
+            .update_one(filter, Self::transition_update(transition)?, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        Ok(update_result.matched_count > 0)
+    }
+
+    // Moves every report of the poll still in `from` along with the one acted on, so
+    // a poll is resolved once however many users reported it.
+    pub async fn transition_poll_reports(
+        &self,
+        poll_id: &str,
+        from: ReportStatus,
+        transition: &ReportTransition,
+    ) -> Result<(), PollError> {
+        let filter = doc! {
+            "pollId": poll_id,
+            "status": report_bson(&from)?
+        };
+
+        let transition = ReportTransition {
+            from,
+            ..transition.clone()
+        };
+
+        self.report_collection
+            .update_many(filter, Self::transition_update(&transition)?, None)
+            .await
+            .map_err(|e| PollError::PollUpdateError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn transition_update(transition: &ReportTransition) -> Result<Document, PollError> {
+        Ok(doc! {
+            "$set": {
+                "status": report_bson(&transition.to)?,
+                "updatedAt": report_bson(&transition.at)?
+            },
+            "$push": { "history": report_bson(transition)? }
+        })
+    }
+}
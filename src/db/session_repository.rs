@@ -0,0 +1,150 @@
+use chrono::Utc;
+use mongodb::bson::{doc, to_bson, Bson};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument};
+use mongodb::results::InsertOneResult;
+use mongodb::{Collection, IndexModel};
+
+use crate::config::session_config::SessionError;
+use crate::models::session_model::Session;
+
+fn now_bson() -> Result<Bson, SessionError> {
+    to_bson(&Utc::now()).map_err(|e| SessionError::SessionUpdateError(e.to_string()))
+}
+
+pub struct SessionRepository {
+    session_collection: Collection<Session>,
+}
+impl SessionRepository {
+    pub fn init(session_collection: Collection<Session>) -> Result<Self, SessionError> {
+        Ok(SessionRepository { session_collection })
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), SessionError> {
+        let join_code_index = IndexModel::builder()
+            .keys(doc! { "joinCode": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("session_join_code".to_string())
+                    .unique(true)
+                    .build(),
+            )
+            .build();
+
+        self.session_collection
+            .create_index(join_code_index, None)
+            .await
+            .map_err(SessionError::MongoError)?;
+
+        Ok(())
+    }
+
+    // Returns `Ok(None)` when the join code is already taken, so the caller can retry
+    // with a fresh one.
+    pub async fn create_session(
+        &self,
+        session: &Session,
+    ) -> Result<Option<InsertOneResult>, SessionError> {
+        match self.session_collection.insert_one(session, None).await {
+            Ok(result) => Ok(Some(result)),
+            Err(err)
+                if matches!(
+                    err.kind.as_ref(),
+                    ErrorKind::Write(WriteFailure::WriteError(write_error))
+                        if write_error.code == 11000
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(SessionError::SessionCreationError(err.to_string())),
+        }
+    }
+
+    pub async fn get_session_by_id(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<Session>, SessionError> {
+        self.session_collection
+            .find_one(doc! { "sessionId": session_id }, None)
+            .await
+            .map_err(SessionError::MongoError)
+    }
+
+    pub async fn get_session_by_join_code(
+        &self,
+        join_code: &str,
+    ) -> Result<Option<Session>, SessionError> {
+        self.session_collection
+            .find_one(doc! { "joinCode": join_code, "isActive": true }, None)
+            .await
+            .map_err(SessionError::MongoError)
+    }
+
+    pub async fn set_current_index(
+        &self,
+        session_id: &str,
+        username: &str,
+        index: usize,
+    ) -> Result<Session, SessionError> {
+        self.check_user_ownership_on_session(session_id, username)
+            .await?;
+
+        let update = doc! {
+            "$set": { "currentIndex": index as i64, "updatedAt": now_bson()? }
+        };
+
+        self.update_active_session(session_id, update).await
+    }
+
+    pub async fn end_session(
+        &self,
+        session_id: &str,
+        username: &str,
+    ) -> Result<Session, SessionError> {
+        self.check_user_ownership_on_session(session_id, username)
+            .await?;
+
+        let update = doc! { "$set": { "isActive": false, "updatedAt": now_bson()? } };
+
+        self.update_active_session(session_id, update).await
+    }
+
+    async fn update_active_session(
+        &self,
+        session_id: &str,
+        update: mongodb::bson::Document,
+    ) -> Result<Session, SessionError> {
+        self.session_collection
+            .find_one_and_update(
+                doc! { "sessionId": session_id, "isActive": true },
+                update,
+                Some(
+                    FindOneAndUpdateOptions::builder()
+                        .return_document(ReturnDocument::After)
+                        .build(),
+                ),
+            )
+            .await
+            .map_err(|e| SessionError::SessionUpdateError(e.to_string()))?
+            .ok_or_else(|| SessionError::SessionClosed("The session has ended.".to_string()))
+    }
+
+    async fn check_user_ownership_on_session(
+        &self,
+        session_id: &str,
+        username: &str,
+    ) -> Result<(), SessionError> {
+        let session = self
+            .get_session_by_id(session_id)
+            .await?
+            .ok_or_else(|| SessionError::SessionNotFound(session_id.to_string()))?;
+
+        if session.username == username {
+            Ok(())
+        } else {
+            Err(SessionError::SessionUnauthorizedAccess(
+                "Only the presenter can control the session.".to_string(),
+            ))
+        }
+    }
+}
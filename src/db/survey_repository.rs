@@ -0,0 +1,129 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, to_bson, Document};
+use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::results::{InsertOneResult, UpdateResult};
+use mongodb::Collection;
+
+use crate::config::survey_config::SurveyError;
+use crate::models::survey_model::{Survey, SurveyResponse};
+
+pub struct SurveyRepository {
+    survey_collection: Collection<Survey>,
+    response_collection: Collection<SurveyResponse>,
+}
+impl SurveyRepository {
+    pub fn init(
+        survey_collection: Collection<Survey>,
+        response_collection: Collection<SurveyResponse>,
+    ) -> Result<Self, SurveyError> {
+        Ok(SurveyRepository {
+            survey_collection,
+            response_collection,
+        })
+    }
+
+    pub async fn create_survey(&self, survey: &Survey) -> Result<InsertOneResult, SurveyError> {
+        self.survey_collection
+            .insert_one(survey, None)
+            .await
+            .map_err(|e| SurveyError::SurveyCreationError(e.to_string()))
+    }
+
+    pub async fn get_survey_by_id(&self, survey_id: &str) -> Result<Option<Survey>, SurveyError> {
+        self.survey_collection
+            .find_one(doc! { "surveyId": survey_id }, None)
+            .await
+            .map_err(SurveyError::MongoError)
+    }
+
+    // Counts every choice and rating answer and registers the respondent in one update,
+    // so a respondent is tallied at most once even when submitting concurrently. The
+    // full response is stored afterwards for free-text answers and exports.
+    pub async fn submit_response(&self, response: &SurveyResponse) -> Result<(), SurveyError> {
+        let mut increments = Document::new();
+        let mut array_filters = Vec::new();
+
+        for (index, answer) in response.answers.iter().enumerate() {
+            if let Some(option_id) = &answer.option_id {
+                increments.insert(
+                    format!("questions.$[q{index}].options.$[o{index}].votes"),
+                    1,
+                );
+                array_filters.push(doc! { format!("q{index}.questionId"): &answer.question_id });
+                array_filters.push(doc! { format!("o{index}.optionId"): option_id });
+            }
+        }
+
+        let now = to_bson(&Utc::now()).map_err(|e| SurveyError::GeneralError(e.to_string()))?;
+
+        let mut update = doc! {
+            "$addToSet": { "respondents": &response.username },
+            "$set": { "updatedAt": now }
+        };
+        if !increments.is_empty() {
+            update.insert("$inc", increments);
+        }
+
+        let filter = doc! {
+            "surveyId": &response.survey_id,
+            "isActive": true,
+            "respondents": { "$ne": &response.username }
+        };
+
+        let update_result: UpdateResult = self
+            .survey_collection
+            .update_one(
+                filter,
+                update,
+                Some(
+                    UpdateOptions::builder()
+                        .array_filters(Some(array_filters))
+                        .build(),
+                ),
+            )
+            .await
+            .map_err(|e| SurveyError::SurveyUpdateError(e.to_string()))?;
+
+        if update_result.matched_count == 0 {
+            return Err(SurveyError::AlreadyResponded(
+                "Survey is closed or already answered by this user.".to_string(),
+            ));
+        }
+
+        self.response_collection
+            .insert_one(response, None)
+            .await
+            .map_err(SurveyError::MongoError)?;
+
+        Ok(())
+    }
+
+    pub async fn get_responses_by_survey_id(
+        &self,
+        survey_id: &str,
+    ) -> Result<Vec<SurveyResponse>, SurveyError> {
+        let options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = self
+            .response_collection
+            .find(doc! { "surveyId": survey_id }, options)
+            .await
+            .map_err(SurveyError::MongoError)?;
+
+        cursor.try_collect().await.map_err(SurveyError::MongoError)
+    }
+
+    pub async fn close_survey_by_id(&self, survey_id: &str) -> Result<UpdateResult, SurveyError> {
+        let now = to_bson(&Utc::now()).map_err(|e| SurveyError::GeneralError(e.to_string()))?;
+
+        self.survey_collection
+            .update_one(
+                doc! { "surveyId": survey_id },
+                doc! { "$set": { "isActive": false, "updatedAt": now } },
+                None,
+            )
+            .await
+            .map_err(|e| SurveyError::SurveyUpdateError(e.to_string()))
+    }
+}
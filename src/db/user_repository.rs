@@ -1,12 +1,14 @@
 use crate::config::user_config::Error;
 
+use futures::TryStreamExt;
 use mongodb::{
     bson::{doc, to_bson},
+    options::FindOptions,
     results::{DeleteResult, InsertOneResult, UpdateResult},
     Collection,
 };
 
-use crate::models::user_model::{User, UserLoginState, UserRegistrationState};
+use crate::models::user_model::{User, UserLoginState, UserRegistrationState, UserRole};
 
 pub struct UserRepository {
     pub user_collection: Collection<User>,
@@ -31,7 +33,7 @@ impl UserRepository {
         self.user_collection
             .insert_one(user, None)
             .await
-            .map_err(|e| Error::MongoError(e))
+            .map_err(Error::MongoError)
     }
 
     pub async fn find_user(&self, username: &str) -> Result<Option<User>, Error> {
@@ -39,7 +41,71 @@ impl UserRepository {
         self.user_collection
             .find_one(filter, None)
             .await
-            .map_err(|e| Error::MongoError(e))
+            .map_err(Error::MongoError)
+    }
+
+    pub async fn list_users(&self) -> Result<Vec<User>, Error> {
+        let options = FindOptions::builder().sort(doc! { "username": 1 }).build();
+
+        let cursor = self
+            .user_collection
+            .find(None, options)
+            .await
+            .map_err(Error::MongoError)?;
+
+        cursor.try_collect().await.map_err(Error::MongoError)
+    }
+
+    pub async fn admin_exists(&self) -> Result<bool, Error> {
+        let role = to_bson(&UserRole::Admin)
+            .map_err(|_e| Error::GeneralError("Failed to Serialize".to_string()))?;
+
+        let admin = self
+            .user_collection
+            .find_one(doc! { "role": role }, None)
+            .await
+            .map_err(Error::MongoError)?;
+
+        Ok(admin.is_some())
+    }
+
+    pub async fn set_role(&self, username: &str, role: UserRole) -> Result<(), Error> {
+        let role =
+            to_bson(&role).map_err(|_e| Error::GeneralError("Failed to Serialize".to_string()))?;
+
+        let update_result = self
+            .user_collection
+            .update_one(
+                doc! { "username": username },
+                doc! { "$set": { "role": role } },
+                None,
+            )
+            .await
+            .map_err(Error::MongoError)?;
+
+        if update_result.matched_count == 0 {
+            return Err(Error::UserNotFound(username.to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_disabled(&self, username: &str, disabled: bool) -> Result<(), Error> {
+        let update_result = self
+            .user_collection
+            .update_one(
+                doc! { "username": username },
+                doc! { "$set": { "disabled": disabled } },
+                None,
+            )
+            .await
+            .map_err(Error::MongoError)?;
+
+        if update_result.matched_count == 0 {
+            return Err(Error::UserNotFound(username.to_string()));
+        }
+
+        Ok(())
     }
 
     pub async fn get_user_credentials(&self, username: &str) -> Result<User, Error> {
@@ -58,7 +124,7 @@ impl UserRepository {
         let filter = doc! {"username": &login_state.username};
 
         let state_bson = to_bson(&login_state.state)
-            .map_err(|_e| return Error::GeneralError("Failed to Deserialize".to_string()))?;
+            .map_err(|_e| Error::GeneralError("Failed to Deserialize".to_string()))?;
 
         let update = doc! {
             "$set": {
@@ -79,7 +145,7 @@ impl UserRepository {
                 ),
             )
             .await
-            .map_err(|e| Error::MongoError(e))?;
+            .map_err(Error::MongoError)?;
 
         Ok(update_result)
     }
@@ -91,7 +157,7 @@ impl UserRepository {
         let filter = doc! {"username": &reg_state.username};
 
         let state_bson = to_bson(&reg_state.state)
-            .map_err(|_e| return Error::GeneralError("Failed to Deserialize".to_string()))?;
+            .map_err(|_e| Error::GeneralError("Failed to Deserialize".to_string()))?;
 
         let update = doc! {
             "$set": {
@@ -113,7 +179,7 @@ impl UserRepository {
                 ),
             )
             .await
-            .map_err(|e| Error::MongoError(e))?;
+            .map_err(Error::MongoError)?;
 
         Ok(update_result)
     }
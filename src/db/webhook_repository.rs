@@ -0,0 +1,299 @@
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::bson::{doc, to_bson, Bson};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+use serde::Serialize;
+
+use crate::config::webhook_config::WebhookError;
+use crate::models::webhook_model::{
+    DeliveryAttempt, DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent,
+};
+
+fn webhook_bson<T: Serialize>(value: &T) -> Result<Bson, WebhookError> {
+    to_bson(value).map_err(|e| WebhookError::GeneralError(e.to_string()))
+}
+
+pub struct WebhookRepository {
+    webhook_collection: Collection<Webhook>,
+    delivery_collection: Collection<WebhookDelivery>,
+}
+
+impl WebhookRepository {
+    pub fn init(
+        webhook_collection: Collection<Webhook>,
+        delivery_collection: Collection<WebhookDelivery>,
+    ) -> Result<Self, WebhookError> {
+        Ok(WebhookRepository {
+            webhook_collection,
+            delivery_collection,
+        })
+    }
+
+    pub async fn create_indexes(&self) -> Result<(), WebhookError> {
+        let owner_index = IndexModel::builder()
+            .keys(doc! { "owner": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("webhook_owner".to_string())
+                    .build(),
+            )
+            .build();
+
+        let poll_index = IndexModel::builder()
+            .keys(doc! { "pollId": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("webhook_poll".to_string())
+                    .build(),
+            )
+            .build();
+
+        self.webhook_collection
+            .create_indexes(vec![owner_index, poll_index], None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        let log_index = IndexModel::builder()
+            .keys(doc! { "webhookId": 1, "createdAt": -1 })
+            .options(
+                IndexOptions::builder()
+                    .name("delivery_log".to_string())
+                    .build(),
+            )
+            .build();
+
+        let queue_index = IndexModel::builder()
+            .keys(doc! { "status": 1, "nextAttemptAt": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("delivery_queue".to_string())
+                    .build(),
+            )
+            .build();
+
+        self.delivery_collection
+            .create_indexes(vec![log_index, queue_index], None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        Ok(())
+    }
+
+    pub async fn create_webhook(&self, webhook: &Webhook) -> Result<(), WebhookError> {
+        self.webhook_collection
+            .insert_one(webhook, None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        Ok(())
+    }
+
+    pub async fn count_webhooks(&self, owner: &str) -> Result<u64, WebhookError> {
+        self.webhook_collection
+            .count_documents(doc! { "owner": owner }, None)
+            .await
+            .map_err(WebhookError::MongoError)
+    }
+
+    pub async fn get_webhooks_by_owner(&self, owner: &str) -> Result<Vec<Webhook>, WebhookError> {
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .build();
+
+        let cursor = self
+            .webhook_collection
+            .find(doc! { "owner": owner }, options)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        cursor.try_collect().await.map_err(WebhookError::MongoError)
+    }
+
+    pub async fn get_webhook_by_id(
+        &self,
+        webhook_id: &str,
+    ) -> Result<Option<Webhook>, WebhookError> {
+        self.webhook_collection
+            .find_one(doc! { "webhookId": webhook_id }, None)
+            .await
+            .map_err(WebhookError::MongoError)
+    }
+
+    // Removes the webhook together with its delivery log and queued deliveries.
+    pub async fn delete_webhook(&self, webhook_id: &str) -> Result<(), WebhookError> {
+        self.webhook_collection
+            .delete_one(doc! { "webhookId": webhook_id }, None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        self.delivery_collection
+            .delete_many(doc! { "webhookId": webhook_id }, None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        Ok(())
+    }
+
+    // Removes the webhooks of purged polls, and every delivery about them including those
+    // of account-wide webhooks.
+    pub async fn delete_poll_webhooks(&self, poll_ids: &[String]) -> Result<(), WebhookError> {
+        let query = doc! { "pollId": { "$in": poll_ids } };
+
+        self.webhook_collection
+            .delete_many(query.clone(), None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        self.delivery_collection
+            .delete_many(query, None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        Ok(())
+    }
+
+    // Webhooks of the poll itself and account-wide webhooks of its owner that want the
+    // event.
+    pub async fn get_subscribed_webhooks(
+        &self,
+        poll_id: &str,
+        poll_owner: &str,
+        event: WebhookEvent,
+    ) -> Result<Vec<Webhook>, WebhookError> {
+        let filter = doc! {
+            "events": event.name(),
+            "$or": [
+                { "pollId": poll_id },
+                { "pollId": null, "owner": poll_owner }
+            ]
+        };
+
+        let cursor = self
+            .webhook_collection
+            .find(filter, None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        cursor.try_collect().await.map_err(WebhookError::MongoError)
+    }
+
+    pub async fn queue_deliveries(
+        &self,
+        deliveries: &[WebhookDelivery],
+    ) -> Result<(), WebhookError> {
+        if deliveries.is_empty() {
+            return Ok(());
+        }
+
+        self.delivery_collection
+            .insert_many(deliveries, None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        Ok(())
+    }
+
+    // Takes the most overdue pending delivery and locks it until `locked_until`. A
+    // dispatcher that dies mid-delivery leaves the lock to expire, and the delivery is
+    // picked up again.
+    pub async fn claim_due_delivery(
+        &self,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<Option<WebhookDelivery>, WebhookError> {
+        let now = webhook_bson(&now)?;
+
+        let filter = doc! {
+            "status": webhook_bson(&DeliveryStatus::Pending)?,
+            "nextAttemptAt": { "$lte": now.clone() },
+            "$or": [
+                { "lockedUntil": null },
+                { "lockedUntil": { "$lt": now } }
+            ]
+        };
+        let update = doc! { "$set": { "lockedUntil": webhook_bson(&locked_until)? } };
+        let options = FindOneAndUpdateOptions::builder()
+            .sort(doc! { "nextAttemptAt": 1 })
+            .return_document(ReturnDocument::After)
+            .build();
+
+        self.delivery_collection
+            .find_one_and_update(filter, update, options)
+            .await
+            .map_err(WebhookError::MongoError)
+    }
+
+    pub async fn record_attempt(
+        &self,
+        delivery_id: &str,
+        attempt: &DeliveryAttempt,
+        status: DeliveryStatus,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<(), WebhookError> {
+        let update = doc! {
+            "$push": { "attempts": webhook_bson(attempt)? },
+            "$set": {
+                "status": webhook_bson(&status)?,
+                "nextAttemptAt": webhook_bson(&next_attempt_at)?,
+                "lockedUntil": Bson::Null
+            }
+        };
+
+        self.delivery_collection
+            .update_one(doc! { "deliveryId": delivery_id }, update, None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        Ok(())
+    }
+
+    // Newest deliveries first.
+    pub async fn get_deliveries(
+        &self,
+        webhook_id: &str,
+        skip: u64,
+        limit: i64,
+    ) -> Result<(u64, Vec<WebhookDelivery>), WebhookError> {
+        let filter = doc! { "webhookId": webhook_id };
+
+        let total = self
+            .delivery_collection
+            .count_documents(filter.clone(), None)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .skip(skip)
+            .limit(limit)
+            .build();
+
+        let cursor = self
+            .delivery_collection
+            .find(filter, options)
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        let deliveries = cursor
+            .try_collect()
+            .await
+            .map_err(WebhookError::MongoError)?;
+
+        Ok((total, deliveries))
+    }
+
+    pub async fn get_delivery(
+        &self,
+        webhook_id: &str,
+        delivery_id: &str,
+    ) -> Result<Option<WebhookDelivery>, WebhookError> {
+        self.delivery_collection
+            .find_one(
+                doc! { "webhookId": webhook_id, "deliveryId": delivery_id },
+                None,
+            )
+            .await
+            .map_err(WebhookError::MongoError)
+    }
+}
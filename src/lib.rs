@@ -6,7 +6,7 @@ pub mod services;
 pub mod startup;
 pub mod utils;
 
-use std::io;
+use std::{io, sync::Arc};
 
 use utils::api_docs::ApiDoc;
 use utoipa::OpenApi;
@@ -18,13 +18,24 @@ use actix_web::{
     App, HttpResponse, HttpServer,
 };
 
-use config::config::AppConfig;
+use config::{
+    config::{AppConfig, RateLimitStoreKind},
+    error_response::{json_error_handler, query_error_handler},
+};
 use models::broadcaster_model::Broadcaster;
 use mongodb::bson::raw::Error;
 
 use db::mongodb_repository::MongoDB;
-use services::{auth_service, poll_service, socket_service};
+use middlewares::rate_limit_middleware::{rate_limit_middleware, RateLimiter};
+use services::{
+    admin_service, auth_service, chat_service, organization_service, poll_service, session_service,
+    socket_service, survey_service, webhook_service,
+};
 use startup::startup;
+use utils::content_filter::ContentPipeline;
+use utils::purge_job::spawn_purge_job;
+use utils::rate_limit_utility::{InMemoryRateLimitStore, RateLimitStore};
+use utils::webhook_job::spawn_webhook_dispatcher;
 
 pub async fn home_route() -> HttpResponse {
     HttpResponse::Ok().body("Hello! Welcome to the backend api of polling application.")
@@ -35,30 +46,57 @@ pub async fn init_db(mongo_uri: &str, database_name: &str) -> Result<Data<MongoD
     Ok(Data::new(db))
 }
 
-pub async fn init_server(db_data: Data<MongoDB>) -> std::io::Result<()> {
-    let webauthn = startup().map_err(|err| {
-        io::Error::new(
-            io::ErrorKind::Other,
-            format!("Failed to initialize Webauthn: {}", err),
-        )
-    })?;
+pub async fn init_server(db_data: Data<MongoDB>, app_config: AppConfig) -> std::io::Result<()> {
+    let webauthn = startup()
+        .map_err(|err| io::Error::other(format!("Failed to initialize Webauthn: {}", err)))?;
 
     let broadcaster = Broadcaster::create();
 
+    spawn_purge_job(db_data.clone(), app_config.poll_retention_days);
+    spawn_webhook_dispatcher(db_data.clone(), app_config.webhooks.clone());
+
+    let content_pipeline = Data::new(ContentPipeline::from_config(&app_config.content_filter));
+
+    let rate_limit_store: Arc<dyn RateLimitStore> = match app_config.rate_limit.store {
+        RateLimitStoreKind::Memory => Arc::new(InMemoryRateLimitStore::default()),
+        RateLimitStoreKind::MongoDB => Arc::new(db_data.rate_limit_repository.clone()),
+    };
+    let rate_limiter = Data::new(RateLimiter::new(
+        app_config.rate_limit.clone(),
+        rate_limit_store,
+    ));
+
+    let app_config = Data::new(app_config);
+
     let openapi = ApiDoc::openapi();
 
     HttpServer::new(move || {
         App::new()
             .app_data(broadcaster.clone())
             .app_data(db_data.clone())
+            .app_data(app_config.clone())
+            .app_data(content_pipeline.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(query_error_handler))
             .app_data(webauthn.clone())
             .service(
                 SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-docs/openapi.json", openapi.clone()),
             )
             .service(web::scope("/api/auth").configure(auth_service::init))
             .service(web::scope("/api/socket").configure(socket_service::init))
-            .service(web::scope("/api").configure(poll_service::init))
+            .service(
+                web::scope("/api")
+                    .configure(poll_service::init)
+                    .configure(survey_service::init)
+                    .configure(session_service::init)
+                    .configure(organization_service::init)
+                    .configure(admin_service::init)
+                    .configure(webhook_service::init)
+                    .configure(chat_service::init),
+            )
             .route("/", web::get().to(home_route))
+            .wrap(actix_web::middleware::from_fn(rate_limit_middleware))
             .wrap(
                 Cors::default()
                     .allow_any_origin()
@@ -75,6 +113,11 @@ pub async fn init_server(db_data: Data<MongoDB>) -> std::io::Result<()> {
 pub async fn run() -> std::io::Result<()> {
     let app_config = AppConfig::default();
 
+    if let Err(err) = app_config.check_secrets() {
+        println!("{}", err);
+        return Err(std::io::Error::other(err));
+    }
+
     let db_data: Result<Data<MongoDB>, Error> =
         init_db(&app_config.mongodb_uri, &app_config.database_name).await;
 
@@ -85,12 +128,9 @@ pub async fn run() -> std::io::Result<()> {
         }
         Err(_) => {
             println!("Failed to connect to the database.");
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Database connection failed",
-            ));
+            return Err(std::io::Error::other("Database connection failed"));
         }
     };
 
-    init_server(db_data).await
+    init_server(db_data, app_config).await
 }
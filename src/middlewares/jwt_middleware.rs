@@ -1,64 +1,111 @@
 use actix_web::{
     body::BoxBody,
     dev::{ServiceRequest, ServiceResponse},
+    http::StatusCode,
     middleware::Next,
-    HttpResponse,
+    web::Data,
+    HttpRequest, HttpResponse,
 };
 
 use jsonwebtoken::{decode, DecodingKey, TokenData, Validation};
-use serde_json::json;
 
-use crate::utils::jwt_token_generation::Claims;
+use crate::{
+    config::error_response::{public_message, ErrorResponse},
+    db::mongodb_repository::MongoDB,
+    utils::jwt_token_generation::Claims,
+};
+
+// Checks the session cookie and that the account behind it is still enabled, so
+// disabling a user takes effect before their token expires. The role is read from the
+// account as well, so revoking the admin role also takes effect immediately.
+async fn authenticate(req: &ServiceRequest) -> Result<Claims, HttpResponse> {
+    let secret_key = std::env::var("JWT_SECRET").map_err(|_| {
+        ErrorResponse::new(
+            "internal_error",
+            "JWT_SECRET is not set in the environment variables",
+        )
+        .into_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    let token = req.cookie("token").ok_or_else(|| {
+        ErrorResponse::new("unauthorized", "No token found in cookies")
+            .into_response(StatusCode::UNAUTHORIZED)
+    })?;
+
+    let mut claims = validate_jwt(token.value(), &secret_key)
+        .map_err(|_| {
+            ErrorResponse::new("unauthorized", "Invalid or expired token")
+                .into_response(StatusCode::UNAUTHORIZED)
+        })?
+        .claims;
+
+    if let Some(db) = req.app_data::<Data<MongoDB>>() {
+        match db.user_repository.find_user(&claims.sub).await {
+            Ok(Some(user)) if !user.disabled => claims.role = user.role,
+            Ok(Some(_)) => {
+                return Err(ErrorResponse::new(
+                    "account_disabled",
+                    format!("Account '{}' is disabled", claims.sub),
+                )
+                .into_response(StatusCode::FORBIDDEN));
+            }
+            Ok(None) => {
+                return Err(
+                    ErrorResponse::new("unauthorized", "Account no longer exists")
+                        .into_response(StatusCode::UNAUTHORIZED),
+                );
+            }
+            Err(err) => {
+                let status = StatusCode::INTERNAL_SERVER_ERROR;
+                return Err(
+                    ErrorResponse::new("database_error", public_message(status, &err))
+                        .into_response(status),
+                );
+            }
+        }
+    }
+
+    Ok(claims)
+}
 
 pub async fn jwt_middleware(
     req: ServiceRequest,
     next: Next<BoxBody>,
 ) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
-    let secret_key = match std::env::var("JWT_SECRET") {
-        Ok(secret) => secret,
-        Err(_) => {
-            return Ok(req.into_response(
-                HttpResponse::InternalServerError()
-                    .json(json!({
-                        "error": "Internal Server Error",
-                        "message": "JWT_SECRET is not set in the environment variables"
-                    }))
-                    .map_into_boxed_body(),
-            ));
-        }
-    };
-
-    let token: String = match req.cookie("token") {
-        Some(cookie) => {
-            let c = cookie.value().to_string();
-            c
-        }
-        None => {
-            return Ok(req.into_response(
-                HttpResponse::Unauthorized()
-                    .json(json!({
-                        "error": "Unauthorized",
-                        "message": "No token found in cookies"
-                    }))
-                    .map_into_boxed_body(),
-            ));
-        }
-    };
-
-    if let Err(_) = validate_jwt(&token, &secret_key) {
-        return Ok(req.into_response(
-            HttpResponse::Unauthorized()
-                .json(serde_json::json!({
-                    "error": "Unauthorized",
-                    "message": "Invalid or expired token"
-                }))
-                .map_into_boxed_body(),
-        ));
+    if let Err(response) = authenticate(&req).await {
+        return Ok(req.into_response(response));
     }
 
     next.call(req).await
 }
 
+// Guards the site administration routes: the caller's account must hold the admin
+// role.
+pub async fn admin_middleware(
+    req: ServiceRequest,
+    next: Next<BoxBody>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    match authenticate(&req).await {
+        Ok(claims) if claims.is_admin() => next.call(req).await,
+        Ok(_) => Ok(req.into_response(
+            ErrorResponse::new("forbidden", "Only site admins can access this resource")
+                .into_response(StatusCode::FORBIDDEN),
+        )),
+        Err(response) => Ok(req.into_response(response)),
+    }
+}
+
+// Identifies the caller on routes that are public but show different data to signed in
+// users. A missing or invalid token simply means an anonymous viewer.
+pub fn claims_from_request(req: &HttpRequest) -> Option<Claims> {
+    let secret_key = std::env::var("JWT_SECRET").ok()?;
+    let token = req.cookie("token")?;
+
+    validate_jwt(token.value(), &secret_key)
+        .ok()
+        .map(|token_data| token_data.claims)
+}
+
 // JWT validation function
 fn validate_jwt(
     token: &str,
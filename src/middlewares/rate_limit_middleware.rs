@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use actix_web::{
+    body::BoxBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header, StatusCode},
+    middleware::Next,
+    web::Data,
+};
+use log::warn;
+
+use crate::{
+    config::{
+        config::{BucketLimit, RateLimitConfig},
+        error_response::ErrorResponse,
+    },
+    middlewares::jwt_middleware::claims_from_request,
+    utils::rate_limit_utility::{route_group, RateDecision, RateLimitStore, RouteGroup},
+};
+
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        RateLimiter { config, store }
+    }
+
+    fn limit_for(&self, group: RouteGroup) -> &BucketLimit {
+        match group {
+            RouteGroup::Auth => &self.config.auth,
+            RouteGroup::Create => &self.config.create,
+            RouteGroup::Vote => &self.config.vote,
+            RouteGroup::Results => &self.config.results,
+        }
+    }
+
+    // Every request draws from the bucket of its address, and signed in callers also from
+    // a bucket of their own, so neither many accounts on one address nor one account
+    // spread over many addresses gets around the limit.
+    fn bucket_keys(&self, req: &ServiceRequest, group: RouteGroup) -> Vec<String> {
+        let ip = if self.config.trust_proxy {
+            req.connection_info()
+                .realip_remote_addr()
+                .map(str::to_string)
+        } else {
+            req.peer_addr().map(|addr| addr.ip().to_string())
+        };
+
+        let mut keys = vec![format!(
+            "{}:ip:{}",
+            group.name(),
+            ip.unwrap_or_else(|| "unknown".to_string())
+        )];
+
+        if let Some(claims) = claims_from_request(req.request()) {
+            keys.push(format!("{}:user:{}", group.name(), claims.sub));
+        }
+
+        keys
+    }
+}
+
+pub async fn rate_limit_middleware(
+    req: ServiceRequest,
+    next: Next<BoxBody>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let limiter = match req.app_data::<Data<RateLimiter>>() {
+        Some(limiter) if limiter.config.enabled => limiter.clone(),
+        _ => return next.call(req).await,
+    };
+
+    let group = match route_group(req.method(), req.path()) {
+        Some(group) => group,
+        None => return next.call(req).await,
+    };
+
+    let mut decision = RateDecision::Allowed;
+
+    for key in limiter.bucket_keys(&req, group) {
+        // A failing shared store lets requests through rather than taking the API down.
+        let bucket_decision = limiter
+            .store
+            .take(&key, limiter.limit_for(group))
+            .await
+            .unwrap_or_else(|err| {
+                warn!("Rate limit store failed for {}: {}", key, err);
+                RateDecision::Allowed
+            });
+
+        // The caller waits for whichever bucket refills last.
+        if let RateDecision::Limited { retry_after_secs } = bucket_decision {
+            decision = match decision {
+                RateDecision::Limited {
+                    retry_after_secs: previous,
+                } => RateDecision::Limited {
+                    retry_after_secs: previous.max(retry_after_secs),
+                },
+                RateDecision::Allowed => bucket_decision,
+            };
+        }
+    }
+
+    match decision {
+        RateDecision::Allowed => next.call(req).await,
+        RateDecision::Limited { retry_after_secs } => {
+            let mut response = ErrorResponse::new(
+                "rate_limited",
+                format!(
+                    "Too many {} requests, retry in {} seconds",
+                    group.name(),
+                    retry_after_secs
+                ),
+            )
+            .into_response(StatusCode::TOO_MANY_REQUESTS);
+
+            response.headers_mut().insert(
+                header::RETRY_AFTER,
+                header::HeaderValue::from(retry_after_secs),
+            );
+
+            Ok(req.into_response(response))
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// One action taken with site-wide admin rights. Entries are only ever appended.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub audit_id: String,
+    // Username of the admin, or `config` for the bootstrap admin promotion.
+    pub actor: String,
+    pub action: AuditAction,
+    // The username or poll ID the action was applied to.
+    pub target: String,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    GrantAdmin,
+    RevokeAdmin,
+    DisableUser,
+    EnableUser,
+    ClosePoll,
+    DeletePoll,
+    HidePoll,
+    UnhidePoll,
+    DismissReport,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogPage {
+    pub page: u64,
+    pub limit: u64,
+    pub total: u64,
+    pub entries: Vec<AuditEntry>,
+}
@@ -6,14 +6,60 @@ use serde_json::Value;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::time::{interval, Duration};
 
+use std::collections::HashSet;
 use std::pin::Pin;
 use std::sync::Mutex;
 
-use super::poll_model::Poll;
+use super::poll_model::{Leaderboard, OptionItem, Poll, PollRole, PollVisibility, TextAnswer};
+use super::session_model::SessionState;
 
 #[derive(Debug)]
 pub struct Broadcaster {
-    pub clients: Vec<Sender<Bytes>>,
+    pub clients: Vec<BroadcastClient>,
+}
+
+// A connected SSE stream together with who is listening and, optionally, the single
+// poll or live session they subscribed to. Poll events are only delivered to viewers
+// allowed to see it, and streams subscribed to every poll only hear of public polls.
+#[derive(Debug)]
+pub struct BroadcastClient {
+    pub sender: Sender<Bytes>,
+    pub viewer: Option<String>,
+    pub poll_id: Option<String>,
+    pub session_id: Option<String>,
+    // Polls this viewer voted in while connected. Needed for anonymous polls, whose
+    // voters are not stored on the poll itself.
+    pub voted_polls: HashSet<String>,
+    // Organizations the viewer belongs to, for polls restricted to an organization.
+    pub org_ids: Vec<String>,
+}
+
+impl BroadcastClient {
+    fn receives(&self, poll: &Poll) -> bool {
+        let subscribed = match (&self.poll_id, &self.session_id) {
+            (Some(poll_id), _) => poll_id == &poll.poll_id,
+            (None, Some(session_id)) => poll.session_id.as_ref() == Some(session_id),
+            (None, None) => poll.visibility == PollVisibility::Public,
+        };
+
+        subscribed
+            && poll.can_view_in_organizations(self.viewer.as_deref(), &self.org_ids)
+            && !poll.is_hidden_from(self.viewer.as_deref())
+    }
+
+    fn has_voted(&self, poll: &Poll) -> bool {
+        poll.has_voted(self.viewer.as_deref()) || self.voted_polls.contains(&poll.poll_id)
+    }
+
+    fn sees_results(&self, poll: &Poll) -> bool {
+        poll.can_see_results(self.viewer.as_deref(), self.has_voted(poll))
+    }
+}
+
+impl Default for Broadcaster {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Broadcaster {
@@ -46,47 +92,185 @@ impl Broadcaster {
     pub fn remove_stale_clients(&mut self) {
         self.clients.retain(|client| {
             client
+                .sender
                 .clone()
                 .try_send(Bytes::from("data: ping\n\n"))
                 .is_ok()
         });
     }
 
-    pub fn new_client(&mut self) -> Client {
+    pub fn new_client(&mut self, viewer: Option<String>, poll_id: Option<String>) -> Client {
         let (tx, rx) = channel(100);
 
         // Send initial connection message
         let _ = tx.clone().try_send(Bytes::from("data: connected\n\n"));
 
-        self.clients.push(tx);
+        self.clients.push(BroadcastClient {
+            sender: tx,
+            viewer,
+            poll_id,
+            session_id: None,
+            voted_polls: HashSet::new(),
+            org_ids: Vec::new(),
+        });
         Client(rx)
     }
 
+    // Audience clients of a live session receive its `session_state` events and the
+    // events of every poll presented in it.
+    pub fn new_session_client(&mut self, viewer: Option<String>, session_id: String) -> Client {
+        let client = self.new_client(viewer, None);
+
+        if let Some(last) = self.clients.last_mut() {
+            last.session_id = Some(session_id);
+        }
+
+        client
+    }
+
     pub fn send(&self, msg: &str) {
         let msg = Bytes::from(format!("data: {}\n\n", msg));
 
         for client in &self.clients {
-            let _ = client.clone().try_send(msg.clone());
+            let _ = client.sender.clone().try_send(msg.clone());
+        }
+    }
+
+    pub fn record_vote(&mut self, viewer: &str, poll_id: &str) {
+        for client in &mut self.clients {
+            if client.viewer.as_deref() == Some(viewer) {
+                client.voted_polls.insert(poll_id.to_string());
+            }
         }
     }
 
+    // Keeps the viewer's connected clients in line with their memberships, on connect and
+    // whenever they join or leave an organization.
+    pub fn set_viewer_organizations(&mut self, viewer: &str, org_ids: &[String]) {
+        for client in &mut self.clients {
+            if client.viewer.as_deref() == Some(viewer) {
+                client.org_ids = org_ids.to_vec();
+            }
+        }
+    }
+
+    // Called when a viewer retracts their ballot, so hidden results stop reaching them.
+    pub fn forget_vote(&mut self, viewer: &str, poll_id: &str) {
+        for client in &mut self.clients {
+            if client.viewer.as_deref() == Some(viewer) {
+                client.voted_polls.remove(poll_id);
+            }
+        }
+    }
+
+    fn send_poll_event(&self, poll: &Poll, event: &str, data: &str) {
+        let msg = Bytes::from(format!("event: {}\ndata: {}\n\n", event, data));
+
+        for client in self.clients.iter().filter(|client| client.receives(poll)) {
+            let _ = client.sender.clone().try_send(msg.clone());
+        }
+    }
+
+    // Every viewer receives their own copy of the poll, with hidden write-ins removed and
+    // vote counts hidden until they may see the results.
     pub fn send_updated_poll(&self, poll: &Poll) {
-        let poll_json = serde_json::to_string(poll).unwrap();
+        for client in self.clients.iter().filter(|client| client.receives(poll)) {
+            let poll = poll.for_viewer(client.viewer.as_deref(), client.has_voted(poll));
 
-        let msg = Bytes::from(format!("event: poll_updated\ndata: {}\n\n", poll_json));
+            let poll_json = serde_json::to_string(&poll).unwrap();
+            let msg = Bytes::from(format!("event: poll_updated\ndata: {}\n\n", poll_json));
 
-        for client in &self.clients {
-            let _ = client.clone().try_send(msg.clone());
+            let _ = client.sender.clone().try_send(msg);
         }
     }
 
-    pub fn send_poll_results(&self, response: &Value) {
-        let poll_json = response.to_string();
+    // Write-ins are announced as soon as they are created so open ballots can show them.
+    pub fn send_option_added(&self, poll: &Poll, option: &OptionItem) {
+        let option_json =
+            serde_json::json!({ "pollId": poll.poll_id, "option": option }).to_string();
 
-        let msg = Bytes::from(format!("event: poll_results\ndata: {}\n\n", poll_json));
+        self.send_poll_event(poll, "option_added", &option_json);
+    }
 
-        for client in &self.clients {
-            let _ = client.clone().try_send(msg.clone());
+    pub fn send_leaderboard_updated(&self, poll: &Poll, leaderboard: &Leaderboard) {
+        let leaderboard_json = serde_json::to_string(leaderboard).unwrap();
+
+        self.send_poll_event(poll, "leaderboard_updated", &leaderboard_json);
+    }
+
+    pub fn send_session_state(&self, state: &SessionState) {
+        let state_json = serde_json::to_string(state).unwrap();
+        let msg = Bytes::from(format!("event: session_state\ndata: {}\n\n", state_json));
+
+        for client in self
+            .clients
+            .iter()
+            .filter(|client| client.session_id.as_ref() == Some(&state.session_id))
+        {
+            let _ = client.sender.clone().try_send(msg.clone());
+        }
+    }
+
+    pub fn send_poll_deleted(&self, poll: &Poll) {
+        let poll_json = serde_json::json!({ "pollId": poll.poll_id }).to_string();
+
+        self.send_poll_event(poll, "poll_deleted", &poll_json);
+    }
+
+    // Viewers who lose access to a poll hidden by moderators are told to drop it, the
+    // collaborators still receive it as updated.
+    pub fn send_poll_hidden(&self, poll: &Poll) {
+        let visible = Poll {
+            hidden: false,
+            ..poll.clone()
+        };
+        let poll_json = serde_json::json!({ "pollId": poll.poll_id }).to_string();
+        let msg = Bytes::from(format!("event: poll_deleted\ndata: {}\n\n", poll_json));
+
+        for client in self
+            .clients
+            .iter()
+            .filter(|client| client.receives(&visible) && !client.receives(poll))
+        {
+            let _ = client.sender.clone().try_send(msg.clone());
+        }
+
+        self.send_updated_poll(poll);
+    }
+
+    // Free-text answers go to everyone who may see the results. Once an answer is hidden,
+    // only collaborators still receive its text; everyone else is told to drop it.
+    pub fn send_text_answer(&self, poll: &Poll, event: &str, answer: &TextAnswer) {
+        let answer_json =
+            serde_json::json!({ "pollId": poll.poll_id, "answer": answer }).to_string();
+        let hidden_json =
+            serde_json::json!({ "pollId": poll.poll_id, "answerId": answer.answer_id }).to_string();
+
+        for client in self
+            .clients
+            .iter()
+            .filter(|client| client.receives(poll) && client.sees_results(poll))
+        {
+            let is_collaborator = poll.has_role(client.viewer.as_deref(), PollRole::Viewer);
+            let msg = if answer.hidden && !is_collaborator {
+                format!("event: answer_hidden\ndata: {}\n\n", hidden_json)
+            } else {
+                format!("event: {}\ndata: {}\n\n", event, answer_json)
+            };
+
+            let _ = client.sender.clone().try_send(Bytes::from(msg));
+        }
+    }
+
+    pub fn send_poll_results(&self, poll: &Poll, response: &Value) {
+        let msg = Bytes::from(format!("event: poll_results\ndata: {}\n\n", response));
+
+        for client in self
+            .clients
+            .iter()
+            .filter(|client| client.receives(poll) && client.sees_results(poll))
+        {
+            let _ = client.sender.clone().try_send(msg.clone());
         }
     }
 }
@@ -133,3 +317,164 @@ impl Stream for Client {
 //     .run()
 //     .await
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::poll_model::ResultsVisibility;
+
+    // Skips the initial connection message before looking for the event.
+    fn received(client: &mut Client) -> bool {
+        let _ = client.0.try_recv();
+        client.0.try_recv().is_ok()
+    }
+
+    #[test]
+    fn test_private_poll_events_reach_only_allowed_viewers() {
+        let poll = Poll {
+            poll_id: "private".to_string(),
+            username: "owner".to_string(),
+            visibility: PollVisibility::Private,
+            participants: vec!["guest".to_string()],
+            ..Default::default()
+        };
+
+        let mut broadcaster = Broadcaster::new();
+        let mut owner =
+            broadcaster.new_client(Some("owner".to_string()), Some("private".to_string()));
+        let mut guest =
+            broadcaster.new_client(Some("guest".to_string()), Some("private".to_string()));
+        let mut stranger =
+            broadcaster.new_client(Some("stranger".to_string()), Some("private".to_string()));
+        let mut other_poll =
+            broadcaster.new_client(Some("owner".to_string()), Some("other".to_string()));
+
+        broadcaster.send_updated_poll(&poll);
+
+        assert!(received(&mut owner));
+        assert!(received(&mut guest));
+        assert!(!received(&mut stranger));
+        assert!(!received(&mut other_poll));
+    }
+
+    #[test]
+    fn test_only_public_poll_events_reach_every_poll_streams() {
+        let mut poll = Poll {
+            poll_id: "poll".to_string(),
+            username: "owner".to_string(),
+            visibility: PollVisibility::Unlisted,
+            ..Default::default()
+        };
+
+        let mut broadcaster = Broadcaster::new();
+        let mut owner = broadcaster.new_client(Some("owner".to_string()), None);
+        let mut anonymous = broadcaster.new_client(None, None);
+        let _ = owner.0.try_recv();
+        let _ = anonymous.0.try_recv();
+
+        broadcaster.send_updated_poll(&poll);
+
+        assert!(owner.0.try_recv().is_err());
+        assert!(anonymous.0.try_recv().is_err());
+
+        poll.visibility = PollVisibility::Public;
+        broadcaster.send_updated_poll(&poll);
+
+        assert!(owner.0.try_recv().is_ok());
+        assert!(anonymous.0.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_hidden_results_are_sent_only_after_voting() {
+        let poll = Poll {
+            poll_id: "poll".to_string(),
+            username: "owner".to_string(),
+            is_active: true,
+            anonymous: true,
+            results_visibility: ResultsVisibility::AfterVote,
+            ..Default::default()
+        };
+        let results = serde_json::json!({ "pollId": "poll" });
+
+        let mut broadcaster = Broadcaster::new();
+        let mut owner = broadcaster.new_client(Some("owner".to_string()), None);
+        let mut voter = broadcaster.new_client(Some("voter".to_string()), None);
+        let mut other = broadcaster.new_client(Some("other".to_string()), None);
+
+        broadcaster.record_vote("voter", "poll");
+        broadcaster.send_poll_results(&poll, &results);
+
+        assert!(received(&mut owner));
+        assert!(received(&mut voter));
+        assert!(!received(&mut other));
+
+        broadcaster.forget_vote("voter", "poll");
+        broadcaster.send_poll_results(&poll, &results);
+
+        assert!(owner.0.try_recv().is_ok());
+        assert!(voter.0.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_session_clients_follow_their_session() {
+        let poll = Poll {
+            poll_id: "poll".to_string(),
+            session_id: Some("session".to_string()),
+            ..Default::default()
+        };
+        let state = SessionState {
+            session_id: "session".to_string(),
+            title: "All hands".to_string(),
+            join_code: "ABC234".to_string(),
+            is_active: true,
+            current_index: Some(0),
+            total_polls: 1,
+            current_poll_id: Some("poll".to_string()),
+        };
+
+        let mut broadcaster = Broadcaster::new();
+        let mut audience = broadcaster.new_session_client(None, "session".to_string());
+        let mut other = broadcaster.new_session_client(None, "other".to_string());
+        let mut everything = broadcaster.new_client(None, None);
+
+        broadcaster.send_session_state(&state);
+
+        assert!(received(&mut audience));
+        assert!(!received(&mut other));
+        assert!(!received(&mut everything));
+
+        broadcaster.send_updated_poll(&poll);
+
+        assert!(audience.0.try_recv().is_ok());
+        assert!(other.0.try_recv().is_err());
+        assert!(everything.0.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_hidden_polls_are_dropped_by_non_collaborators() {
+        let poll = Poll {
+            poll_id: "poll".to_string(),
+            username: "owner".to_string(),
+            hidden: true,
+            ..Default::default()
+        };
+
+        let mut broadcaster = Broadcaster::new();
+        let mut owner = broadcaster.new_client(Some("owner".to_string()), None);
+        let mut stranger = broadcaster.new_client(Some("stranger".to_string()), None);
+        let _ = owner.0.try_recv();
+        let _ = stranger.0.try_recv();
+
+        broadcaster.send_poll_hidden(&poll);
+
+        let owner_msg = owner.0.try_recv().unwrap();
+        let stranger_msg = stranger.0.try_recv().unwrap();
+        assert!(String::from_utf8_lossy(&owner_msg).starts_with("event: poll_updated"));
+        assert!(String::from_utf8_lossy(&stranger_msg).starts_with("event: poll_deleted"));
+
+        broadcaster.send_updated_poll(&poll);
+
+        assert!(owner.0.try_recv().is_ok());
+        assert!(stranger.0.try_recv().is_err());
+    }
+}
@@ -1,3 +1,9 @@
+pub mod audit_model;
 pub mod broadcaster_model;
+pub mod organization_model;
 pub mod poll_model;
+pub mod report_model;
+pub mod session_model;
+pub mod survey_model;
 pub mod user_model;
+pub mod webhook_model;
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// A team sharing a poll workspace. Polls created inside an organization can be
+// restricted to its members, and its admins manage every poll of the organization.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub org_id: String,
+    pub name: String,
+    pub created_by: String,
+    pub members: Vec<OrganizationMember>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Organization {
+    pub fn role_of(&self, username: &str) -> Option<OrganizationRole> {
+        self.members
+            .iter()
+            .find(|member| member.username == username)
+            .map(|member| member.role)
+    }
+
+    pub fn is_admin(&self, username: &str) -> bool {
+        self.role_of(username) == Some(OrganizationRole::Admin)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationMember {
+    pub username: String,
+    pub role: OrganizationRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum OrganizationRole {
+    #[default]
+    Member,
+    Admin,
+}
@@ -2,28 +2,455 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Poll {
     pub poll_id: String,
     pub username: String,
     pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
     pub options: Vec<OptionItem>,
     pub is_active: bool,
     pub voters: Vec<VoteHistory>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub visibility: PollVisibility,
+    #[serde(default)]
+    pub participants: Vec<String>,
+    #[serde(default)]
+    pub anonymous: bool,
+    #[serde(default)]
+    pub results_visibility: ResultsVisibility,
+    #[serde(default)]
+    pub allow_write_ins: bool,
+    #[serde(default)]
+    pub kind: PollKind,
+    #[serde(default)]
+    pub quiz: Option<QuizSettings>,
+    #[serde(default)]
+    pub availability: Vec<AvailabilityBallot>,
+    #[serde(default)]
+    pub chosen_option_id: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    // Once set, ballots are final: voters can neither change nor retract them.
+    #[serde(default)]
+    pub lock_votes: bool,
+    #[serde(default)]
+    pub collaborators: Vec<Collaborator>,
+    #[serde(default)]
+    pub org_id: Option<String>,
+    // Set by moderators acting on a report. Hidden polls are unlisted and only their
+    // collaborators can still open them.
+    #[serde(default)]
+    pub hidden: bool,
+    // Voters must solve a proof-of-work challenge from `/polls/{id}/challenge` first.
+    #[serde(default)]
+    pub require_proof_of_work: bool,
+    // Visitors without an account may vote with a guest token scoped to this poll.
+    #[serde(default)]
+    pub allow_guests: bool,
+}
+
+impl Poll {
+    // The poll's `username` is its owner; collaborators hold the role they were given.
+    pub fn role_of(&self, username: &str) -> Option<PollRole> {
+        if username == self.username {
+            return Some(PollRole::Owner);
+        }
+
+        self.collaborators
+            .iter()
+            .find(|collaborator| collaborator.username == username)
+            .map(|collaborator| collaborator.role)
+    }
+
+    pub fn has_role(&self, viewer: Option<&str>, role: PollRole) -> bool {
+        viewer
+            .and_then(|viewer| self.role_of(viewer))
+            .is_some_and(|granted| granted >= role)
+    }
+
+    // Public and unlisted polls can be read by anyone who knows the id, private polls
+    // only by collaborators and by users who joined through an invite or access code.
+    // Polls restricted to an organization are also open to its members, see
+    // `can_view_in_organizations`.
+    pub fn can_view(&self, viewer: Option<&str>) -> bool {
+        match self.visibility {
+            PollVisibility::Public | PollVisibility::Unlisted => true,
+            PollVisibility::Private | PollVisibility::Organization => {
+                viewer.is_some_and(|viewer| {
+                    self.role_of(viewer).is_some() || self.participants.iter().any(|p| p == viewer)
+                })
+            }
+        }
+    }
+
+    // Memberships are not stored on the poll, so callers pass the organizations the
+    // viewer belongs to.
+    pub fn can_view_in_organizations(&self, viewer: Option<&str>, org_ids: &[String]) -> bool {
+        let is_member = viewer.is_some()
+            && self.visibility == PollVisibility::Organization
+            && self
+                .org_id
+                .as_ref()
+                .is_some_and(|org_id| org_ids.contains(org_id));
+
+        is_member || self.can_view(viewer)
+    }
+
+    pub fn is_hidden_from(&self, viewer: Option<&str>) -> bool {
+        self.hidden && !self.has_role(viewer, PollRole::Viewer)
+    }
+
+    pub fn has_voted(&self, viewer: Option<&str>) -> bool {
+        viewer.is_some_and(|viewer| {
+            self.voters.iter().any(|vote| vote.username == viewer)
+                || self
+                    .availability
+                    .iter()
+                    .any(|ballot| ballot.username == viewer)
+        })
+    }
+
+    // Collaborators always see the results. Everyone else sees them as allowed by the
+    // results visibility, and once the poll is closed results are open to all viewers.
+    pub fn can_see_results(&self, viewer: Option<&str>, has_voted: bool) -> bool {
+        if self.has_role(viewer, PollRole::Viewer) {
+            return true;
+        }
+
+        match self.results_visibility {
+            ResultsVisibility::Always => true,
+            ResultsVisibility::AfterVote => has_voted || !self.is_active,
+            ResultsVisibility::AfterClose => !self.is_active,
+        }
+    }
+
+    // Copy of the poll for a viewer who may not see results yet: vote counts are zeroed
+    // and only the viewer's own ballot is kept.
+    pub fn without_results(&self, viewer: Option<&str>) -> Poll {
+        let mut poll = self.without_hidden_options(viewer);
+
+        for option in &mut poll.options {
+            option.votes = 0;
+            option.guest_votes = 0;
+        }
+        poll.voters
+            .retain(|vote| viewer.is_some_and(|viewer| vote.username == viewer));
+        poll.availability
+            .retain(|ballot| viewer.is_some_and(|viewer| ballot.username == viewer));
+
+        poll
+    }
+
+    // Hidden write-ins and the lists of collaborators and participants are only kept in
+    // the copies of the poll made for collaborators.
+    pub fn without_hidden_options(&self, viewer: Option<&str>) -> Poll {
+        let mut poll = self.clone();
+
+        if !self.has_role(viewer, PollRole::Viewer) {
+            poll.options.retain(|option| !option.hidden);
+            poll.collaborators.clear();
+            poll.participants.clear();
+        }
+
+        poll
+    }
+
+    pub fn for_viewer(&self, viewer: Option<&str>, has_voted: bool) -> Poll {
+        let mut poll = if self.can_see_results(viewer, has_voted) {
+            self.without_hidden_options(viewer)
+        } else {
+            self.without_results(viewer)
+        };
+
+        if !self.has_role(viewer, PollRole::Viewer) && !self.is_question_closed(Utc::now()) {
+            if let Some(quiz) = &mut poll.quiz {
+                quiz.correct_option_ids.clear();
+                // The answers of other players would give the correct option away too.
+                poll.voters
+                    .retain(|vote| viewer.is_some_and(|viewer| vote.username == viewer));
+            }
+        }
+
+        poll
+    }
+
+    // A quiz question stops taking answers when it is closed or its time limit runs out,
+    // and only then are its correct options revealed.
+    pub fn is_question_closed(&self, now: DateTime<Utc>) -> bool {
+        let timed_out = self
+            .quiz
+            .as_ref()
+            .and_then(|quiz| quiz.closes_at)
+            .is_some_and(|closes_at| now >= closes_at);
+
+        !self.is_active || timed_out
+    }
+}
+
+// A challenge to solve before voting: find a solution such that SHA-256 of
+// `challenge:solution` starts with `difficulty` zero bits.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteChallenge {
+    pub challenge: String,
+    pub difficulty: u32,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizSettings {
+    pub quiz_id: String,
+    pub correct_option_ids: Vec<String>,
+    pub time_limit_secs: Option<u32>,
+    // Unset while a question of a live session waits for the presenter to reach it.
+    #[serde(default)]
+    pub opened_at: Option<DateTime<Utc>>,
+    pub closes_at: Option<DateTime<Utc>>,
+}
+
+// The outcome of one answer to a quiz question, kept for the leaderboard.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizScore {
+    pub quiz_id: String,
+    pub poll_id: String,
+    pub username: String,
+    pub option_id: String,
+    pub correct: bool,
+    pub score: u32,
+    pub elapsed_ms: i64,
+    pub answered_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub username: String,
+    pub score: u32,
+    pub correct_answers: u32,
+    pub answered: u32,
+    pub total_elapsed_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaderboard {
+    pub quiz_id: String,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PollKind {
+    #[default]
+    SingleChoice,
+    FreeText,
+    Quiz,
+    Schedule,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultsVisibility {
+    #[default]
+    Always,
+    AfterVote,
+    AfterClose,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PollVisibility {
+    #[default]
+    Public,
+    Unlisted,
+    Private,
+    Organization,
+}
+
+// Roles are ordered by what they allow: editors can do everything viewers can, and
+// owners everything editors can.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum PollRole {
+    #[default]
+    Viewer,
+    Editor,
+    Owner,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Collaborator {
+    pub username: String,
+    pub role: PollRole,
+    pub added_at: DateTime<Utc>,
+}
+
+// Records that someone took part in an anonymous poll without saying who or how. The
+// id is a keyed hash of the poll and the voter, so a second ballot collides with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipationMarker {
+    #[serde(rename = "_id")]
+    pub marker: String,
+    pub poll_id: String,
+}
+
+// Marks that a guest has voted in a poll. Guests vote once and their ballot is final, so
+// only the fact that they voted is kept; the id combines the poll and the guest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestBallot {
+    #[serde(rename = "_id")]
+    pub ballot_id: String,
+    pub poll_id: String,
+    pub cast_at: DateTime<Utc>,
+}
+
+// Returned when a visitor starts or resumes a guest session for a poll. The token itself
+// is set as the `guest_token` cookie.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestSession {
+    pub guest_id: String,
+    pub poll_id: String,
+    pub has_voted: bool,
+    pub expires_at: DateTime<Utc>,
+}
+
+// An answer to a free-text poll. Answers of anonymous polls are stored without a username.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextAnswer {
+    pub answer_id: String,
+    pub poll_id: String,
+    pub username: Option<String>,
+    pub text: String,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub pinned: bool,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
+pub struct TextAnswerPage {
+    pub page: u64,
+    pub limit: u64,
+    pub total: u64,
+    pub answers: Vec<TextAnswer>,
+    pub summary: Vec<WordFrequency>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WordFrequency {
+    pub word: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum InviteKind {
+    #[default]
+    Token,
+    AccessCode,
+}
+
+// Secrets granting access to a private poll. Kept out of the poll document so they are
+// never included when a poll is returned or broadcast.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PollInvite {
+    pub poll_id: String,
+    pub secret: String,
+    pub kind: InviteKind,
+    pub label: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct OptionItem {
     pub option_id: String,
     pub text: String,
     pub votes: u32,
+    // Share of `votes` cast by guests.
+    #[serde(default)]
+    pub guest_votes: u32,
+    #[serde(default)]
+    pub write_in: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub payload: Option<OptionPayload>,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+// Typed data attached to an option by poll kinds whose options are more than a label.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OptionPayload {
+    TimeSlot(TimeSlot),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSlot {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+impl OptionItem {
+    pub fn time_slot(&self) -> Option<&TimeSlot> {
+        match &self.payload {
+            Some(OptionPayload::TimeSlot(slot)) => Some(slot),
+            None => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Availability {
+    Yes,
+    IfNeedBe,
+    #[default]
+    No,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotAnswer {
+    pub option_id: String,
+    pub availability: Availability,
+}
+
+// One participant's answers to every slot of a scheduling poll. Submitting again
+// replaces the previous ballot.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailabilityBallot {
+    pub username: String,
+    pub answers: Vec<SlotAnswer>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct VoteHistory {
     pub username: String,
@@ -37,6 +464,7 @@ pub struct PollResults {
     title: String,
     options: Vec<ResultsOptionItem>,
     total_votes: u32,
+    guest_votes: u32,
     time_elapsed: String,
 }
 
@@ -46,6 +474,7 @@ pub struct ResultsOptionItem {
     option_id: String,
     text: String,
     votes: u32,
+    guest_votes: u32,
     percentage: u32,
 }
 
@@ -55,3 +484,125 @@ pub struct PollQueryParams {
     pub closed: Option<bool>,
     pub creator: Option<String>,
 }
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionParams {
+    pub poll_id: Option<String>,
+    pub session_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct PageParams {
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct PollSearchParams {
+    pub q: String,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PollSearchResults {
+    pub query: String,
+    pub page: u64,
+    pub limit: u64,
+    pub total: u64,
+    pub results: Vec<PollSearchHit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PollSearchHit {
+    pub poll_id: String,
+    pub username: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub score: f64,
+    pub highlights: Vec<SearchHighlight>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHighlight {
+    pub field: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PollRevision {
+    pub revision_id: String,
+    pub poll_id: String,
+    pub username: String,
+    pub created_at: DateTime<Utc>,
+    pub changes: Vec<RevisionChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collaborator_roles() {
+        let poll = Poll {
+            username: "owner".to_string(),
+            visibility: PollVisibility::Private,
+            results_visibility: ResultsVisibility::AfterClose,
+            is_active: true,
+            collaborators: vec![
+                Collaborator {
+                    username: "editor".to_string(),
+                    role: PollRole::Editor,
+                    added_at: Utc::now(),
+                },
+                Collaborator {
+                    username: "viewer".to_string(),
+                    role: PollRole::Viewer,
+                    added_at: Utc::now(),
+                },
+            ],
+            participants: vec!["participant".to_string()],
+            ..Default::default()
+        };
+
+        assert!(poll.has_role(Some("owner"), PollRole::Owner));
+        assert!(poll.has_role(Some("editor"), PollRole::Editor));
+        assert!(!poll.has_role(Some("editor"), PollRole::Owner));
+        assert!(!poll.has_role(Some("viewer"), PollRole::Editor));
+        assert!(!poll.has_role(None, PollRole::Viewer));
+
+        assert!(poll.can_view(Some("viewer")));
+        assert!(poll.can_see_results(Some("viewer"), false));
+        assert!(!poll.can_view(Some("stranger")));
+
+        assert_eq!(
+            poll.for_viewer(Some("viewer"), false).collaborators.len(),
+            2
+        );
+        assert!(poll
+            .for_viewer(Some("participant"), true)
+            .collaborators
+            .is_empty());
+        assert_eq!(poll.for_viewer(Some("editor"), false).participants.len(), 1);
+        assert!(poll
+            .for_viewer(Some("participant"), true)
+            .participants
+            .is_empty());
+        assert!(poll.for_viewer(None, true).participants.is_empty());
+    }
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// A user's complaint about a poll, waiting in the moderation queue until an admin acts
+// on it. Every status change is kept in `history`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub report_id: String,
+    pub poll_id: String,
+    // Title of the poll when it was reported, so moderators see what was flagged.
+    pub poll_title: String,
+    pub reporter: String,
+    pub category: ReportCategory,
+    pub comment: Option<String>,
+    pub status: ReportStatus,
+    pub history: Vec<ReportTransition>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportCategory {
+    Spam,
+    Harassment,
+    HateSpeech,
+    Violence,
+    SexualContent,
+    Misinformation,
+    Other,
+    // Filed automatically when the content filter flags a poll for review.
+    ContentFilter,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    #[default]
+    Open,
+    Dismissed,
+    Hidden,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportAction {
+    Dismiss,
+    Hide,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportTransition {
+    pub from: ReportStatus,
+    pub to: ReportStatus,
+    pub by: String,
+    pub note: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportPage {
+    pub page: u64,
+    pub limit: u64,
+    pub total: u64,
+    pub reports: Vec<Report>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct ReportQueueParams {
+    pub status: Option<ReportStatus>,
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
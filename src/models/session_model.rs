@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// A live presentation: an ordered list of polls the presenter moves through. Only the
+// poll at `current_index` accepts votes; before the first move nothing does.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub session_id: String,
+    pub username: String,
+    pub title: String,
+    pub poll_ids: Vec<String>,
+    #[serde(default)]
+    pub current_index: Option<usize>,
+    pub join_code: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Session {
+    pub fn current_poll_id(&self) -> Option<&str> {
+        if !self.is_active {
+            return None;
+        }
+
+        self.current_index
+            .and_then(|index| self.poll_ids.get(index))
+            .map(String::as_str)
+    }
+
+    pub fn state(&self) -> SessionState {
+        SessionState {
+            session_id: self.session_id.clone(),
+            title: self.title.clone(),
+            join_code: self.join_code.clone(),
+            is_active: self.is_active,
+            current_index: self.current_index,
+            total_polls: self.poll_ids.len(),
+            current_poll_id: self.current_poll_id().map(str::to_string),
+        }
+    }
+}
+
+// What the audience sees of a session: where the presenter is, but not the polls to come.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    pub session_id: String,
+    pub title: String,
+    pub join_code: String,
+    pub is_active: bool,
+    pub current_index: Option<usize>,
+    pub total_polls: usize,
+    pub current_poll_id: Option<String>,
+}
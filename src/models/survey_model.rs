@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use super::poll_model::OptionItem;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Survey {
+    pub survey_id: String,
+    pub username: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub questions: Vec<SurveyQuestion>,
+    pub is_active: bool,
+    #[serde(default)]
+    pub respondents: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum QuestionKind {
+    #[default]
+    SingleChoice,
+    Rating,
+    FreeText,
+}
+
+// Choice and rating questions keep their tally in `options`, exactly like a poll. A rating
+// question has one option per point of its scale, with the value as option id.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SurveyQuestion {
+    pub question_id: String,
+    pub kind: QuestionKind,
+    pub prompt: String,
+    #[serde(default)]
+    pub options: Vec<OptionItem>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+// One respondent's answers to the whole survey, kept so free-text answers and exports
+// are available after the tallies have been updated.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SurveyResponse {
+    pub response_id: String,
+    pub survey_id: String,
+    pub username: String,
+    pub answers: Vec<SurveyAnswer>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SurveyAnswer {
+    pub question_id: String,
+    pub option_id: Option<String>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SurveyResults {
+    pub survey_id: String,
+    pub title: String,
+    pub total_responses: usize,
+    pub questions: Vec<QuestionResults>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionResults {
+    pub question_id: String,
+    pub kind: QuestionKind,
+    pub prompt: String,
+    #[schema(value_type = Option<Object>)]
+    pub tally: Option<Value>,
+    pub average: Option<f64>,
+    pub answers: Vec<String>,
+}
@@ -7,12 +7,47 @@ use webauthn_rs::prelude::Passkey;
 pub struct User {
     pub username: String,
     pub sk: serde_json::Value,
+    #[serde(default)]
+    pub role: UserRole,
+    // Disabled accounts can no longer sign in or use their existing sessions.
+    #[serde(default)]
+    pub disabled: bool,
 }
 impl User {
-    pub fn init(username: &String, sk: &Passkey) -> Self {
+    pub fn init(username: &str, sk: &Passkey) -> Self {
         User {
-            username: username.clone(),
+            username: username.to_string(),
             sk: serde_json::to_value(sk.clone()).unwrap(),
+            role: UserRole::default(),
+            disabled: false,
+        }
+    }
+}
+
+// Site-wide role, carried in the session token.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum UserRole {
+    #[default]
+    User,
+    Admin,
+}
+
+// What admins see of an account, without its credentials.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSummary {
+    pub username: String,
+    pub role: UserRole,
+    pub disabled: bool,
+}
+
+impl From<User> for UserSummary {
+    fn from(user: User) -> Self {
+        UserSummary {
+            username: user.username,
+            role: user.role,
+            disabled: user.disabled,
         }
     }
 }
@@ -37,3 +72,21 @@ pub struct UserLoginState {
     pub username: String,
     pub state: serde_json::Value,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_users_stored_before_roles_are_regular_users() {
+        let user: User = serde_json::from_value(serde_json::json!({
+            "username": "alice",
+            "sk": {}
+        }))
+        .unwrap();
+
+        let summary = UserSummary::from(user);
+        assert_eq!(summary.role, UserRole::User);
+        assert!(!summary.disabled);
+    }
+}
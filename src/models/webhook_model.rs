@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// An endpoint notified of poll events. Webhooks registered with a `poll_id` receive the
+// events of that poll; without one they receive the events of every poll the owner
+// created.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub webhook_id: String,
+    pub owner: String,
+    pub poll_id: Option<String>,
+    pub url: String,
+    // Key of the HMAC-SHA256 signature sent with every delivery. Only returned once,
+    // when the webhook is created.
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.events.contains(&event)
+    }
+}
+
+// A webhook as listed to its owner, without its secret.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSummary {
+    pub webhook_id: String,
+    pub poll_id: Option<String>,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Webhook> for WebhookSummary {
+    fn from(webhook: Webhook) -> Self {
+        WebhookSummary {
+            webhook_id: webhook.webhook_id,
+            poll_id: webhook.poll_id,
+            url: webhook.url,
+            events: webhook.events,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    PollCreated,
+    VoteCast,
+    PollClosed,
+    PollReset,
+}
+
+impl WebhookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEvent::PollCreated => "poll_created",
+            WebhookEvent::VoteCast => "vote_cast",
+            WebhookEvent::PollClosed => "poll_closed",
+            WebhookEvent::PollReset => "poll_reset",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    #[default]
+    Pending,
+    Delivered,
+    Failed,
+}
+
+// One event queued for one webhook. The body is stored as sent so redeliveries carry
+// exactly the same payload.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    pub poll_id: String,
+    pub body: String,
+    pub status: DeliveryStatus,
+    pub attempts: Vec<DeliveryAttempt>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    // Set while a dispatcher is sending the delivery, so it is not picked up twice.
+    #[serde(default)]
+    pub locked_until: Option<DateTime<Utc>>,
+    // The delivery this one was created from by a redelivery request.
+    pub redelivery_of: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAttempt {
+    pub attempted_at: DateTime<Utc>,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+}
+
+impl DeliveryAttempt {
+    pub fn succeeded(&self) -> bool {
+        self.status_code
+            .is_some_and(|code| (200..300).contains(&code))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryPage {
+    pub page: u64,
+    pub limit: u64,
+    pub total: u64,
+    pub deliveries: Vec<WebhookDelivery>,
+}
@@ -0,0 +1,580 @@
+use actix_web::{
+    web::{self, Data, Path, Query},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use std::sync::Mutex;
+
+use crate::{
+    config::{admin_config::AdminError, poll_config::PollError, user_config::Error as UserError},
+    db::mongodb_repository::MongoDB,
+    middlewares::jwt_middleware::{admin_middleware, claims_from_request},
+    models::{
+        audit_model::{AuditAction, AuditLogPage},
+        broadcaster_model::Broadcaster,
+        poll_model::{PageParams, Poll},
+        report_model::{
+            ReportAction, ReportPage, ReportQueueParams, ReportStatus, ReportTransition,
+        },
+        user_model::{UserRole, UserSummary},
+        webhook_model::WebhookEvent,
+    },
+    services::{
+        poll_service::{poll_results, send_quiz_leaderboard},
+        webhook_service::queue_webhook_event,
+    },
+    utils::{
+        admin_utility::audit_entry,
+        report_utility::next_report_status,
+        types::{ReportResolution, UserRoleChange},
+    },
+};
+
+const DEFAULT_AUDIT_PAGE_SIZE: u64 = 50;
+const MAX_AUDIT_PAGE_SIZE: u64 = 200;
+
+const DEFAULT_REPORT_PAGE_SIZE: u64 = 20;
+const MAX_REPORT_PAGE_SIZE: u64 = 100;
+
+// The admin middleware has already checked the token, so the claims are present.
+fn admin_from_request(req: &HttpRequest) -> String {
+    claims_from_request(req)
+        .map(|claims| claims.sub)
+        .unwrap_or_default()
+}
+
+fn map_poll_error(err: PollError) -> AdminError {
+    match err {
+        PollError::PollNotFound(poll_id) => AdminError::PollNotFound(poll_id),
+        err => AdminError::GeneralError(err.to_string()),
+    }
+}
+
+fn map_user_error(err: UserError) -> AdminError {
+    match err {
+        UserError::UserNotFound(username) => AdminError::UserNotFound(username),
+        err => AdminError::GeneralError(err.to_string()),
+    }
+}
+
+async fn get_poll_utility(db: &Data<MongoDB>, id: &str) -> Result<Poll, AdminError> {
+    db.poll_repository
+        .get_poll_by_id(id)
+        .await
+        .map_err(map_poll_error)?
+        .ok_or_else(|| AdminError::PollNotFound(id.to_string()))
+}
+
+// Admins may not lock themselves out, so at least the acting admin keeps access.
+fn ensure_not_self(admin: &str, username: &str, message: &str) -> Result<(), AdminError> {
+    if admin == username {
+        return Err(AdminError::InvalidRequest(message.to_string()));
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users",
+    responses(
+        (status = 200, description = "Every registered account", body = Vec<UserSummary>),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "listUsers",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn list_users(db: Data<MongoDB>) -> Result<HttpResponse, AdminError> {
+    let users: Vec<UserSummary> = db
+        .user_repository
+        .list_users()
+        .await
+        .map_err(map_user_error)?
+        .into_iter()
+        .map(UserSummary::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(users))
+}
+
+async fn set_user_disabled(
+    req: &HttpRequest,
+    db: &Data<MongoDB>,
+    username: &str,
+    disabled: bool,
+) -> Result<(), AdminError> {
+    let admin = admin_from_request(req);
+    ensure_not_self(&admin, username, "Admins cannot disable their own account.")?;
+
+    db.user_repository
+        .set_disabled(username, disabled)
+        .await
+        .map_err(map_user_error)?;
+
+    let action = if disabled {
+        AuditAction::DisableUser
+    } else {
+        AuditAction::EnableUser
+    };
+    db.audit_repository
+        .record(&audit_entry(&admin, action, username, None))
+        .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{username}/disable",
+    params(
+        ("username" = String, Path, description = "The account to disable")
+    ),
+    responses(
+        (status = 200, description = "Account disabled, its sessions stop working"),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 422, description = "Admins cannot disable themselves", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "disableUser",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn disable_user(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    username: Path<String>,
+) -> Result<HttpResponse, AdminError> {
+    set_user_disabled(&req, &db, &username, true).await?;
+
+    Ok(HttpResponse::Ok().body("User disabled successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{username}/enable",
+    params(
+        ("username" = String, Path, description = "The account to enable again")
+    ),
+    responses(
+        (status = 200, description = "Account enabled"),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 422, description = "Admins cannot change their own account", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "enableUser",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn enable_user(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    username: Path<String>,
+) -> Result<HttpResponse, AdminError> {
+    set_user_disabled(&req, &db, &username, false).await?;
+
+    Ok(HttpResponse::Ok().body("User enabled successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{username}/role",
+    params(
+        ("username" = String, Path, description = "The account to grant or revoke the admin role")
+    ),
+    request_body = UserRoleChange,
+    responses(
+        (status = 200, description = "Role changed, effective from the user's next request"),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 422, description = "Admins cannot change their own role", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "setUserRole",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn set_user_role(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    username: Path<String>,
+    data: web::Json<UserRoleChange>,
+) -> Result<HttpResponse, AdminError> {
+    let admin = admin_from_request(&req);
+    ensure_not_self(&admin, &username, "Admins cannot change their own role.")?;
+
+    db.user_repository
+        .set_role(&username, data.role)
+        .await
+        .map_err(map_user_error)?;
+
+    let action = match data.role {
+        UserRole::Admin => AuditAction::GrantAdmin,
+        UserRole::User => AuditAction::RevokeAdmin,
+    };
+    db.audit_repository
+        .record(&audit_entry(&admin, action, &username, None))
+        .await?;
+
+    Ok(HttpResponse::Ok().body("Role changed successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/polls/{id}/close",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    responses(
+        (status = 200, description = "Poll closed regardless of its owner"),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "forceClosePoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn force_close_poll(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, AdminError> {
+    let admin = admin_from_request(&req);
+    get_poll_utility(&db, &id).await?;
+
+    db.poll_repository
+        .close_poll_by_id(&id)
+        .await
+        .map_err(map_poll_error)?;
+
+    db.audit_repository
+        .record(&audit_entry(&admin, AuditAction::ClosePoll, &id, None))
+        .await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(&db, &poll).await.map_err(map_poll_error)?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+    send_quiz_leaderboard(&db, &broadcaster, &poll)
+        .await
+        .map_err(map_poll_error)?;
+
+    queue_webhook_event(&db, WebhookEvent::PollClosed, &poll).await;
+
+    Ok(HttpResponse::Ok().body("Closed poll successfully."))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/polls/{id}",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    responses(
+        (status = 200, description = "Poll moved to the trash regardless of its owner"),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "forceDeletePoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn force_delete_poll(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, AdminError> {
+    let admin = admin_from_request(&req);
+    let poll = get_poll_utility(&db, &id).await?;
+
+    db.poll_repository
+        .soft_delete_poll_by_id(&id)
+        .await
+        .map_err(map_poll_error)?;
+
+    db.audit_repository
+        .record(&audit_entry(
+            &admin,
+            AuditAction::DeletePoll,
+            &id,
+            Some(format!("Owned by '{}'.", poll.username)),
+        ))
+        .await?;
+
+    broadcaster.lock().unwrap().send_poll_deleted(&poll);
+
+    Ok(HttpResponse::Ok().body("Poll deleted successfully."))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit",
+    params(
+        ("page" = Option<u64>, Query, description = "Page number, starting at 1"),
+        ("limit" = Option<u64>, Query, description = "Number of entries per page")
+    ),
+    responses(
+        (status = 200, description = "Admin actions, newest first", body = AuditLogPage),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "getAuditLog",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_audit_log(
+    db: Data<MongoDB>,
+    query: Query<PageParams>,
+) -> Result<HttpResponse, AdminError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_PAGE_SIZE)
+        .clamp(1, MAX_AUDIT_PAGE_SIZE);
+
+    let (total, entries) = db
+        .audit_repository
+        .get_entries((page - 1) * limit, limit as i64)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(AuditLogPage {
+        page,
+        limit,
+        total,
+        entries,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/reports",
+    params(
+        ("status" = Option<ReportStatus>, Query, description = "Only reports in this status, for example `open` for the moderation queue"),
+        ("page" = Option<u64>, Query, description = "Page number, starting at 1"),
+        ("limit" = Option<u64>, Query, description = "Number of reports per page")
+    ),
+    responses(
+        (status = 200, description = "Reports, oldest first", body = ReportPage),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "getReports",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_reports(
+    db: Data<MongoDB>,
+    query: Query<ReportQueueParams>,
+) -> Result<HttpResponse, AdminError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_REPORT_PAGE_SIZE)
+        .clamp(1, MAX_REPORT_PAGE_SIZE);
+
+    let (total, reports) = db
+        .report_repository
+        .get_reports(query.status, (page - 1) * limit, limit as i64)
+        .await
+        .map_err(map_poll_error)?;
+
+    Ok(HttpResponse::Ok().json(ReportPage {
+        page,
+        limit,
+        total,
+        reports,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/reports/{id}",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the report")
+    ),
+    request_body = ReportResolution,
+    responses(
+        (status = 200, description = "Report resolved, the poll hidden or deleted as asked", body = Report),
+        (status = 403, description = "Caller is not a site admin", body = ErrorResponse),
+        (status = 404, description = "Report not found", body = ErrorResponse),
+        (status = 409, description = "The report cannot take this action from its current status", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Admin",
+    operation_id = "resolveReport",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn resolve_report(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<ReportResolution>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, AdminError> {
+    let admin = admin_from_request(&req);
+
+    let report = db
+        .report_repository
+        .get_report_by_id(&id)
+        .await
+        .map_err(map_poll_error)?
+        .ok_or_else(|| AdminError::ReportNotFound(id.to_string()))?;
+
+    let to = next_report_status(report.status, data.action).ok_or_else(|| {
+        AdminError::InvalidTransition(format!(
+            "A {:?} report cannot be moved to {:?}.",
+            report.status, data.action
+        ))
+    })?;
+
+    let transition = ReportTransition {
+        from: report.status,
+        to,
+        by: admin.clone(),
+        note: data.note.clone(),
+        at: Utc::now(),
+    };
+
+    if !db
+        .report_repository
+        .transition_report(&id, &transition)
+        .await
+        .map_err(map_poll_error)?
+    {
+        return Err(AdminError::InvalidTransition(
+            "The report was resolved by someone else in the meantime.".to_string(),
+        ));
+    }
+
+    let poll_id = report.poll_id.as_str();
+    let details = Some(format!("Report '{}'.", report.report_id));
+    let poll = db
+        .poll_repository
+        .get_poll_by_id(poll_id)
+        .await
+        .map_err(map_poll_error)?;
+
+    let audit = match data.action {
+        ReportAction::Dismiss if report.status == ReportStatus::Hidden => {
+            db.report_repository
+                .transition_poll_reports(poll_id, ReportStatus::Hidden, &transition)
+                .await
+                .map_err(map_poll_error)?;
+            db.poll_repository
+                .set_poll_hidden(poll_id, false)
+                .await
+                .map_err(map_poll_error)?;
+
+            if let Some(poll) = poll {
+                let poll = Poll {
+                    hidden: false,
+                    ..poll
+                };
+                broadcaster.lock().unwrap().send_updated_poll(&poll);
+            }
+
+            audit_entry(&admin, AuditAction::UnhidePoll, poll_id, details)
+        }
+        ReportAction::Dismiss => {
+            audit_entry(&admin, AuditAction::DismissReport, &report.report_id, None)
+        }
+        ReportAction::Hide => {
+            db.report_repository
+                .transition_poll_reports(poll_id, ReportStatus::Open, &transition)
+                .await
+                .map_err(map_poll_error)?;
+            db.poll_repository
+                .set_poll_hidden(poll_id, true)
+                .await
+                .map_err(map_poll_error)?;
+
+            if let Some(poll) = poll {
+                let poll = Poll {
+                    hidden: true,
+                    ..poll
+                };
+                broadcaster.lock().unwrap().send_poll_hidden(&poll);
+            }
+
+            audit_entry(&admin, AuditAction::HidePoll, poll_id, details)
+        }
+        ReportAction::Delete => {
+            for from in [ReportStatus::Open, ReportStatus::Hidden] {
+                db.report_repository
+                    .transition_poll_reports(poll_id, from, &transition)
+                    .await
+                    .map_err(map_poll_error)?;
+            }
+            // The poll stays hidden, so restoring it from the trash does not bring it back
+            // to the listings.
+            db.poll_repository
+                .set_poll_hidden(poll_id, true)
+                .await
+                .map_err(map_poll_error)?;
+            db.poll_repository
+                .soft_delete_poll_by_id(poll_id)
+                .await
+                .map_err(map_poll_error)?;
+
+            if let Some(poll) = poll {
+                broadcaster.lock().unwrap().send_poll_deleted(&poll);
+            }
+
+            audit_entry(&admin, AuditAction::DeletePoll, poll_id, details)
+        }
+    };
+
+    db.audit_repository.record(&audit).await?;
+
+    let report = db
+        .report_repository
+        .get_report_by_id(&id)
+        .await
+        .map_err(map_poll_error)?
+        .ok_or_else(|| AdminError::ReportNotFound(id.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+pub fn init(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/admin")
+            .wrap(actix_web::middleware::from_fn(admin_middleware))
+            .route("/users", web::get().to(list_users))
+            .route("/users/{username}/disable", web::post().to(disable_user))
+            .route("/users/{username}/enable", web::post().to(enable_user))
+            .route("/users/{username}/role", web::post().to(set_user_role))
+            .route("/polls/{id}/close", web::post().to(force_close_poll))
+            .route("/polls/{id}", web::delete().to(force_delete_poll))
+            .route("/reports", web::get().to(get_reports))
+            .route("/reports/{id}", web::post().to(resolve_report))
+            .route("/audit", web::get().to(get_audit_log)),
+    );
+}
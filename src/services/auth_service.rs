@@ -1,4 +1,8 @@
-use crate::models::user_model::{User, UserRegistrationState};
+use crate::config::{config::AppConfig, user_config::Error};
+use crate::models::audit_model::AuditAction;
+use crate::models::user_model::{User, UserRegistrationState, UserRole};
+use crate::utils::admin_utility::{audit_entry, needs_admin_bootstrap, CONFIG_ACTOR};
+use crate::utils::chat_utility::is_chat_username;
 use crate::utils::jwt_token_generation::Claims;
 use crate::{db::mongodb_repository::MongoDB, models::user_model::UserLoginState};
 use actix_web::cookie::time::Duration;
@@ -19,36 +23,23 @@ async fn register_start(
     username: Path<String>,
     webauthn: Data<Webauthn>,
     db: Data<MongoDB>,
-) -> impl Responder {
-    match db.user_repository.find_user(&username).await {
-        Ok(Some(_)) => {
-            return HttpResponse::Conflict().body("User is already registered.");
-        }
-        Ok(None) => {}
-        Err(_) => {
-            return HttpResponse::InternalServerError().body("Error checking user registration.");
-        }
+) -> Result<HttpResponse, Error> {
+    // Names of chat users are reserved for the chat integration.
+    if is_chat_username(&username) || db.user_repository.find_user(&username).await?.is_some() {
+        return Err(Error::UserAlreadyExists(username.to_string()));
     }
 
     let user_unique_id = Uuid::new_v4();
 
-    let (ccr, reg_state) =
-        match webauthn.start_passkey_registration(user_unique_id, &username, &username, None) {
-            Ok(result) => result,
-            Err(e) => {
-                info!("Error starting passkey registration -> {:?}", e);
-                return HttpResponse::InternalServerError()
-                    .body("Failed to start registration process.");
-            }
-        };
+    let (ccr, reg_state) = webauthn
+        .start_passkey_registration(user_unique_id, &username, &username, None)
+        .map_err(|e| {
+            info!("Error starting passkey registration -> {:?}", e);
+            Error::GeneralError("Failed to start registration process.".to_string())
+        })?;
 
-    let reg_state_value = match serde_json::to_value(&reg_state) {
-        Ok(value) => value,
-        Err(_) => {
-            return HttpResponse::InternalServerError()
-                .body("Failed to serialize registration state.");
-        }
-    };
+    let reg_state_value = serde_json::to_value(&reg_state)
+        .map_err(|_| Error::GeneralError("Failed to serialize registration state.".to_string()))?;
 
     let user_reg_state = UserRegistrationState {
         user_id: user_unique_id.to_string(),
@@ -56,12 +47,9 @@ async fn register_start(
         state: reg_state_value,
     };
 
-    db.user_repository
-        .store_reg_state(user_reg_state)
-        .await
-        .unwrap();
+    db.user_repository.store_reg_state(user_reg_state).await?;
 
-    HttpResponse::Ok().json(ccr)
+    Ok(HttpResponse::Ok().json(ccr))
 }
 
 #[post("/register/finish/{username}")]
@@ -70,53 +58,66 @@ async fn register_finish(
     webauthn: Data<Webauthn>,
     username: Path<String>,
     db: Data<MongoDB>,
-) -> impl Responder {
+) -> Result<HttpResponse, Error> {
     let user_reg_state = match db.user_repository.get_reg_state(&username).await {
         Ok(Some(reg_state)) => reg_state,
         Ok(None) => {
-            return HttpResponse::Unauthorized().body("Registration state not found for the user.");
+            return Err(Error::Unauthorized(
+                "Registration state not found for the user.".to_string(),
+            ));
         }
         Err(err) => {
             println!("{:?}", err);
-            return HttpResponse::InternalServerError()
-                .body("Error while retrieving registration state.");
+            return Err(err);
         }
     };
 
-    let reg_state = match serde_json::from_value(user_reg_state.state.clone()) {
-        Ok(reg) => reg,
-        Err(_) => {
-            return HttpResponse::InternalServerError()
-                .body("Failed to deserialize the registration state.");
-        }
-    };
+    let reg_state = serde_json::from_value(user_reg_state.state.clone()).map_err(|_| {
+        Error::GeneralError("Failed to deserialize the registration state.".to_string())
+    })?;
 
     let sk = match webauthn.finish_passkey_registration(&req, &reg_state) {
         Ok(passkey) => passkey,
         Err(e) => {
             println!("Error during registration finish -> {:?}", e);
-            if let Err(_) = db.user_repository.delete_reg_state(&username).await {
-                return HttpResponse::InternalServerError()
-                    .body("Error registering user, and failed to clean up registration state.");
+            if db
+                .user_repository
+                .delete_reg_state(&username)
+                .await
+                .is_err()
+            {
+                return Err(Error::GeneralError(
+                    "Error registering user, and failed to clean up registration state."
+                        .to_string(),
+                ));
             }
-            return HttpResponse::BadRequest()
-                .body("Failed to finish the passkey registration process.");
+            return Err(Error::AuthenticationFailed(
+                "Failed to finish the passkey registration process.".to_string(),
+            ));
         }
     };
 
     let user = User::init(&username, &sk);
 
-    if let Err(_) = db.user_repository.insert_user(&user).await {
-        return HttpResponse::InternalServerError()
-            .body("Failed to insert user data into the database. Please try registering again.");
+    if db.user_repository.insert_user(&user).await.is_err() {
+        return Err(Error::GeneralError(
+            "Failed to insert user data into the database. Please try registering again."
+                .to_string(),
+        ));
     }
 
-    if let Err(_) = db.user_repository.delete_reg_state(&username).await {
-        return HttpResponse::InternalServerError()
-            .body("User registered successfully, but failed to clean up registration state.");
+    if db
+        .user_repository
+        .delete_reg_state(&username)
+        .await
+        .is_err()
+    {
+        return Err(Error::GeneralError(
+            "User registered successfully, but failed to clean up registration state.".to_string(),
+        ));
     }
 
-    HttpResponse::Ok().body("User registered successfully.")
+    Ok(HttpResponse::Ok().body("User registered successfully."))
 }
 
 #[post("/login/start/{username}")]
@@ -124,14 +125,18 @@ async fn authentication_start(
     username: Path<String>,
     webauthn: Data<Webauthn>,
     db: Data<MongoDB>,
-) -> impl Responder {
-    let user_credentials = match db.user_repository.get_user_credentials(&username).await {
-        Ok(credentials) => credentials,
-        Err(_) => {
+) -> Result<HttpResponse, Error> {
+    let user_credentials = db
+        .user_repository
+        .get_user_credentials(&username)
+        .await
+        .inspect_err(|_| {
             info!("User not found during authentication start: {}", username);
-            return HttpResponse::NotFound().body("User not found.");
-        }
-    };
+        })?;
+
+    if user_credentials.disabled {
+        return Err(Error::AccountDisabled(username.to_string()));
+    }
 
     let mut allow_credentials: Vec<Passkey> = Vec::new();
     match serde_json::from_value(user_credentials.sk) {
@@ -143,39 +148,37 @@ async fn authentication_start(
                 "Failed to deserialize user credentials for {}: {:?}",
                 username, err
             );
-            return HttpResponse::InternalServerError()
-                .body("Failed to deserialize user credentials.");
+            return Err(Error::GeneralError(
+                "Failed to deserialize user credentials.".to_string(),
+            ));
         }
     };
 
-    let (rcr, auth_state) = match webauthn.start_passkey_authentication(&allow_credentials) {
-        Ok(result) => result,
-        Err(e) => {
+    let (rcr, auth_state) = webauthn
+        .start_passkey_authentication(&allow_credentials)
+        .map_err(|e| {
             info!("Failed to start authentication for {}: {:?}", username, e);
-            return HttpResponse::InternalServerError().body("Authentication challenge failed.");
-        }
-    };
+            Error::GeneralError("Authentication challenge failed.".to_string())
+        })?;
 
-    let login_state_value = match serde_json::to_value(&auth_state) {
-        Ok(value) => value,
-        Err(_) => {
-            info!("Failed to serialize authentication state for {}", username);
-            return HttpResponse::InternalServerError()
-                .body("Failed to serialize authentication state.");
-        }
-    };
+    let login_state_value = serde_json::to_value(&auth_state).map_err(|_| {
+        info!("Failed to serialize authentication state for {}", username);
+        Error::GeneralError("Failed to serialize authentication state.".to_string())
+    })?;
 
     let login_state = UserLoginState {
         username: username.clone(),
         state: login_state_value,
     };
 
-    if let Err(err) = db.user_repository.store_login_state(login_state).await {
-        info!("Failed to store login state for {}: {:?}", username, err);
-        return HttpResponse::InternalServerError().body("Failed to store login state.");
-    }
+    db.user_repository
+        .store_login_state(login_state)
+        .await
+        .inspect_err(|err| {
+            info!("Failed to store login state for {}: {:?}", username, err);
+        })?;
 
-    HttpResponse::Ok().json(rcr)
+    Ok(HttpResponse::Ok().json(rcr))
 }
 
 #[post("/login/finish/{username}")]
@@ -184,35 +187,34 @@ async fn authentication_finish(
     webauthn: Data<Webauthn>,
     username: Path<String>,
     db: Data<MongoDB>,
-) -> impl Responder {
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, Error> {
     let user_login_state = match db.user_repository.get_login_state(&username).await {
         Ok(Some(reg_state)) => reg_state,
         Ok(None) => {
             info!("No login state found for user: {}", username);
-            return HttpResponse::Unauthorized().body("User doesn't have an active login state.");
+            return Err(Error::Unauthorized(
+                "User doesn't have an active login state.".to_string(),
+            ));
         }
         Err(err) => {
             info!(
                 "Error retrieving login state for user {}: {:?}",
                 username, err
             );
-            return HttpResponse::InternalServerError().body("Failed to retrieve login state.");
+            return Err(err);
         }
     };
 
     let state = user_login_state.state.clone();
 
-    let auth_state = match serde_json::from_value(state) {
-        Ok(reg) => reg,
-        Err(_) => {
-            info!(
-                "Failed to deserialize authentication state for user: {}",
-                username
-            );
-            return HttpResponse::InternalServerError()
-                .body("Failed to deserialize authentication state.");
-        }
-    };
+    let auth_state = serde_json::from_value(state).map_err(|_| {
+        info!(
+            "Failed to deserialize authentication state for user: {}",
+            username
+        );
+        Error::GeneralError("Failed to deserialize authentication state.".to_string())
+    })?;
 
     let _auth_result = match webauthn.finish_passkey_authentication(&auth, &auth_state) {
         Ok(result) => result,
@@ -226,9 +228,13 @@ async fn authentication_finish(
                     "Authentication Failed , and error deleting login state for user {}: {:?}",
                     username, err
                 );
-                return HttpResponse::InternalServerError().body("Failed to clean up login state.");
+                return Err(Error::LoginStateError(
+                    "Failed to clean up login state.".to_string(),
+                ));
             }
-            return HttpResponse::BadRequest().body("Authentication failed.");
+            return Err(Error::AuthenticationFailed(
+                "Authentication failed.".to_string(),
+            ));
         }
     };
 
@@ -237,10 +243,39 @@ async fn authentication_finish(
             "Error deleting login state for user {}: {:?}",
             username, err
         );
-        return HttpResponse::InternalServerError().body("Failed to clean up login state.");
+        return Err(Error::LoginStateError(
+            "Failed to clean up login state.".to_string(),
+        ));
     }
 
-    let token = Claims::generate_token(&username).unwrap();
+    let user = db.user_repository.get_user_credentials(&username).await?;
+
+    if user.disabled {
+        return Err(Error::AccountDisabled(username.to_string()));
+    }
+
+    let mut role = user.role;
+    let admin_exists = db.user_repository.admin_exists().await?;
+    if needs_admin_bootstrap(app_config.admin_username.as_deref(), &user, admin_exists) {
+        db.user_repository
+            .set_role(&username, UserRole::Admin)
+            .await?;
+        db.audit_repository
+            .record(&audit_entry(
+                CONFIG_ACTOR,
+                AuditAction::GrantAdmin,
+                &username,
+                Some("Bootstrapped from ADMIN_USERNAME.".to_string()),
+            ))
+            .await
+            .map_err(|err| Error::GeneralError(err.to_string()))?;
+
+        info!("Granted the admin role to bootstrap admin: {}", username);
+        role = UserRole::Admin;
+    }
+
+    let token = Claims::generate_token(&username, role)
+        .map_err(|_| Error::GeneralError("Failed to generate token.".to_string()))?;
 
     let cookie = Cookie::build("token", token)
         .path("/")
@@ -252,9 +287,9 @@ async fn authentication_finish(
 
     info!("Authentication successful for user: {}", username);
 
-    HttpResponse::Ok()
+    Ok(HttpResponse::Ok()
         .cookie(cookie)
-        .body("Logged in successfully.")
+        .body("Logged in successfully."))
 }
 
 #[post("/logout")]
@@ -272,13 +307,11 @@ async fn logout() -> impl Responder {
         .body("Logged out successfully")
 }
 
-pub fn init(config: &mut web::ServiceConfig) -> () {
+pub fn init(config: &mut web::ServiceConfig) {
     config
         .service(register_start)
         .service(register_finish)
         .service(authentication_start)
         .service(authentication_finish)
         .service(logout);
-
-    ()
 }
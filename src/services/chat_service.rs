@@ -0,0 +1,235 @@
+use actix_web::{
+    web::{self, Bytes, Data},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use log::{error, warn};
+use serde_json::Value;
+use std::sync::Mutex;
+
+use crate::{
+    config::{
+        chat_config::ChatError,
+        config::{AppConfig, ChatConfig},
+        poll_config::PollError,
+    },
+    db::mongodb_repository::MongoDB,
+    models::{broadcaster_model::Broadcaster, poll_model::Poll},
+    services::poll_service::{cast_vote, create_poll},
+    utils::{
+        chat_utility::{
+            build_poll_message, chat_username, ephemeral_message, parse_poll_command,
+            parse_vote_button_value, verify_chat_signature, CHAT_USAGE, SIGNATURE_HEADER,
+            TIMESTAMP_HEADER,
+        },
+        content_filter::ContentPipeline,
+        types::{ChatActionForm, ChatActionPayload, ChatCommand, VoteOption},
+    },
+};
+
+const BLOCK_ACTIONS: &str = "block_actions";
+
+fn header<'a>(req: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    req.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+// Every request must be signed by the chat platform with the configured secret. The
+// signature covers the raw body, so it is checked before the form is parsed.
+fn verify_request(req: &HttpRequest, body: &[u8], config: &ChatConfig) -> Result<(), ChatError> {
+    let secret = config.signing_secret.as_deref().ok_or_else(|| {
+        ChatError::IntegrationDisabled("No chat signing secret is configured.".to_string())
+    })?;
+
+    let (timestamp, signature) =
+        match (header(req, TIMESTAMP_HEADER), header(req, SIGNATURE_HEADER)) {
+            (Some(timestamp), Some(signature)) => (timestamp, signature),
+            _ => {
+                return Err(ChatError::InvalidSignature(
+                    "The request is not signed.".to_string(),
+                ))
+            }
+        };
+
+    verify_chat_signature(
+        secret,
+        timestamp,
+        signature,
+        body,
+        config.max_request_age_secs,
+        Utc::now(),
+    )
+    .map_err(ChatError::InvalidSignature)
+}
+
+// What the chat user is told when the poll or the vote is refused. Internal errors are
+// logged rather than shown in the channel.
+fn chat_error_text(err: &PollError) -> String {
+    match err {
+        PollError::ValidationError(errors) => errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        PollError::PollNotFound(_) => "This poll no longer exists.".to_string(),
+        PollError::MongoError(_) | PollError::GeneralError(_) => {
+            error!("Chat request failed: {}", err);
+            "Something went wrong, please try again.".to_string()
+        }
+        err => err.to_string(),
+    }
+}
+
+// Everyone in the channel sees the message, so counts follow what an anonymous viewer
+// of the poll may see.
+fn poll_message(poll: &Poll) -> Value {
+    let show_counts = poll.can_see_results(None, false);
+
+    build_poll_message(&poll.for_viewer(None, false), show_counts)
+}
+
+// Chat platforms may ignore the body of the answer to a button callback and only apply
+// messages sent to the callback's `response_url`, so the reply is also sent there.
+fn send_to_response_url(response_url: Option<String>, message: Value) {
+    let response_url = match response_url {
+        Some(response_url) => response_url,
+        None => return,
+    };
+
+    tokio::spawn(async move {
+        let result = reqwest::Client::new()
+            .post(&response_url)
+            .header("Content-Type", "application/json")
+            .body(message.to_string())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        if let Err(err) = result {
+            warn!("Failed to update the chat message: {}", err);
+        }
+    });
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/integrations/chat/command",
+    request_body(content = ChatCommand, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Message with the new poll and its vote buttons, or an ephemeral message explaining why no poll was created"),
+        (status = 400, description = "Malformed command", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired request signature", body = ErrorResponse),
+        (status = 404, description = "Chat integration is not configured", body = ErrorResponse)
+    ),
+    tag = "Integrations",
+    operation_id = "handleChatCommand"
+)]
+async fn handle_chat_command(
+    req: HttpRequest,
+    body: Bytes,
+    db: Data<MongoDB>,
+    app_config: Data<AppConfig>,
+    content_pipeline: Data<ContentPipeline>,
+) -> Result<HttpResponse, ChatError> {
+    verify_request(&req, &body, &app_config.chat)?;
+
+    let command: ChatCommand = serde_urlencoded::from_bytes(&body)
+        .map_err(|err| ChatError::BadRequest(err.to_string()))?;
+
+    let text = command.text.trim();
+    if text.is_empty() || text.eq_ignore_ascii_case("help") {
+        return Ok(HttpResponse::Ok().json(ephemeral_message(CHAT_USAGE)));
+    }
+
+    let username = chat_username(&command.team_id, &command.user_id);
+
+    let data = match parse_poll_command(text) {
+        Ok(data) => data,
+        Err(message) => return Ok(HttpResponse::Ok().json(ephemeral_message(&message))),
+    };
+
+    match create_poll(&db, &app_config, &content_pipeline, &username, &data).await {
+        Ok(poll) => Ok(HttpResponse::Ok().json(poll_message(&poll))),
+        Err(err) => Ok(HttpResponse::Ok().json(ephemeral_message(&chat_error_text(&err)))),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/integrations/chat/actions",
+    request_body(content = ChatActionForm, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Poll message updated with the vote, or an ephemeral message explaining why the vote was refused"),
+        (status = 400, description = "Malformed callback", body = ErrorResponse),
+        (status = 401, description = "Missing, invalid or expired request signature", body = ErrorResponse),
+        (status = 404, description = "Chat integration is not configured", body = ErrorResponse)
+    ),
+    tag = "Integrations",
+    operation_id = "handleChatAction"
+)]
+async fn handle_chat_action(
+    req: HttpRequest,
+    body: Bytes,
+    db: Data<MongoDB>,
+    app_config: Data<AppConfig>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+    content_pipeline: Data<ContentPipeline>,
+) -> Result<HttpResponse, ChatError> {
+    verify_request(&req, &body, &app_config.chat)?;
+
+    let form: ChatActionForm = serde_urlencoded::from_bytes(&body)
+        .map_err(|err| ChatError::BadRequest(err.to_string()))?;
+    let payload: ChatActionPayload = serde_json::from_str(&form.payload)
+        .map_err(|err| ChatError::BadRequest(err.to_string()))?;
+
+    // Other interactions, such as opened menus, need no answer.
+    let vote = payload
+        .actions
+        .first()
+        .filter(|_| payload.kind == BLOCK_ACTIONS)
+        .and_then(|action| parse_vote_button_value(&action.value));
+
+    let (poll_id, option_id) = match vote {
+        Some(vote) => vote,
+        None => return Ok(HttpResponse::Ok().finish()),
+    };
+
+    let username = chat_username(&payload.team.id, &payload.user.id);
+    let data = VoteOption {
+        option_id,
+        write_in: None,
+        proof_of_work: None,
+    };
+
+    let message = match cast_vote(
+        &db,
+        &app_config,
+        &broadcaster,
+        &content_pipeline,
+        &poll_id,
+        &username,
+        &data,
+    )
+    .await
+    {
+        Ok((poll, _)) => {
+            let mut message = poll_message(&poll);
+            message["replace_original"] = Value::Bool(true);
+            message
+        }
+        Err(err) => ephemeral_message(&chat_error_text(&err)),
+    };
+
+    send_to_response_url(payload.response_url, message.clone());
+
+    Ok(HttpResponse::Ok().json(message))
+}
+
+pub fn init(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/integrations/chat")
+            .route("/command", web::post().to(handle_chat_command))
+            .route("/actions", web::post().to(handle_chat_action)),
+    );
+}
@@ -1,3 +1,9 @@
+pub mod admin_service;
 pub mod auth_service;
+pub mod chat_service;
+pub mod organization_service;
 pub mod poll_service;
+pub mod session_service;
 pub mod socket_service;
+pub mod survey_service;
+pub mod webhook_service;
@@ -0,0 +1,322 @@
+use actix_web::{
+    web::{self, Data, Path},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use std::sync::Mutex;
+
+use crate::{
+    config::{config::AppConfig, organization_config::OrganizationError},
+    db::mongodb_repository::MongoDB,
+    middlewares::jwt_middleware::{claims_from_request, jwt_middleware},
+    models::{
+        broadcaster_model::Broadcaster,
+        organization_model::{Organization, OrganizationMember},
+    },
+    services::poll_service::poll_for_viewer,
+    utils::{
+        organization_utility::{build_organization, validate_organization_creation},
+        types::{OrganizationCreation, OrganizationMemberInvite},
+    },
+};
+
+fn viewer_from_request(req: &HttpRequest) -> String {
+    claims_from_request(req)
+        .map(|claims| claims.sub)
+        .unwrap_or_default()
+}
+
+// Organizations are reported as missing to anyone outside them.
+async fn get_member_organization(
+    db: &Data<MongoDB>,
+    id: &str,
+    username: &str,
+) -> Result<Organization, OrganizationError> {
+    db.organization_repository
+        .get_organization_by_id(id)
+        .await?
+        .filter(|organization| organization.role_of(username).is_some())
+        .ok_or_else(|| OrganizationError::OrganizationNotFound(id.to_string()))
+}
+
+fn ensure_admin(organization: &Organization, username: &str) -> Result<(), OrganizationError> {
+    if organization.is_admin(username) {
+        Ok(())
+    } else {
+        Err(OrganizationError::OrganizationUnauthorizedAccess(
+            "Only admins can manage the members of the organization.".to_string(),
+        ))
+    }
+}
+
+// Open SSE streams of the member pick up polls restricted to the organizations they
+// now belong to.
+async fn refresh_member_organizations(
+    db: &Data<MongoDB>,
+    broadcaster: &Data<Mutex<Broadcaster>>,
+    username: &str,
+) -> Result<(), OrganizationError> {
+    let org_ids = db
+        .organization_repository
+        .get_organization_ids_for_user(username)
+        .await?;
+
+    broadcaster
+        .lock()
+        .unwrap()
+        .set_viewer_organizations(username, &org_ids);
+
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/orgs/",
+    request_body = OrganizationCreation,
+    responses(
+        (status = 200, description = "Organization created with the creator as its admin", body = Organization),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 422, description = "Organization failed validation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Organizations",
+    operation_id = "createOrganization",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn create_organization(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    data: web::Json<OrganizationCreation>,
+    app_config: Data<AppConfig>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, OrganizationError> {
+    let username = viewer_from_request(&req);
+
+    validate_organization_creation(&data, &app_config.poll_limits)?;
+
+    let organization = build_organization(&data, &username, Utc::now());
+
+    db.organization_repository
+        .create_organization(&organization)
+        .await?;
+
+    refresh_member_organizations(&db, &broadcaster, &username).await?;
+
+    Ok(HttpResponse::Ok().json(organization))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/orgs/",
+    responses(
+        (status = 200, description = "Organizations the caller belongs to", body = Vec<Organization>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Organizations",
+    operation_id = "getOrganizations",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_organizations(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+) -> Result<HttpResponse, OrganizationError> {
+    let viewer = viewer_from_request(&req);
+
+    let organizations = db
+        .organization_repository
+        .get_organizations_for_user(&viewer)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(organizations))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/orgs/{id}",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the organization")
+    ),
+    responses(
+        (status = 200, description = "Organization and its members", body = Organization),
+        (status = 404, description = "Organization not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Organizations",
+    operation_id = "getOrganization",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_organization(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, OrganizationError> {
+    let viewer = viewer_from_request(&req);
+    let organization = get_member_organization(&db, &id, &viewer).await?;
+
+    Ok(HttpResponse::Ok().json(organization))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/orgs/{id}/polls",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the organization")
+    ),
+    responses(
+        (status = 200, description = "Polls created in the organization", body = Vec<Poll>),
+        (status = 404, description = "Organization not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Organizations",
+    operation_id = "getOrganizationPolls",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_organization_polls(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, OrganizationError> {
+    let viewer = viewer_from_request(&req);
+    let organization = get_member_organization(&db, &id, &viewer).await?;
+
+    let org_polls = db
+        .poll_repository
+        .get_polls_by_organization(&organization.org_id)
+        .await
+        .map_err(|err| OrganizationError::GeneralError(err.to_string()))?;
+
+    let org_ids = [organization.org_id];
+    let mut polls = Vec::new();
+    for poll in org_polls.iter().filter(|poll| {
+        poll.can_view_in_organizations(Some(&viewer), &org_ids)
+            && !poll.is_hidden_from(Some(&viewer))
+    }) {
+        polls.push(
+            poll_for_viewer(&db, &app_config, poll, Some(&viewer))
+                .await
+                .map_err(|err| OrganizationError::GeneralError(err.to_string()))?,
+        );
+    }
+
+    Ok(HttpResponse::Ok().json(polls))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/orgs/{id}/members",
+    request_body = OrganizationMemberInvite,
+    responses(
+        (status = 200, description = "Member added, or their role changed", body = OrganizationMember),
+        (status = 403, description = "Only admins can manage members", body = ErrorResponse),
+        (status = 404, description = "Organization not found", body = ErrorResponse),
+        (status = 409, description = "The organization would be left without admins", body = ErrorResponse),
+        (status = 422, description = "User does not exist", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Organizations",
+    operation_id = "addOrganizationMember",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn add_member(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<OrganizationMemberInvite>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, OrganizationError> {
+    let username = viewer_from_request(&req);
+
+    let organization = get_member_organization(&db, &id, &username).await?;
+    ensure_admin(&organization, &username)?;
+
+    if db
+        .user_repository
+        .find_user(&data.member)
+        .await
+        .map_err(|err| OrganizationError::GeneralError(err.to_string()))?
+        .is_none()
+    {
+        return Err(OrganizationError::InvalidRequest(format!(
+            "User '{}' does not exist.",
+            data.member
+        )));
+    }
+
+    let member = OrganizationMember {
+        username: data.member.clone(),
+        role: data.role,
+        joined_at: Utc::now(),
+    };
+
+    db.organization_repository.set_member(&id, &member).await?;
+
+    refresh_member_organizations(&db, &broadcaster, &member.username).await?;
+
+    Ok(HttpResponse::Ok().json(member))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/orgs/{id}/members/{username}",
+    responses(
+        (status = 200, description = "Member removed successfully"),
+        (status = 403, description = "Only admins can remove other members", body = ErrorResponse),
+        (status = 404, description = "Organization or member not found", body = ErrorResponse),
+        (status = 409, description = "The organization would be left without admins", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Organizations",
+    operation_id = "removeOrganizationMember",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn remove_member(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    path: Path<(String, String)>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, OrganizationError> {
+    let username = viewer_from_request(&req);
+
+    let (id, member) = path.into_inner();
+
+    let organization = get_member_organization(&db, &id, &username).await?;
+
+    // Members may always leave on their own.
+    if username != member {
+        ensure_admin(&organization, &username)?;
+    }
+
+    db.organization_repository
+        .remove_member(&id, &member)
+        .await?;
+
+    refresh_member_organizations(&db, &broadcaster, &member).await?;
+
+    Ok(HttpResponse::Ok().body("Member removed successfully."))
+}
+
+pub fn init(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/orgs")
+            .wrap(actix_web::middleware::from_fn(jwt_middleware))
+            .route("/", web::post().to(create_organization))
+            .route("/", web::get().to(get_organizations))
+            .route("/{id}", web::get().to(get_organization))
+            .route("/{id}/polls", web::get().to(get_organization_polls))
+            .route("/{id}/members", web::post().to(add_member))
+            .route("/{id}/members/{username}", web::delete().to(remove_member)),
+    );
+}
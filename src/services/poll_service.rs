@@ -1,379 +1,2789 @@
 use actix_web::{
-    get,
+    cookie::{time::Duration as CookieDuration, Cookie, SameSite},
+    get, post,
     web::{self, Data, Path, Query},
-    HttpResponse, Responder,
+    HttpRequest, HttpResponse,
 };
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use nanoid::nanoid;
+use serde_json::Value;
 use std::sync::Mutex;
 
 use crate::{
-    db::mongodb_repository::MongoDB,
-    middlewares::jwt_middleware::jwt_middleware,
+    config::{config::AppConfig, error_response::FieldError, poll_config::PollError},
+    db::{challenge_repository::SpentChallenge, mongodb_repository::MongoDB},
+    middlewares::jwt_middleware::{claims_from_request, jwt_middleware},
     models::{
         broadcaster_model::Broadcaster,
-        poll_model::{OptionItem, Poll, PollQueryParams},
+        organization_model::OrganizationRole,
+        poll_model::{
+            AvailabilityBallot, Collaborator, GuestSession, InviteKind, Leaderboard, OptionItem,
+            PageParams, Poll, PollInvite, PollKind, PollQueryParams, PollRevision, PollRole,
+            PollSearchParams, PollSearchResults, PollVisibility, QuizScore, ResultsVisibility,
+            TextAnswer, TextAnswerPage, VoteChallenge,
+        },
+        report_model::{Report, ReportStatus},
+        session_model::Session,
+        webhook_model::WebhookEvent,
     },
+    services::webhook_service::queue_webhook_event,
     utils::{
-        poll_results_utility::calculate_poll_results,
-        types::{PollCreation, UserNameRequest, VoteOption},
+        access_code_utility::{generate_access_code, normalize_access_code},
+        anonymity_utility::participation_marker,
+        content_filter::ContentPipeline,
+        jwt_token_generation::GuestClaims,
+        poll_edit_utility::{apply_poll_edit, PollEditOutcome},
+        poll_results_utility::{
+            calculate_poll_results, calculate_schedule_results, calculate_text_results,
+            calculate_word_frequencies,
+        },
+        pow_utility::{
+            challenge_digest, difficulty_for_rate, issue_challenge, verify_proof_of_work,
+        },
+        quiz_utility::{
+            build_leaderboard, build_quiz_settings, closed_question_scores, score_quiz_answer,
+        },
+        report_utility::{build_filter_report, validate_report, CONTENT_FILTER_REPORTER},
+        schedule_utility::{build_ics_event, build_slot_options, validate_availability},
+        search_utility::{build_search_hit, search_terms},
+        types::{
+            AccessCodeResponse, AvailabilitySubmission, CollaboratorInvite, GuestVote,
+            InviteCreation, JoinPollRequest, OwnershipTransfer, PollCreation, PollEdit,
+            PollReportRequest, ProofOfWork, SlotChoice, TextAnswerModeration, TextAnswerSubmission,
+            VoteOption, WriteInMerge, WriteInVisibility,
+        },
+        validation::{
+            validate_free_text_contents, validate_poll_contents, validate_poll_creation,
+            validate_text_answer, validate_vote_option,
+        },
+        write_in_utility::{
+            apply_write_in_merge, apply_write_in_visibility, find_matching_option,
+            validate_write_in,
+        },
     },
 };
 
-async fn get_poll_utility(db: &Data<MongoDB>, id: &str) -> Option<Poll> {
-    let poll_option = match db.poll_repository.get_poll_by_id(id).await {
-        Ok(poll_option) => poll_option,
-        Err(_) => {
-            return None;
+const DEFAULT_SEARCH_LIMIT: u64 = 10;
+const MAX_SEARCH_LIMIT: u64 = 50;
+
+// Window over which the vote rate driving proof-of-work difficulty is measured.
+const POW_RATE_WINDOW_SECS: i64 = 60;
+
+const GUEST_TOKEN_COOKIE: &str = "guest_token";
+
+const DEFAULT_ANSWER_PAGE_SIZE: u64 = 20;
+const MAX_ANSWER_PAGE_SIZE: u64 = 100;
+
+async fn get_poll_utility(db: &Data<MongoDB>, id: &str) -> Result<Poll, PollError> {
+    db.poll_repository
+        .get_poll_by_id(id)
+        .await?
+        .ok_or_else(|| PollError::PollNotFound(id.to_string()))
+}
+
+// Private polls are reported as missing to anyone who may not see them, so their
+// existence is not leaked. Polls hidden by moderators answer 451 instead.
+async fn get_visible_poll(
+    db: &Data<MongoDB>,
+    id: &str,
+    viewer: Option<&str>,
+) -> Result<Poll, PollError> {
+    let poll = get_poll_utility(db, id).await?;
+
+    if !can_view_poll(db, &poll, viewer).await? {
+        Err(PollError::PollNotFound(id.to_string()))
+    } else if poll.is_hidden_from(viewer) {
+        Err(PollError::PollHidden(id.to_string()))
+    } else {
+        Ok(poll)
+    }
+}
+
+// Polls restricted to an organization are also visible to its members.
+async fn can_view_poll(
+    db: &Data<MongoDB>,
+    poll: &Poll,
+    viewer: Option<&str>,
+) -> Result<bool, PollError> {
+    let org_ids = match (viewer, &poll.org_id) {
+        (Some(viewer), Some(org_id)) if poll.visibility == PollVisibility::Organization => db
+            .organization_repository
+            .get_member_role(org_id, viewer)
+            .await
+            .map_err(|err| PollError::GeneralError(err.to_string()))?
+            .map(|_| org_id.clone())
+            .into_iter()
+            .collect(),
+        _ => vec![],
+    };
+
+    Ok(poll.can_view_in_organizations(viewer, &org_ids))
+}
+
+fn viewer_from_request(req: &HttpRequest) -> Option<String> {
+    claims_from_request(req).map(|claims| claims.sub)
+}
+
+// Voters of anonymous polls are not stored on the poll, so fall back to the
+// participation marker to find out whether the viewer already voted.
+async fn viewer_has_voted(
+    db: &Data<MongoDB>,
+    app_config: &AppConfig,
+    poll: &Poll,
+    viewer: Option<&str>,
+) -> Result<bool, PollError> {
+    match viewer {
+        Some(viewer) if poll.anonymous => {
+            let marker = participation_marker(&app_config.ballot_secret, &poll.poll_id, viewer);
+            db.poll_repository.has_participated(&marker).await
+        }
+        _ => Ok(poll.has_voted(viewer)),
+    }
+}
+
+// Refuses a ballot that cannot be counted before anything is stored for it, so neither
+// a new write-in nor a solved challenge is used up by a vote that fails anyway.
+async fn ensure_ballot_allowed(
+    db: &Data<MongoDB>,
+    app_config: &AppConfig,
+    poll: &Poll,
+    username: &str,
+) -> Result<(), PollError> {
+    if poll.quiz.is_some() && poll.is_question_closed(Utc::now()) {
+        return Err(PollError::PollClosed(
+            "Time is up for this question.".to_string(),
+        ));
+    }
+
+    if !viewer_has_voted(db, app_config, poll, Some(username)).await? {
+        return Ok(());
+    }
+
+    let message = if poll.quiz.is_some() {
+        "Already answered this question, quiz answers cannot be changed."
+    } else if poll.anonymous {
+        "Already voted in this anonymous poll, votes cannot be changed."
+    } else if poll.lock_votes {
+        "Votes in this poll are final and cannot be changed."
+    } else {
+        return Ok(());
+    };
+
+    Err(PollError::AlreadyVotedError(message.to_string()))
+}
+
+pub(crate) async fn poll_for_viewer(
+    db: &Data<MongoDB>,
+    app_config: &AppConfig,
+    poll: &Poll,
+    viewer: Option<&str>,
+) -> Result<Poll, PollError> {
+    let has_voted = viewer_has_voted(db, app_config, poll, viewer).await?;
+
+    Ok(poll.for_viewer(viewer, has_voted))
+}
+
+// Free-text polls have no tally, their results summarize the visible answers instead.
+pub(crate) async fn poll_results(db: &Data<MongoDB>, poll: &Poll) -> Result<Value, PollError> {
+    match poll.kind {
+        PollKind::FreeText => {
+            let answers = db
+                .poll_repository
+                .get_visible_text_answer_texts(&poll.poll_id)
+                .await?;
+            Ok(calculate_text_results(poll, &answers))
+        }
+        PollKind::Schedule => Ok(calculate_schedule_results(poll)),
+        _ => Ok(calculate_poll_results(poll)),
+    }
+}
+
+// Polls presented in a live session only take votes while they are the current poll.
+async fn ensure_open_in_session(db: &Data<MongoDB>, poll: &Poll) -> Result<(), PollError> {
+    let session_id = match &poll.session_id {
+        Some(session_id) => session_id,
+        None => return Ok(()),
+    };
+
+    let session = db
+        .session_repository
+        .get_session_by_id(session_id)
+        .await
+        .map_err(|err| PollError::GeneralError(err.to_string()))?;
+
+    if session.as_ref().and_then(Session::current_poll_id) == Some(poll.poll_id.as_str()) {
+        Ok(())
+    } else {
+        Err(PollError::PollClosed(
+            "This poll is not open in its session right now.".to_string(),
+        ))
+    }
+}
+
+fn available_option(option: &OptionItem) -> Result<String, PollError> {
+    if option.hidden {
+        Err(PollError::PollVoteError(
+            "This option is not available in the poll.".to_string(),
+        ))
+    } else {
+        Ok(option.option_id.clone())
+    }
+}
+
+// Under the flag policy, content caught by the filter is accepted and the poll joins the
+// moderation queue, once for as long as its report stays open.
+async fn flag_content(
+    db: &Data<MongoDB>,
+    poll: &Poll,
+    matches: &[FieldError],
+) -> Result<(), PollError> {
+    if matches.is_empty()
+        || db
+            .report_repository
+            .has_open_report(&poll.poll_id, CONTENT_FILTER_REPORTER)
+            .await?
+    {
+        return Ok(());
+    }
+
+    db.report_repository
+        .create_report(&build_filter_report(poll, matches, Utc::now()))
+        .await
+}
+
+fn poll_text_fields<'a>(
+    title: &'a str,
+    description: Option<&'a str>,
+    options: &'a [OptionItem],
+) -> Vec<(String, &'a str)> {
+    let mut fields = vec![("title".to_string(), title)];
+    if let Some(description) = description {
+        fields.push(("description".to_string(), description));
+    }
+    fields.extend(
+        options
+            .iter()
+            .filter(|option| option.time_slot().is_none())
+            .enumerate()
+            .map(|(index, option)| (format!("options[{}]", index), option.text.as_str())),
+    );
+    fields
+}
+
+// A write-in names either an option the poll already has, or a new option that is only
+// stored once the ballot is about to be cast.
+enum WriteIn {
+    Existing(String),
+    New(OptionItem, Vec<FieldError>),
+}
+
+// Checks a write-in and matches it against the existing options, without storing anything.
+fn prepare_write_in(
+    app_config: &AppConfig,
+    content_pipeline: &ContentPipeline,
+    poll: &Poll,
+    text: &str,
+) -> Result<WriteIn, PollError> {
+    let text = validate_write_in(poll, text, &app_config.poll_limits)?;
+
+    if let Some(option) = find_matching_option(poll, &text) {
+        return available_option(option).map(WriteIn::Existing);
+    }
+
+    let matches = content_pipeline.review([("writeIn".to_string(), text.as_str())])?;
+
+    let option = OptionItem {
+        option_id: nanoid!(10),
+        text,
+        votes: 0,
+        guest_votes: 0,
+        write_in: true,
+        hidden: false,
+        payload: None,
+    };
+
+    Ok(WriteIn::New(option, matches))
+}
+
+// Turns a write-in into the id of the option to vote for, adding a new option and
+// announcing it to subscribers when needed.
+async fn save_write_in(
+    db: &Data<MongoDB>,
+    app_config: &AppConfig,
+    broadcaster: &Data<Mutex<Broadcaster>>,
+    poll: &Poll,
+    write_in: WriteIn,
+) -> Result<String, PollError> {
+    let limits = &app_config.poll_limits;
+
+    let (option, matches) = match write_in {
+        WriteIn::Existing(option_id) => return Ok(option_id),
+        WriteIn::New(option, matches) => (option, matches),
+    };
+
+    if db
+        .poll_repository
+        .add_write_in_option(&poll.poll_id, &option, limits.max_options)
+        .await?
+    {
+        flag_content(db, poll, &matches).await?;
+        broadcaster.lock().unwrap().send_option_added(poll, &option);
+        return Ok(option.option_id);
+    }
+
+    // Someone else added the same write-in first, or the poll ran out of room.
+    let poll = get_poll_utility(db, &poll.poll_id).await?;
+
+    match find_matching_option(&poll, &option.text) {
+        Some(existing) => available_option(existing),
+        None => Err(PollError::ValidationError(vec![FieldError::new(
+            "writeIn",
+            format!("A poll can have at most {} options.", limits.max_options),
+        )])),
+    }
+}
+
+// Stores a moderation change to a write-in like any other edit, with a revision.
+async fn save_write_in_moderation(
+    db: &Data<MongoDB>,
+    broadcaster: &Data<Mutex<Broadcaster>>,
+    poll: &Poll,
+    username: &str,
+    outcome: PollEditOutcome,
+) -> Result<PollRevision, PollError> {
+    db.poll_repository
+        .update_poll_contents(poll, &outcome)
+        .await?;
+
+    let revision = PollRevision {
+        revision_id: nanoid!(10),
+        poll_id: poll.poll_id.clone(),
+        username: username.to_string(),
+        created_at: Utc::now(),
+        changes: outcome.changes,
+    };
+
+    db.poll_repository.insert_revision(&revision).await?;
+
+    let poll = get_poll_utility(db, &poll.poll_id).await?;
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    Ok(revision)
+}
+
+// Admins of the poll's organization manage it as if they owned it.
+pub(crate) async fn ensure_poll_role(
+    db: &Data<MongoDB>,
+    poll: &Poll,
+    username: &str,
+    role: PollRole,
+    message: &str,
+) -> Result<(), PollError> {
+    if poll.has_role(Some(username), role) {
+        return Ok(());
+    }
+
+    if let Some(org_id) = &poll.org_id {
+        let org_role = db
+            .organization_repository
+            .get_member_role(org_id, username)
+            .await
+            .map_err(|err| PollError::GeneralError(err.to_string()))?;
+
+        if org_role == Some(OrganizationRole::Admin) {
+            return Ok(());
+        }
+    }
+
+    Err(PollError::PollUnauthorizedAccess(message.to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/",
+    responses(
+        (status = 200, description = "Successfully fetched all polls", body = Vec<Poll>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getAllPolls"
+)]
+#[get("/")]
+async fn get_all_polls(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, PollError> {
+    let viewer = viewer_from_request(&req);
+
+    let mut polls = Vec::new();
+    for poll in db.poll_repository.get_all_polls().await? {
+        polls.push(poll_for_viewer(&db, &app_config, &poll, viewer.as_deref()).await?);
+    }
+
+    Ok(HttpResponse::Ok().json(polls))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/search",
+    params(
+        ("q" = String, Query, description = "Text to search for in poll titles, descriptions and options"),
+        ("page" = Option<u64>, Query, description = "Page number, starting at 1"),
+        ("limit" = Option<u64>, Query, description = "Number of results per page (max 50)")
+    ),
+    responses(
+        (status = 200, description = "Successfully searched polls", body = PollSearchResults),
+        (status = 400, description = "Empty search query", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "searchPolls"
+)]
+#[get("/polls/search")]
+async fn search_polls(
+    db: Data<MongoDB>,
+    query: Query<PollSearchParams>,
+) -> Result<HttpResponse, PollError> {
+    let params = query.into_inner();
+
+    let terms = search_terms(&params.q);
+    if terms.is_empty() {
+        return Err(PollError::BadRequest(
+            "Search query must not be empty.".to_string(),
+        ));
+    }
+
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let (total, hits) = db
+        .poll_repository
+        .search_polls(&params.q, (page - 1) * limit, limit)
+        .await?;
+
+    let results = hits
+        .into_iter()
+        .map(|(poll, score)| build_search_hit(poll, score, &terms))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(PollSearchResults {
+        query: params.q,
+        page,
+        limit,
+        total,
+        results,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    responses(
+        (status = 200, description = "Successfully fetched poll details", body = Poll),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getPollById"
+)]
+#[get("/polls/{id}")]
+async fn get_poll_by_id(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, PollError> {
+    let viewer = viewer_from_request(&req);
+    let poll = get_visible_poll(&db, &id, viewer.as_deref()).await?;
+    let poll = poll_for_viewer(&db, &app_config, &poll, viewer.as_deref()).await?;
+
+    Ok(HttpResponse::Ok().json(poll))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/challenge",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    responses(
+        (status = 200, description = "Challenge to solve before voting", body = VoteChallenge),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 422, description = "Poll does not require proof of work", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getVoteChallenge"
+)]
+#[get("/polls/{id}/challenge")]
+async fn get_vote_challenge(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, PollError> {
+    let viewer = viewer_from_request(&req);
+    let poll = get_visible_poll(&db, &id, viewer.as_deref()).await?;
+
+    if !poll.require_proof_of_work {
+        return Err(PollError::InvalidRequest(
+            "This poll does not require proof of work.".to_string(),
+        ));
+    }
+
+    let config = &app_config.proof_of_work;
+    let now = Utc::now();
+    let recent_votes = db
+        .challenge_repository
+        .count_spent_since(&poll.poll_id, now - Duration::seconds(POW_RATE_WINDOW_SECS))
+        .await?;
+
+    let difficulty = difficulty_for_rate(recent_votes, config);
+    let expires_at = now + Duration::seconds(config.challenge_ttl_secs);
+
+    Ok(HttpResponse::Ok().json(VoteChallenge {
+        challenge: issue_challenge(&config.secret, &poll.poll_id, difficulty, expires_at),
+        difficulty,
+        expires_at,
+    }))
+}
+
+// Checks the solved challenge sent with a vote and marks it spent, so each solution
+// buys a single ballot.
+async fn spend_proof_of_work(
+    db: &Data<MongoDB>,
+    app_config: &AppConfig,
+    poll: &Poll,
+    proof: Option<&ProofOfWork>,
+) -> Result<(), PollError> {
+    let proof = proof.ok_or_else(|| {
+        PollError::ProofOfWorkFailed("This poll requires a solved challenge.".to_string())
+    })?;
+
+    let now = Utc::now();
+    verify_proof_of_work(
+        &app_config.proof_of_work.secret,
+        &poll.poll_id,
+        &proof.challenge,
+        &proof.solution,
+        now,
+    )
+    .map_err(PollError::ProofOfWorkFailed)?;
+
+    let spent = SpentChallenge {
+        challenge_hash: challenge_digest(&proof.challenge),
+        poll_id: poll.poll_id.clone(),
+        spent_at: now,
+        expires_at: now + Duration::seconds(app_config.proof_of_work.challenge_ttl_secs),
+    };
+
+    if !db.challenge_repository.spend(&spent).await? {
+        return Err(PollError::ProofOfWorkFailed(
+            "Challenge has already been used.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/revisions",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    responses(
+        (status = 200, description = "Successfully fetched poll revisions", body = Vec<PollRevision>),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getPollRevisions"
+)]
+#[get("/polls/{id}/revisions")]
+async fn get_poll_revisions(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, PollError> {
+    let viewer = viewer_from_request(&req);
+    get_visible_poll(&db, &id, viewer.as_deref()).await?;
+
+    let revisions = db.poll_repository.get_revisions_by_poll_id(&id).await?;
+
+    Ok(HttpResponse::Ok().json(revisions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/results",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll"),
+        ("live" = Option<bool>, Query, description = "Filter by live status of the poll"),
+        ("closed" = Option<bool>, Query, description = "Filter by closed status of the poll"),
+        ("creator" = Option<String>, Query, description = "Filter by creator username")
+    ),
+    responses(
+        (status = 200, description = "Successfully fetched poll results", body = PollResults),
+        (status = 400, description = "Query parameters mismatch", body = ErrorResponse),
+        (status = 403, description = "Results are hidden from this viewer for now, or the creator does not own the poll", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getPollResults"
+)]
+#[get("/polls/{id}/results")]
+async fn fetch_results_by_id(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    app_config: Data<AppConfig>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+    query: Query<PollQueryParams>,
+) -> Result<HttpResponse, PollError> {
+    let params = query.into_inner();
+
+    let viewer = viewer_from_request(&req);
+    let poll = get_visible_poll(&db, &id, viewer.as_deref()).await?;
+
+    let has_voted = viewer_has_voted(&db, &app_config, &poll, viewer.as_deref()).await?;
+    if !poll.can_see_results(viewer.as_deref(), has_voted) {
+        let message = match poll.results_visibility {
+            ResultsVisibility::AfterVote => "Results of this poll are shown after you vote.",
+            _ => "Results of this poll are shown once it closes.",
+        };
+        return Err(PollError::PollUnauthorizedAccess(message.to_string()));
+    }
+
+    if let Some(close) = params.closed {
+        if close && !poll.is_active || !close && poll.is_active {
+            return Err(PollError::BadRequest(
+                "Query parameters mismatch, poll status is not specified correctly.".to_string(),
+            ));
+        }
+    }
+
+    if let Some(live) = params.live {
+        if live && !poll.is_active || !live && poll.is_active {
+            return Err(PollError::BadRequest(
+                "Query parameters mismatch, poll status is not specified correctly.".to_string(),
+            ));
+        }
+    }
+
+    if let Some(creator_id) = params.creator {
+        if poll.username != creator_id {
+            return Err(PollError::PollUnauthorizedAccess(
+                "Query parameters mismatch, provided creator is not owner of this poll."
+                    .to_string(),
+            ));
+        }
+    }
+
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/answers",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the free-text poll"),
+        ("page" = Option<u64>, Query, description = "Page number, starting at 1"),
+        ("limit" = Option<u64>, Query, description = "Number of answers per page")
+    ),
+    responses(
+        (status = 200, description = "Page of answers, pinned first, with a word-frequency summary", body = TextAnswerPage),
+        (status = 403, description = "Answers are hidden from this viewer for now", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 422, description = "Poll is not a free-text poll", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getPollAnswers"
+)]
+#[get("/polls/{id}/answers")]
+async fn get_text_answers(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    app_config: Data<AppConfig>,
+    query: Query<PageParams>,
+) -> Result<HttpResponse, PollError> {
+    let viewer = viewer_from_request(&req);
+    let poll = get_visible_poll(&db, &id, viewer.as_deref()).await?;
+
+    if poll.kind != PollKind::FreeText {
+        return Err(PollError::InvalidRequest(
+            "Only free-text polls have answers.".to_string(),
+        ));
+    }
+
+    let has_voted = viewer_has_voted(&db, &app_config, &poll, viewer.as_deref()).await?;
+    if !poll.can_see_results(viewer.as_deref(), has_voted) {
+        return Err(PollError::PollUnauthorizedAccess(
+            "Answers of this poll are not shown yet.".to_string(),
+        ));
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ANSWER_PAGE_SIZE)
+        .clamp(1, MAX_ANSWER_PAGE_SIZE);
+
+    let is_collaborator = poll.has_role(viewer.as_deref(), PollRole::Viewer);
+
+    let (total, answers) = db
+        .poll_repository
+        .get_text_answers(&id, is_collaborator, (page - 1) * limit, limit as i64)
+        .await?;
+
+    let texts = db
+        .poll_repository
+        .get_visible_text_answer_texts(&id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(TextAnswerPage {
+        page,
+        limit,
+        total,
+        answers,
+        summary: calculate_word_frequencies(&texts, MAX_ANSWER_PAGE_SIZE as usize),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/quizzes/{id}/leaderboard",
+    params(
+        ("id" = String, Path, description = "The identifier shared by the questions of the quiz")
+    ),
+    responses(
+        (status = 200, description = "Players ranked by score on the closed questions, then by time taken", body = Leaderboard),
+        (status = 404, description = "Quiz not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getQuizLeaderboard"
+)]
+#[get("/quizzes/{id}/leaderboard")]
+async fn get_quiz_leaderboard(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, PollError> {
+    let viewer = viewer_from_request(&req);
+
+    let questions = db.poll_repository.get_quiz_questions(&id).await?;
+
+    let mut visible = false;
+    for question in &questions {
+        if can_view_poll(&db, question, viewer.as_deref()).await?
+            && !question.is_hidden_from(viewer.as_deref())
+        {
+            visible = true;
+            break;
+        }
+    }
+    if !visible {
+        return Err(PollError::PollNotFound(id.to_string()));
+    }
+
+    let leaderboard = quiz_leaderboard(&db, &id, &questions).await?;
+
+    Ok(HttpResponse::Ok().json(leaderboard))
+}
+
+// Standings of the quiz as of its last closed question.
+async fn quiz_leaderboard(
+    db: &Data<MongoDB>,
+    quiz_id: &str,
+    questions: &[Poll],
+) -> Result<Leaderboard, PollError> {
+    let scores = db.poll_repository.get_quiz_scores(quiz_id).await?;
+    let scores = closed_question_scores(&scores, questions, Utc::now());
+
+    Ok(build_leaderboard(quiz_id, &scores))
+}
+
+// The leaderboard only moves when a question closes, so it is announced then.
+pub(crate) async fn send_quiz_leaderboard(
+    db: &Data<MongoDB>,
+    broadcaster: &Data<Mutex<Broadcaster>>,
+    poll: &Poll,
+) -> Result<(), PollError> {
+    if let Some(quiz) = &poll.quiz {
+        let questions = db.poll_repository.get_quiz_questions(&quiz.quiz_id).await?;
+        let leaderboard = quiz_leaderboard(db, &quiz.quiz_id, &questions).await?;
+        broadcaster
+            .lock()
+            .unwrap()
+            .send_leaderboard_updated(poll, &leaderboard);
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/schedule.ics",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the scheduling poll")
+    ),
+    responses(
+        (status = 200, description = "iCalendar event for the chosen slot", content_type = "text/calendar", body = String),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 422, description = "Poll is not a scheduling poll or no slot has been chosen", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "exportScheduleIcs"
+)]
+#[get("/polls/{id}/schedule.ics")]
+async fn export_schedule_ics(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, PollError> {
+    let viewer = viewer_from_request(&req);
+    let poll = get_visible_poll(&db, &id, viewer.as_deref()).await?;
+
+    let ics = poll
+        .chosen_option_id
+        .as_ref()
+        .and_then(|option_id| {
+            poll.options
+                .iter()
+                .find(|option| &option.option_id == option_id)
+        })
+        .and_then(|option| build_ics_event(&poll, option, Utc::now()))
+        .ok_or_else(|| {
+            PollError::InvalidRequest("No slot has been chosen for this poll yet.".to_string())
+        })?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"poll-{}.ics\"", poll.poll_id),
+        ))
+        .body(ics))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/",
+    request_body = PollCreation,
+    responses(
+        (status = 200, description = "Poll created successfully"),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 422, description = "Poll failed validation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "createPoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn create_new_poll(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    data: web::Json<PollCreation>,
+    app_config: Data<AppConfig>,
+    content_pipeline: Data<ContentPipeline>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    create_poll(&db, &app_config, &content_pipeline, &username, &data).await?;
+
+    Ok(HttpResponse::Ok().body("New poll created successfully."))
+}
+
+// Validates, filters and stores a new poll owned by `username`. Shared by the API and
+// chat integrations.
+pub(crate) async fn create_poll(
+    db: &Data<MongoDB>,
+    app_config: &AppConfig,
+    content_pipeline: &ContentPipeline,
+    username: &str,
+    data: &PollCreation,
+) -> Result<Poll, PollError> {
+    validate_poll_creation(data, &app_config.poll_limits)?;
+
+    let poll_id = nanoid!(10);
+
+    let title = data.title.trim().to_string();
+
+    let description = data
+        .description
+        .as_ref()
+        .map(|description| description.trim().to_string());
+
+    let username = username.to_string();
+
+    let options = data.options.clone();
+
+    let options: Vec<OptionItem> = match data.kind {
+        PollKind::Schedule => build_slot_options(&data.slots),
+        _ => options
+            .into_iter()
+            .map(|text| OptionItem {
+                option_id: nanoid!(10),
+                text: text.trim().to_string(),
+                votes: 0,
+                guest_votes: 0,
+                write_in: false,
+                hidden: false,
+                payload: None,
+            })
+            .collect(),
+    };
+
+    let matches =
+        content_pipeline.review(poll_text_fields(&title, description.as_deref(), &options))?;
+
+    let now = Utc::now();
+
+    let quiz = match &data.quiz {
+        Some(quiz) => {
+            let quiz_id = match &quiz.quiz_id {
+                Some(quiz_id) => {
+                    let questions = db.poll_repository.get_quiz_questions(quiz_id).await?;
+                    if questions
+                        .iter()
+                        .any(|question| question.username != username)
+                    {
+                        return Err(PollError::PollUnauthorizedAccess(
+                            "Only the creator of a quiz can add questions to it.".to_string(),
+                        ));
+                    }
+                    quiz_id.clone()
+                }
+                None => nanoid!(10),
+            };
+            Some(build_quiz_settings(quiz, quiz_id, &options, now))
+        }
+        None => None,
+    };
+
+    if let Some(org_id) = &data.org_id {
+        let role = db
+            .organization_repository
+            .get_member_role(org_id, &username)
+            .await
+            .map_err(|err| PollError::GeneralError(err.to_string()))?;
+
+        if role.is_none() {
+            return Err(PollError::PollUnauthorizedAccess(
+                "Polls can only be created in organizations you belong to.".to_string(),
+            ));
+        }
+    }
+
+    let poll = Poll {
+        poll_id,
+        username,
+        title,
+        description,
+        options,
+        is_active: true,
+        voters: vec![],
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+        visibility: data.visibility,
+        participants: vec![],
+        anonymous: data.anonymous,
+        results_visibility: data.results_visibility,
+        allow_write_ins: data.allow_write_ins,
+        kind: data.kind,
+        quiz,
+        availability: vec![],
+        chosen_option_id: None,
+        session_id: None,
+        lock_votes: data.lock_votes,
+        collaborators: vec![],
+        org_id: data.org_id.clone(),
+        hidden: false,
+        require_proof_of_work: data.require_proof_of_work,
+        allow_guests: data.allow_guests,
+    };
+
+    db.poll_repository.create_poll(&poll).await?;
+
+    flag_content(db, &poll, &matches).await?;
+
+    queue_webhook_event(db, WebhookEvent::PollCreated, &poll).await;
+
+    Ok(poll)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/polls/{id}",
+    request_body = PollEdit,
+    responses(
+        (status = 200, description = "Poll edited successfully", body = PollRevision),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 403, description = "Only owners and editors can edit the poll", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "Edit affects options that already have votes, or the poll changed concurrently", body = ErrorResponse),
+        (status = 422, description = "Edit is invalid, changes nothing or fails validation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "editPoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn edit_poll_by_id(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<PollEdit>,
+    app_config: Data<AppConfig>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+    content_pipeline: Data<ContentPipeline>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Editor,
+        "Only owners and editors can edit the poll.",
+    )
+    .await?;
+
+    let outcome = apply_poll_edit(&poll, &data)?;
+
+    let option_texts: Vec<String> = outcome
+        .options
+        .iter()
+        .map(|option| option.text.clone())
+        .collect();
+
+    if let Some(quiz) = &poll.quiz {
+        let keeps_correct_option = outcome
+            .options
+            .iter()
+            .any(|option| quiz.correct_option_ids.contains(&option.option_id));
+        if !keeps_correct_option {
+            return Err(PollError::ValidationError(vec![FieldError::new(
+                "options",
+                "A quiz question must keep at least one correct option.",
+            )]));
+        }
+    }
+
+    if poll.kind == PollKind::Schedule
+        && outcome
+            .options
+            .iter()
+            .any(|option| option.time_slot().is_none())
+    {
+        return Err(PollError::ValidationError(vec![FieldError::new(
+            "options",
+            "Every option of a scheduling poll must be a time slot.",
+        )]));
+    }
+
+    let errors = match poll.kind {
+        PollKind::FreeText => validate_free_text_contents(
+            &outcome.title,
+            outcome.description.as_deref(),
+            &option_texts,
+            &app_config.poll_limits,
+        ),
+        _ => validate_poll_contents(
+            &outcome.title,
+            outcome.description.as_deref(),
+            &option_texts,
+            &app_config.poll_limits,
+        ),
+    };
+    if !errors.is_empty() {
+        return Err(PollError::ValidationError(errors));
+    }
+
+    let matches = content_pipeline.review(poll_text_fields(
+        &outcome.title,
+        outcome.description.as_deref(),
+        &outcome.options,
+    ))?;
+
+    db.poll_repository
+        .update_poll_contents(&poll, &outcome)
+        .await?;
+
+    let revision = PollRevision {
+        revision_id: nanoid!(10),
+        poll_id: poll.poll_id.clone(),
+        username: username.clone(),
+        created_at: Utc::now(),
+        changes: outcome.changes,
+    };
+
+    db.poll_repository.insert_revision(&revision).await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+    flag_content(&db, &poll, &matches).await?;
+
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    Ok(HttpResponse::Ok().json(revision))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/vote",
+    request_body = VoteOption,
+    responses(
+        (status = 200, description = "Vote cast successfully"),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 403, description = "Missing, invalid, expired or reused proof of work", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "Poll is closed, vote already cast for the option, already voted in an anonymous poll, votes are final, or the poll changed while voting", body = ErrorResponse),
+        (status = 422, description = "Option does not exist in this poll, or the write-in is not accepted", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "castVoteToPoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn cast_vote_to_poll(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<VoteOption>,
+    app_config: Data<AppConfig>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+    content_pipeline: Data<ContentPipeline>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let (_, message) = cast_vote(
+        &db,
+        &app_config,
+        &broadcaster,
+        &content_pipeline,
+        &id,
+        &username,
+        &data,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().body(message))
+}
+
+// Casts, changes or scores the vote of `username` and broadcasts the updated poll.
+// Returns the poll as it is after the vote. Shared by the API and chat integrations.
+pub(crate) async fn cast_vote(
+    db: &Data<MongoDB>,
+    app_config: &AppConfig,
+    broadcaster: &Data<Mutex<Broadcaster>>,
+    content_pipeline: &ContentPipeline,
+    id: &str,
+    username: &str,
+    data: &VoteOption,
+) -> Result<(Poll, &'static str), PollError> {
+    let poll = get_visible_poll(db, id, Some(username)).await?;
+
+    if !poll.is_active {
+        return Err(PollError::PollClosed(
+            "Cannot vote to a closed poll".to_string(),
+        ));
+    }
+
+    ensure_open_in_session(db, &poll).await?;
+
+    match poll.kind {
+        PollKind::FreeText => {
+            return Err(PollError::InvalidRequest(
+                "Free-text polls take answers instead of votes.".to_string(),
+            ))
+        }
+        PollKind::Schedule => {
+            return Err(PollError::InvalidRequest(
+                "Scheduling polls take availability instead of votes.".to_string(),
+            ))
+        }
+        _ => {}
+    }
+
+    let write_in = match &data.write_in {
+        Some(text) => Some(prepare_write_in(app_config, content_pipeline, &poll, text)?),
+        None => {
+            validate_vote_option(&poll, &data.option_id)?;
+            None
+        }
+    };
+
+    ensure_ballot_allowed(db, app_config, &poll, username).await?;
+
+    // The challenge is only spent on a ballot that is otherwise valid.
+    if poll.require_proof_of_work {
+        spend_proof_of_work(db, app_config, &poll, data.proof_of_work.as_ref()).await?;
+    }
+
+    let option_id = match write_in {
+        Some(write_in) => save_write_in(db, app_config, broadcaster, &poll, write_in).await?,
+        None => data.option_id.clone(),
+    };
+    let option_id = &option_id;
+
+    let message = if let Some(quiz) = &poll.quiz {
+        let now = Utc::now();
+        if poll.is_question_closed(now) {
+            return Err(PollError::PollClosed(
+                "Time is up for this question.".to_string(),
+            ));
+        }
+
+        let correct = quiz.correct_option_ids.contains(option_id);
+        let elapsed = now.signed_duration_since(quiz.opened_at.unwrap_or(poll.created_at));
+
+        let score = QuizScore {
+            quiz_id: quiz.quiz_id.clone(),
+            poll_id: poll.poll_id.clone(),
+            username: username.to_string(),
+            option_id: option_id.clone(),
+            correct,
+            score: score_quiz_answer(correct, elapsed, quiz.time_limit_secs),
+            elapsed_ms: elapsed.num_milliseconds(),
+            answered_at: now,
+        };
+
+        db.poll_repository.cast_quiz_answer(&score).await?;
+        "Successfully answered the question."
+    } else if poll.anonymous {
+        // Anonymous polls keep no record of who picked which option, so a ballot is final.
+        let marker = participation_marker(&app_config.ballot_secret, id, username);
+
+        db.poll_repository
+            .cast_anonymous_vote_to_poll_by_id(id, option_id, &marker)
+            .await?;
+        "Successfully voted for the option."
+    } else if db
+        .poll_repository
+        .check_user_vote_in_poll(username, id)
+        .await?
+    {
+        if poll.lock_votes {
+            return Err(PollError::AlreadyVotedError(
+                "Votes in this poll are final and cannot be changed.".to_string(),
+            ));
+        }
+
+        db.poll_repository
+            .change_vote_in_poll_by_id(id, option_id, username)
+            .await?;
+        "Successfully changed your option."
+    } else {
+        db.poll_repository
+            .cast_vote_to_poll_by_id(id, option_id, username)
+            .await?;
+        "Successfully voted for the option."
+    };
+
+    let poll = get_poll_utility(db, id).await?;
+
+    broadcaster.lock().unwrap().record_vote(username, id);
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    queue_webhook_event(db, WebhookEvent::VoteCast, &poll).await;
+
+    Ok((poll, message))
+}
+
+fn guest_from_request(
+    req: &HttpRequest,
+    app_config: &AppConfig,
+    poll_id: &str,
+) -> Option<GuestClaims> {
+    let token = req.cookie(GUEST_TOKEN_COOKIE)?;
+
+    GuestClaims::from_token(&app_config.jwt_secret, token.value(), poll_id)
+}
+
+fn ensure_guests_allowed(poll: &Poll) -> Result<(), PollError> {
+    if !poll.allow_guests {
+        return Err(PollError::InvalidRequest(
+            "This poll does not accept guest votes.".to_string(),
+        ));
+    }
+
+    // Guests pick from the options as they are; quizzes, free-text and scheduling polls
+    // keep per-user records that guests do not have.
+    if poll.kind != PollKind::SingleChoice || poll.quiz.is_some() {
+        return Err(PollError::InvalidRequest(
+            "Guests can only vote in single choice polls.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/guest",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    responses(
+        (status = 200, description = "Guest session started or resumed, the token is set as the guest_token cookie", body = GuestSession),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 422, description = "Poll does not accept guest votes", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "startGuestSession"
+)]
+#[post("/polls/{id}/guest")]
+async fn start_guest_session(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, PollError> {
+    let poll = get_visible_poll(&db, &id, None).await?;
+    ensure_guests_allowed(&poll)?;
+
+    // A guest who already holds a token for the poll keeps it, so asking again does not
+    // hand out a fresh ballot.
+    let claims = match guest_from_request(&req, &app_config, &poll.poll_id) {
+        Some(claims) => claims,
+        None => {
+            let expires_at = Utc::now() + Duration::days(app_config.guest_token_days);
+            GuestClaims::new(&nanoid!(), &poll.poll_id, expires_at)
+        }
+    };
+
+    let token = claims
+        .generate_token(&app_config.jwt_secret)
+        .map_err(|err| PollError::GeneralError(err.to_string()))?;
+    let expires_at = DateTime::from_timestamp(claims.exp as i64, 0).unwrap_or_default();
+
+    let cookie = Cookie::build(GUEST_TOKEN_COOKIE, token)
+        .path(format!("/api/polls/{}", poll.poll_id))
+        .http_only(true)
+        .max_age(CookieDuration::seconds(
+            (expires_at - Utc::now()).num_seconds().max(0),
+        ))
+        .same_site(SameSite::None)
+        .secure(true)
+        .finish();
+
+    let has_voted = db
+        .poll_repository
+        .has_guest_voted(&poll.poll_id, &claims.sub)
+        .await?;
+
+    Ok(HttpResponse::Ok().cookie(cookie).json(GuestSession {
+        guest_id: claims.sub,
+        poll_id: poll.poll_id,
+        has_voted,
+        expires_at,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/guest/vote",
+    request_body = GuestVote,
+    responses(
+        (status = 200, description = "Guest vote cast successfully"),
+        (status = 403, description = "Missing or invalid guest token, or missing, invalid, expired or reused proof of work", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "Poll is closed, the guest already voted, or the poll changed while voting", body = ErrorResponse),
+        (status = 422, description = "Option does not exist in this poll, or the poll does not accept guest votes", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "castGuestVote"
+)]
+#[post("/polls/{id}/guest/vote")]
+async fn cast_guest_vote(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<GuestVote>,
+    app_config: Data<AppConfig>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let poll = get_visible_poll(&db, &id, None).await?;
+    ensure_guests_allowed(&poll)?;
+
+    let guest = guest_from_request(&req, &app_config, &poll.poll_id).ok_or_else(|| {
+        PollError::PollUnauthorizedAccess(
+            "A guest token for this poll is required to vote as a guest.".to_string(),
+        )
+    })?;
+
+    if !poll.is_active {
+        return Err(PollError::PollClosed(
+            "Cannot vote to a closed poll".to_string(),
+        ));
+    }
+
+    ensure_open_in_session(&db, &poll).await?;
+    validate_vote_option(&poll, &data.option_id)?;
+
+    if db
+        .poll_repository
+        .has_guest_voted(&poll.poll_id, &guest.sub)
+        .await?
+    {
+        return Err(PollError::AlreadyVotedError(
+            "Guests can vote only once and cannot change their vote.".to_string(),
+        ));
+    }
+
+    if poll.require_proof_of_work {
+        spend_proof_of_work(&db, &app_config, &poll, data.proof_of_work.as_ref()).await?;
+    }
+
+    db.poll_repository
+        .cast_guest_vote_to_poll_by_id(&poll.poll_id, &data.option_id, &guest.sub)
+        .await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    queue_webhook_event(&db, WebhookEvent::VoteCast, &poll).await;
+
+    Ok(HttpResponse::Ok().body("Successfully voted for the option."))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/polls/{id}/vote",
+    responses(
+        (status = 200, description = "Vote retracted successfully"),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "Poll is closed, votes are final, or the poll changed concurrently", body = ErrorResponse),
+        (status = 422, description = "No vote to retract, or the poll does not allow retracting", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "retractVote",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn retract_vote(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+    let username = username.as_str();
+
+    let poll = get_visible_poll(&db, &id, Some(username)).await?;
+
+    if !poll.is_active {
+        return Err(PollError::PollClosed(
+            "Cannot retract a vote from a closed poll".to_string(),
+        ));
+    }
+
+    ensure_open_in_session(&db, &poll).await?;
+
+    if poll.lock_votes {
+        return Err(PollError::AlreadyVotedError(
+            "Votes in this poll are final and cannot be retracted.".to_string(),
+        ));
+    }
+
+    // Anonymous ballots cannot be traced back to the voter, and quiz answers and
+    // free-text answers are scored or moderated once submitted.
+    if poll.anonymous || poll.quiz.is_some() || poll.kind == PollKind::FreeText {
+        return Err(PollError::InvalidRequest(
+            "Votes in this poll cannot be retracted.".to_string(),
+        ));
+    }
+
+    if poll.kind == PollKind::Schedule {
+        db.poll_repository
+            .retract_availability(&id, username)
+            .await?;
+    } else {
+        db.poll_repository
+            .retract_vote_in_poll_by_id(&id, username)
+            .await?;
+    }
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    broadcaster.lock().unwrap().forget_vote(username, &id);
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    Ok(HttpResponse::Ok().body("Vote retracted successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/close",
+    responses(
+        (status = 200, description = "Poll closed successfully"),
+        (status = 403, description = "Only owners and editors can close the poll", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "closePoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn close_poll_by_id(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Editor,
+        "Poll can be closed only by its owners and editors.",
+    )
+    .await?;
+
+    db.poll_repository.close_poll_by_id(&id).await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+    send_quiz_leaderboard(&db, &broadcaster, &poll).await?;
+
+    queue_webhook_event(&db, WebhookEvent::PollClosed, &poll).await;
+
+    Ok(HttpResponse::Ok().body("Closed poll successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/reset",
+    responses(
+        (status = 200, description = "Poll reset successfully"),
+        (status = 403, description = "Only owners and editors can reset the poll", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "resetPoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn reset_votes_by_id(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Editor,
+        "Only owners and editors can reset the votes.",
+    )
+    .await?;
+
+    db.poll_repository.reset_poll_by_id(&id).await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    queue_webhook_event(&db, WebhookEvent::PollReset, &poll).await;
+
+    Ok(HttpResponse::Ok().body("Poll reset successfully."))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/polls/{id}",
+    responses(
+        (status = 200, description = "Poll deleted successfully"),
+        (status = 403, description = "Only owners can delete the poll", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "deletePoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn delete_poll_by_id(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Owner,
+        "Poll can be deleted only by its owners.",
+    )
+    .await?;
+
+    db.poll_repository.soft_delete_poll_by_id(&id).await?;
+
+    broadcaster.lock().unwrap().send_poll_deleted(&poll);
+
+    Ok(HttpResponse::Ok().body("Poll deleted successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/restore",
+    responses(
+        (status = 200, description = "Poll restored successfully"),
+        (status = 403, description = "Only owners can restore the poll", body = ErrorResponse),
+        (status = 404, description = "Poll not found or past its retention window", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "restorePoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn restore_poll_by_id(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    app_config: Data<AppConfig>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = db
+        .poll_repository
+        .get_deleted_poll_by_id(&id)
+        .await?
+        .ok_or_else(|| PollError::PollNotFound(id.to_string()))?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Owner,
+        "Poll can be restored only by its owners.",
+    )
+    .await?;
+
+    let deleted_after = Utc::now() - Duration::days(app_config.poll_retention_days);
+
+    db.poll_repository
+        .restore_poll_by_id(&id, deleted_after)
+        .await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+
+    Ok(HttpResponse::Ok().body("Poll restored successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/invites",
+    request_body = InviteCreation,
+    responses(
+        (status = 200, description = "Invite link created successfully", body = PollInvite),
+        (status = 403, description = "Only owners can invite to the poll", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 422, description = "Expiry must be positive", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "createPollInvite",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn create_poll_invite(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<InviteCreation>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Owner,
+        "Only owners can invite to the poll.",
+    )
+    .await?;
+
+    let now = Utc::now();
+
+    let expires_at = match data.expires_in_hours {
+        Some(hours) if hours <= 0 => {
+            return Err(PollError::InvalidRequest(
+                "Invite expiry must be a positive number of hours.".to_string(),
+            ));
+        }
+        Some(hours) => Some(now + Duration::hours(hours)),
+        None => None,
+    };
+
+    let invite = PollInvite {
+        poll_id: poll.poll_id,
+        secret: nanoid!(21),
+        kind: InviteKind::Token,
+        label: data.label.clone(),
+        created_by: username.clone(),
+        created_at: now,
+        expires_at,
+    };
+
+    db.poll_repository.create_invite(&invite).await?;
+
+    Ok(HttpResponse::Ok().json(invite))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/invites",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    responses(
+        (status = 200, description = "Successfully fetched invites and access codes", body = Vec<PollInvite>),
+        (status = 403, description = "Only owners can list invites", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getPollInvites",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_poll_invites(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, PollError> {
+    let poll = get_poll_utility(&db, &id).await?;
+
+    let viewer = viewer_from_request(&req).unwrap_or_default();
+    ensure_poll_role(
+        &db,
+        &poll,
+        &viewer,
+        PollRole::Owner,
+        "Only owners can list invites.",
+    )
+    .await?;
+
+    let invites = db.poll_repository.get_invites_by_poll_id(&id).await?;
+
+    Ok(HttpResponse::Ok().json(invites))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/polls/{id}/invites/{token}",
+    responses(
+        (status = 200, description = "Invite revoked successfully"),
+        (status = 403, description = "Only owners can revoke invites", body = ErrorResponse),
+        (status = 404, description = "Poll or invite not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "revokePollInvite",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn revoke_poll_invite(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let (id, token) = path.into_inner();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Owner,
+        "Only owners can revoke invites.",
+    )
+    .await?;
+
+    let result = db.poll_repository.delete_invite(&id, &token).await?;
+
+    if result.deleted_count == 0 {
+        return Err(PollError::PollNotFound(id));
+    }
+
+    Ok(HttpResponse::Ok().body("Invite revoked successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/access-code",
+    responses(
+        (status = 200, description = "Access code generated, replacing any previous code", body = AccessCodeResponse),
+        (status = 403, description = "Only owners can manage the access code", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "createPollAccessCode",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn create_access_code(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Owner,
+        "Only owners can manage the access code.",
+    )
+    .await?;
+
+    let access_code = generate_access_code();
+
+    let invite = PollInvite {
+        poll_id: poll.poll_id,
+        secret: access_code.clone(),
+        kind: InviteKind::AccessCode,
+        label: None,
+        created_by: username.clone(),
+        created_at: Utc::now(),
+        expires_at: None,
+    };
+
+    db.poll_repository.replace_access_code(&invite).await?;
+
+    Ok(HttpResponse::Ok().json(AccessCodeResponse { access_code }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/polls/{id}/access-code",
+    responses(
+        (status = 200, description = "Access code revoked successfully"),
+        (status = 403, description = "Only owners can manage the access code", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "revokePollAccessCode",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn revoke_access_code(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Owner,
+        "Only owners can manage the access code.",
+    )
+    .await?;
+
+    db.poll_repository.delete_access_code(&id).await?;
+
+    Ok(HttpResponse::Ok().body("Access code revoked successfully."))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/join",
+    request_body = JoinPollRequest,
+    responses(
+        (status = 200, description = "Joined the poll successfully", body = Poll),
+        (status = 403, description = "Invite token or access code is invalid or expired", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "joinPoll",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn join_poll(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<JoinPollRequest>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+    let viewer = Some(username.as_str());
+
+    if poll.is_hidden_from(viewer) {
+        return Err(PollError::PollHidden(id.to_string()));
+    }
+
+    if can_view_poll(&db, &poll, viewer).await? {
+        let poll = poll_for_viewer(&db, &app_config, &poll, viewer).await?;
+        return Ok(HttpResponse::Ok().json(poll));
+    }
+
+    let invite = match (&data.token, &data.code) {
+        (Some(token), _) => {
+            db.poll_repository
+                .find_valid_invite(&id, token, InviteKind::Token)
+                .await?
+        }
+        (None, Some(code)) => {
+            db.poll_repository
+                .find_valid_invite(&id, &normalize_access_code(code), InviteKind::AccessCode)
+                .await?
         }
+        (None, None) => None,
     };
 
-    return poll_option;
+    if invite.is_none() {
+        return Err(PollError::PollUnauthorizedAccess(
+            "Invite token or access code is invalid or expired.".to_string(),
+        ));
+    }
+
+    db.poll_repository.add_participant(&id, &username).await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+    let poll = poll_for_viewer(&db, &app_config, &poll, viewer).await?;
+
+    Ok(HttpResponse::Ok().json(poll))
 }
 
 #[utoipa::path(
-    get,
-    path = "/api/",
+    post,
+    path = "/api/polls/{id}/write-ins/{optionId}/visibility",
+    request_body = WriteInVisibility,
     responses(
-        (status = 200, description = "Successfully fetched all polls", body = Vec<Poll>),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Write-in hidden or shown successfully", body = PollRevision),
+        (status = 403, description = "Only owners and editors can moderate write-ins", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "The poll changed concurrently", body = ErrorResponse),
+        (status = 422, description = "Option is not a write-in or already in that state", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Polls",
-    operation_id = "getAllPolls"
+    operation_id = "setWriteInVisibility",
+    security(
+        ("bearerAuth" = [])
+    )
 )]
-#[get("/")]
-async fn get_all_polls(db: Data<MongoDB>) -> impl Responder {
-    let polls = match db.poll_repository.get_all_polls().await {
-        Ok(polls) => polls,
-        Err(err) => {
-            return HttpResponse::InternalServerError().body(err.to_string());
-        }
-    };
+async fn set_write_in_visibility(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    path: Path<(String, String)>,
+    data: web::Json<WriteInVisibility>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let (id, option_id) = path.into_inner();
 
-    HttpResponse::Ok().json(polls)
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Editor,
+        "Only owners and editors can moderate write-ins.",
+    )
+    .await?;
+
+    let outcome = apply_write_in_visibility(&poll, &option_id, data.hidden)?;
+
+    let revision = save_write_in_moderation(&db, &broadcaster, &poll, &username, outcome).await?;
+
+    Ok(HttpResponse::Ok().json(revision))
 }
 
 #[utoipa::path(
-    get,
-    path = "/api/polls/{id}",
-    params(
-        ("id" = String, Path, description = "The unique identifier of the poll")
+    post,
+    path = "/api/polls/{id}/write-ins/{optionId}/merge",
+    request_body = WriteInMerge,
+    responses(
+        (status = 200, description = "Write-in merged successfully", body = PollRevision),
+        (status = 403, description = "Only owners and editors can moderate write-ins", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "The poll changed concurrently", body = ErrorResponse),
+        (status = 422, description = "Option is not a write-in or the target does not exist", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
+    tag = "Polls",
+    operation_id = "mergeWriteIn",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn merge_write_in(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    path: Path<(String, String)>,
+    data: web::Json<WriteInMerge>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let (id, option_id) = path.into_inner();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Editor,
+        "Only owners and editors can moderate write-ins.",
+    )
+    .await?;
+
+    let outcome = apply_write_in_merge(&poll, &option_id, &data.target_option_id)?;
+
+    let revision = save_write_in_moderation(&db, &broadcaster, &poll, &username, outcome).await?;
+
+    Ok(HttpResponse::Ok().json(revision))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/answers",
+    request_body = TextAnswerSubmission,
     responses(
-        (status = 200, description = "Successfully fetched poll details", body = Poll),
-        (status = 404, description = "Poll not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Answer submitted successfully", body = TextAnswer),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "Poll is closed or already answered by this user", body = ErrorResponse),
+        (status = 422, description = "Poll is not a free-text poll, or the answer fails validation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Polls",
-    operation_id = "getPollById"
+    operation_id = "submitPollAnswer",
+    security(
+        ("bearerAuth" = [])
+    )
 )]
-#[get("/polls/{id}")]
-async fn get_poll_by_id(db: Data<MongoDB>, id: Path<String>) -> impl Responder {
-    let poll = match get_poll_utility(&db, &id).await {
-        Some(poll) => poll,
-        None => {
-            return HttpResponse::Ok().body("No poll found with given id.");
-        }
+async fn submit_text_answer(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<TextAnswerSubmission>,
+    app_config: Data<AppConfig>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_visible_poll(&db, &id, Some(&username)).await?;
+
+    if poll.kind != PollKind::FreeText {
+        return Err(PollError::InvalidRequest(
+            "Only free-text polls take answers.".to_string(),
+        ));
+    }
+
+    if !poll.is_active {
+        return Err(PollError::PollClosed(
+            "Cannot answer a closed poll".to_string(),
+        ));
+    }
+
+    ensure_open_in_session(&db, &poll).await?;
+
+    let text = validate_text_answer(&data.text, &app_config.poll_limits)?;
+
+    let answer = TextAnswer {
+        answer_id: nanoid!(10),
+        poll_id: poll.poll_id.clone(),
+        username: (!poll.anonymous).then(|| username.clone()),
+        text,
+        hidden: false,
+        pinned: false,
+        created_at: Utc::now(),
     };
 
-    HttpResponse::Ok().json(poll)
+    let marker = poll
+        .anonymous
+        .then(|| participation_marker(&app_config.ballot_secret, &poll.poll_id, &username));
+
+    db.poll_repository
+        .submit_text_answer(&answer, &username, marker.as_deref())
+        .await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    broadcaster.lock().unwrap().record_vote(&username, &id);
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_text_answer(&poll, "answer_added", &answer);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    Ok(HttpResponse::Ok().json(answer))
 }
 
 #[utoipa::path(
-    get,
-    path = "/api/polls/{id}/results",
-    params(
-        ("id" = String, Path, description = "The unique identifier of the poll"),
-        ("live" = Option<bool>, Query, description = "Filter by live status of the poll"),
-        ("closed" = Option<bool>, Query, description = "Filter by closed status of the poll"),
-        ("creator" = Option<String>, Query, description = "Filter by creator username")
+    post,
+    path = "/api/polls/{id}/answers/{answerId}/moderation",
+    request_body = TextAnswerModeration,
+    responses(
+        (status = 200, description = "Answer hidden, shown, pinned or unpinned successfully", body = TextAnswer),
+        (status = 403, description = "Only owners and editors can moderate answers", body = ErrorResponse),
+        (status = 404, description = "Poll or answer not found", body = ErrorResponse),
+        (status = 422, description = "The moderation changes nothing", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
+    tag = "Polls",
+    operation_id = "moderatePollAnswer",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn moderate_text_answer(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    path: Path<(String, String)>,
+    data: web::Json<TextAnswerModeration>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let (id, answer_id) = path.into_inner();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Editor,
+        "Only owners and editors can moderate answers.",
+    )
+    .await?;
+
+    if data.hidden.is_none() && data.pinned.is_none() {
+        return Err(PollError::InvalidRequest(
+            "The moderation does not change anything.".to_string(),
+        ));
+    }
+
+    let answer = db
+        .poll_repository
+        .update_text_answer_moderation(&id, &answer_id, data.hidden, data.pinned)
+        .await?
+        .ok_or_else(|| PollError::PollNotFound(format!("{}/answers/{}", id, answer_id)))?;
+
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_text_answer(&poll, "answer_updated", &answer);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    Ok(HttpResponse::Ok().json(answer))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/polls/{id}/availability",
+    request_body = AvailabilitySubmission,
     responses(
-        (status = 200, description = "Successfully fetched poll results", body = PollResults),
-        (status = 400, description = "Query parameters mismatch"),
-        (status = 404, description = "Poll not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Availability saved, replacing any earlier answers", body = AvailabilityBallot),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "Poll is closed", body = ErrorResponse),
+        (status = 422, description = "Poll is not a scheduling poll, or the answers refer to unknown slots", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Polls",
-    operation_id = "getPollResults"
+    operation_id = "submitAvailability",
+    security(
+        ("bearerAuth" = [])
+    )
 )]
-#[get("/polls/{id}/results")]
-async fn fetch_results_by_id(
+async fn submit_availability(
+    req: HttpRequest,
     db: Data<MongoDB>,
     id: Path<String>,
+    data: web::Json<AvailabilitySubmission>,
     broadcaster: Data<Mutex<Broadcaster>>,
-    query: Query<PollQueryParams>,
-) -> impl Responder {
-    let params = query.into_inner();
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
 
-    let poll_id = id.into_inner();
+    let poll = get_visible_poll(&db, &id, Some(&username)).await?;
 
-    match db.poll_repository.get_poll_by_id(&poll_id).await {
-        Ok(Some(poll)) => {
-            if let Some(close) = params.closed {
-                if close && !poll.is_active || !close && poll.is_active {
-                    return HttpResponse::BadRequest().body(
-                        "Query parameters mismatch, poll status is not specified correctly.",
-                    );
-                }
-            }
+    if poll.kind != PollKind::Schedule {
+        return Err(PollError::InvalidRequest(
+            "Only scheduling polls take availability.".to_string(),
+        ));
+    }
 
-            if let Some(live) = params.live {
-                if live && !poll.is_active || !live && poll.is_active {
-                    return HttpResponse::NotImplemented().body(
-                        "Query parameters mismatch, poll status is not specified correctly.",
-                    );
-                }
-            }
+    if !poll.is_active {
+        return Err(PollError::PollClosed(
+            "Cannot answer a closed poll".to_string(),
+        ));
+    }
 
-            if let Some(creator_id) = params.creator {
-                if poll.username != creator_id {
-                    return HttpResponse::Forbidden().body(
-                        "Query parameters mismatch, provided creator is not owner of this poll.",
-                    );
-                }
-            }
+    ensure_open_in_session(&db, &poll).await?;
 
-            let response = calculate_poll_results(&poll);
-            broadcaster.lock().unwrap().send_poll_results(&response);
-            HttpResponse::Ok().json(response)
-        }
-        Ok(None) => HttpResponse::NotFound().body("No poll found with the given ID."),
-        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    if poll.lock_votes && poll.has_voted(Some(&username)) {
+        return Err(PollError::AlreadyVotedError(
+            "Votes in this poll are final and cannot be changed.".to_string(),
+        ));
     }
+
+    let ballot = AvailabilityBallot {
+        username: username.clone(),
+        answers: validate_availability(&poll, &data.answers)?,
+        updated_at: Utc::now(),
+    };
+
+    db.poll_repository.submit_availability(&id, &ballot).await?;
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    broadcaster.lock().unwrap().record_vote(&username, &id);
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    Ok(HttpResponse::Ok().json(ballot))
 }
 
 #[utoipa::path(
     post,
-    path = "/api/polls/",
-    request_body = PollCreation,
+    path = "/api/polls/{id}/schedule/choice",
+    request_body = SlotChoice,
     responses(
-        (status = 200, description = "Poll created successfully"),
-        (status = 400, description = "Bad request"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Slot chosen successfully"),
+        (status = 403, description = "Only owners and editors can choose the slot", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 422, description = "Poll is not a scheduling poll or the slot does not exist", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Polls",
-    operation_id = "createPoll",
+    operation_id = "chooseScheduleSlot",
     security(
         ("bearerAuth" = [])
     )
 )]
-async fn create_new_poll(db: Data<MongoDB>, data: web::Json<PollCreation>) -> impl Responder {
-    let poll_id = nanoid!(10);
+async fn choose_schedule_slot(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<SlotChoice>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
 
-    let title = data.title.clone();
+    let poll = get_poll_utility(&db, &id).await?;
 
-    let username = data.username.clone();
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Editor,
+        "Only owners and editors can choose the slot.",
+    )
+    .await?;
 
-    let options = data.options.clone();
+    if poll.kind != PollKind::Schedule {
+        return Err(PollError::InvalidRequest(
+            "Only scheduling polls have slots to choose.".to_string(),
+        ));
+    }
 
-    let options = options
-        .into_iter()
-        .map(|text| OptionItem {
-            option_id: nanoid!(10),
-            text: text.clone(),
-            votes: 0,
-        })
-        .collect();
+    validate_vote_option(&poll, &data.option_id)?;
 
-    let now = Utc::now();
+    db.poll_repository.choose_slot(&id, &data.option_id).await?;
 
-    let poll = Poll {
-        poll_id,
-        username,
-        title,
-        options,
-        is_active: true,
-        voters: vec![],
-        created_at: now,
-        updated_at: now,
-    };
+    let poll = get_poll_utility(&db, &id).await?;
 
-    if let Err(err) = db.poll_repository.create_poll(&poll).await {
-        return HttpResponse::InternalServerError().body(err.to_string());
-    }
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+    let response = poll_results(&db, &poll).await?;
+    broadcaster
+        .lock()
+        .unwrap()
+        .send_poll_results(&poll, &response);
+
+    Ok(HttpResponse::Ok().body("Slot chosen successfully."))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/polls/{id}/collaborators",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    responses(
+        (status = 200, description = "Collaborators of the poll and their roles, not including the owner", body = Vec<Collaborator>),
+        (status = 403, description = "Only collaborators can list collaborators", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "getPollCollaborators",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_collaborators(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, PollError> {
+    let poll = get_poll_utility(&db, &id).await?;
 
-    HttpResponse::Ok().body("New poll created successfully.")
+    let viewer = viewer_from_request(&req).unwrap_or_default();
+    ensure_poll_role(
+        &db,
+        &poll,
+        &viewer,
+        PollRole::Viewer,
+        "Only collaborators can list collaborators.",
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(poll.collaborators))
 }
 
 #[utoipa::path(
     post,
-    path = "/api/polls/{id}/vote",
-    request_body = VoteOption,
+    path = "/api/polls/{id}/collaborators",
+    request_body = CollaboratorInvite,
     responses(
-        (status = 200, description = "Vote cast successfully"),
-        (status = 400, description = "Bad request"),
-        (status = 404, description = "Poll not found"),
-        (status = 409, description = "Poll is closed"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Collaborator added, or their role changed", body = Collaborator),
+        (status = 403, description = "Only owners can manage collaborators", body = ErrorResponse),
+        (status = 404, description = "Poll or user not found", body = ErrorResponse),
+        (status = 422, description = "The owner cannot be added as a collaborator", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Polls",
-    operation_id = "castVoteToPoll",
+    operation_id = "addPollCollaborator",
     security(
         ("bearerAuth" = [])
     )
 )]
-async fn cast_vote_to_poll(
+async fn add_collaborator(
+    req: HttpRequest,
     db: Data<MongoDB>,
     id: Path<String>,
-    data: web::Json<VoteOption>,
+    data: web::Json<CollaboratorInvite>,
     broadcaster: Data<Mutex<Broadcaster>>,
-) -> impl Responder {
-    let poll = match get_poll_utility(&db, &id).await {
-        Some(poll) => poll,
-        None => {
-            return HttpResponse::Ok().body("Internal server error.");
-        }
-    };
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
 
-    if poll.is_active == false {
-        return HttpResponse::Conflict().body("Cannot vote to a closed poll");
-    }
+    let poll = get_poll_utility(&db, &id).await?;
 
-    let username = &data.username;
+    ensure_poll_role(
+        &db,
+        &poll,
+        &username,
+        PollRole::Owner,
+        "Only owners can manage collaborators.",
+    )
+    .await?;
 
-    let option_id = &data.option_id;
+    if data.collaborator == poll.username {
+        return Err(PollError::InvalidRequest(
+            "The owner of the poll cannot be added as a collaborator.".to_string(),
+        ));
+    }
 
-    let user_voted = match db
-        .poll_repository
-        .check_user_vote_in_poll(username, &id)
+    db.user_repository
+        .find_user(&data.collaborator)
         .await
-    {
-        Ok(res) => res,
-        Err(_) => {
-            return HttpResponse::InternalServerError().body("Error accessing poll");
-        }
+        .map_err(|err| PollError::GeneralError(err.to_string()))?
+        .ok_or_else(|| PollError::UserNotFound(data.collaborator.clone()))?;
+
+    let collaborator = Collaborator {
+        username: data.collaborator.clone(),
+        role: data.role,
+        added_at: Utc::now(),
     };
 
-    let message: &str;
+    db.poll_repository
+        .set_collaborator(&id, &collaborator)
+        .await?;
 
-    if user_voted {
-        if let Err(err) = db
-            .poll_repository
-            .change_vote_in_poll_by_id(&id, option_id, username)
-            .await
-        {
-            return HttpResponse::InternalServerError().body(format!("{}", err));
-        }
-        message = "Successfully changed your option.";
-    } else {
-        if let Err(err) = db
-            .poll_repository
-            .cast_vote_to_poll_by_id(&id, option_id, username)
-            .await
-        {
-            return HttpResponse::InternalServerError()
-                .body(format!("Error voting to the poll {}", err));
-        }
-        message = "Successfully voted for the option."
+    let poll = get_poll_utility(&db, &id).await?;
+    broadcaster.lock().unwrap().send_updated_poll(&poll);
+
+    Ok(HttpResponse::Ok().json(collaborator))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/polls/{id}/collaborators/{username}",
+    responses(
+        (status = 200, description = "Collaborator removed successfully"),
+        (status = 403, description = "Only owners can remove other collaborators", body = ErrorResponse),
+        (status = 404, description = "Poll or collaborator not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Polls",
+    operation_id = "removePollCollaborator",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn remove_collaborator(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    path: Path<(String, String)>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let (id, collaborator) = path.into_inner();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    // Collaborators may always leave a poll on their own.
+    if username != collaborator {
+        ensure_poll_role(
+            &db,
+            &poll,
+            &username,
+            PollRole::Owner,
+            "Only owners can remove other collaborators.",
+        )
+        .await?;
     }
 
-    let poll = match get_poll_utility(&db, &id).await {
-        Some(poll) => poll,
-        None => {
-            return HttpResponse::Ok().body(message);
-        }
-    };
+    if !db
+        .poll_repository
+        .remove_collaborator(&id, &collaborator)
+        .await?
+    {
+        return Err(PollError::PollNotFound(format!(
+            "{}/collaborators/{}",
+            id, collaborator
+        )));
+    }
 
+    let poll = get_poll_utility(&db, &id).await?;
     broadcaster.lock().unwrap().send_updated_poll(&poll);
-    let response = calculate_poll_results(&poll);
-    broadcaster.lock().unwrap().send_poll_results(&response);
-    HttpResponse::Ok().body("Successfully voted to the poll.")
+
+    Ok(HttpResponse::Ok().body("Collaborator removed successfully."))
 }
 
 #[utoipa::path(
     post,
-    path = "/api/polls/{id}/close",
-    request_body = UserNameRequest,
+    path = "/api/polls/{id}/owner",
+    request_body = OwnershipTransfer,
     responses(
-        (status = 200, description = "Poll closed successfully"),
-        (status = 404, description = "Poll not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Ownership transferred, the previous owner stays on as a co-owner"),
+        (status = 403, description = "Only the owner can transfer the poll", body = ErrorResponse),
+        (status = 404, description = "Poll or user not found", body = ErrorResponse),
+        (status = 409, description = "Ownership changed concurrently", body = ErrorResponse),
+        (status = 422, description = "The poll already belongs to that user", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Polls",
-    operation_id = "closePoll",
+    operation_id = "transferPollOwnership",
     security(
         ("bearerAuth" = [])
     )
 )]
-async fn close_poll_by_id(
+async fn transfer_ownership(
+    req: HttpRequest,
     db: Data<MongoDB>,
     id: Path<String>,
-    data: web::Json<UserNameRequest>,
+    data: web::Json<OwnershipTransfer>,
     broadcaster: Data<Mutex<Broadcaster>>,
-) -> impl Responder {
-    let username = &data.username;
-    if let Err(err) = db.poll_repository.close_poll_by_id(&id, username).await {
-        return HttpResponse::InternalServerError().body(format!("Error closing poll : {}", err));
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    let poll = get_poll_utility(&db, &id).await?;
+
+    if poll.username != username {
+        return Err(PollError::PollUnauthorizedAccess(
+            "Only the owner can transfer the poll.".to_string(),
+        ));
     }
 
-    let poll = match get_poll_utility(&db, &id).await {
-        Some(poll) => poll,
-        None => {
-            return HttpResponse::Ok().body("No poll found with given id.");
-        }
-    };
+    if data.new_owner == poll.username {
+        return Err(PollError::InvalidRequest(
+            "The poll already belongs to this user.".to_string(),
+        ));
+    }
+
+    db.user_repository
+        .find_user(&data.new_owner)
+        .await
+        .map_err(|err| PollError::GeneralError(err.to_string()))?
+        .ok_or_else(|| PollError::UserNotFound(data.new_owner.clone()))?;
+
+    db.poll_repository
+        .transfer_poll_ownership(&id, &username, &data.new_owner)
+        .await?;
 
+    let poll = get_poll_utility(&db, &id).await?;
     broadcaster.lock().unwrap().send_updated_poll(&poll);
-    let response = calculate_poll_results(&poll);
-    broadcaster.lock().unwrap().send_poll_results(&response);
-    HttpResponse::Ok().body("Closed poll successfully.")
+
+    Ok(HttpResponse::Ok().body("Ownership transferred successfully."))
 }
 
 #[utoipa::path(
     post,
-    path = "/api/polls/{id}/reset",
-    request_body = UserNameRequest,
+    path = "/api/polls/{id}/report",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the poll")
+    ),
+    request_body = PollReportRequest,
     responses(
-        (status = 200, description = "Poll reset successfully"),
-        (status = 404, description = "Poll not found"),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Report added to the moderation queue", body = Report),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 409, description = "The user already has an open report on this poll", body = ErrorResponse),
+        (status = 422, description = "Report failed validation, or the poll is the reporter's own", body = ErrorResponse),
+        (status = 429, description = "The user filed too many reports in the last hour", body = ErrorResponse),
+        (status = 451, description = "Poll was hidden by moderators", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "Polls",
-    operation_id = "resetPoll",
+    operation_id = "reportPoll",
     security(
         ("bearerAuth" = [])
     )
 )]
-async fn reset_votes_by_id(
+async fn report_poll(
+    req: HttpRequest,
     db: Data<MongoDB>,
     id: Path<String>,
-    data: web::Json<UserNameRequest>,
-    broadcaster: Data<Mutex<Broadcaster>>,
-) -> impl Responder {
-    let username = &data.username;
+    data: web::Json<PollReportRequest>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, PollError> {
+    let username = viewer_from_request(&req).unwrap_or_default();
+
+    validate_report(&data, &app_config.poll_limits)?;
 
-    if let Err(err) = db.poll_repository.reset_poll_by_id(&id, username).await {
-        return HttpResponse::InternalServerError().body(format!("Error resetting poll : {}", err));
+    let poll = get_visible_poll(&db, &id, Some(&username)).await?;
+
+    if poll.has_role(Some(&username), PollRole::Editor) {
+        return Err(PollError::InvalidRequest(
+            "Polls you manage cannot be reported.".to_string(),
+        ));
     }
 
-    let poll = match get_poll_utility(&db, &id).await {
-        Some(poll) => poll,
-        None => {
-            return HttpResponse::Ok().body("No poll found with given id.");
-        }
+    let now = Utc::now();
+    let recent_reports = db
+        .report_repository
+        .count_reports_since(&username, now - Duration::hours(1))
+        .await?;
+    if recent_reports >= app_config.reports_per_hour {
+        return Err(PollError::TooManyReports(format!(
+            "At most {} reports can be filed per hour.",
+            app_config.reports_per_hour
+        )));
+    }
+
+    if db.report_repository.has_open_report(&id, &username).await? {
+        return Err(PollError::AlreadyReported(
+            "You already reported this poll.".to_string(),
+        ));
+    }
+
+    let report = Report {
+        report_id: nanoid!(10),
+        poll_id: poll.poll_id.clone(),
+        poll_title: poll.title.clone(),
+        reporter: username.clone(),
+        category: data.category,
+        comment: data
+            .comment
+            .as_ref()
+            .map(|comment| comment.trim().to_string())
+            .filter(|comment| !comment.is_empty()),
+        status: ReportStatus::Open,
+        history: Vec::new(),
+        created_at: now,
+        updated_at: now,
     };
 
-    broadcaster.lock().unwrap().send_updated_poll(&poll);
-    let response = calculate_poll_results(&poll);
-    broadcaster.lock().unwrap().send_poll_results(&response);
+    db.report_repository.create_report(&report).await?;
 
-    HttpResponse::Ok().body("Poll reset successfully.")
+    Ok(HttpResponse::Ok().json(report))
 }
 
-pub fn init(config: &mut web::ServiceConfig) -> () {
+pub fn init(config: &mut web::ServiceConfig) {
     config
         .service(get_all_polls)
+        .service(search_polls)
         .service(get_poll_by_id)
+        .service(get_vote_challenge)
+        .service(start_guest_session)
+        .service(cast_guest_vote)
+        .service(get_poll_revisions)
         .service(fetch_results_by_id)
+        .service(get_text_answers)
+        .service(get_quiz_leaderboard)
+        .service(export_schedule_ics)
         .service(
             web::scope("/polls")
                 .wrap(actix_web::middleware::from_fn(jwt_middleware))
                 .route("/", web::post().to(create_new_poll))
                 .route("/{id}/vote", web::post().to(cast_vote_to_poll))
+                .route("/{id}/vote", web::delete().to(retract_vote))
                 .route("/{id}/close", web::post().to(close_poll_by_id))
-                .route("/{id}/reset", web::post().to(reset_votes_by_id)),
+                .route("/{id}/reset", web::post().to(reset_votes_by_id))
+                .route("/{id}", web::patch().to(edit_poll_by_id))
+                .route("/{id}", web::delete().to(delete_poll_by_id))
+                .route("/{id}/restore", web::post().to(restore_poll_by_id))
+                .route("/{id}/report", web::post().to(report_poll))
+                .route("/{id}/invites", web::post().to(create_poll_invite))
+                .route("/{id}/invites", web::get().to(get_poll_invites))
+                .route(
+                    "/{id}/invites/{token}",
+                    web::delete().to(revoke_poll_invite),
+                )
+                .route("/{id}/access-code", web::post().to(create_access_code))
+                .route("/{id}/access-code", web::delete().to(revoke_access_code))
+                .route("/{id}/join", web::post().to(join_poll))
+                .route("/{id}/collaborators", web::get().to(get_collaborators))
+                .route("/{id}/collaborators", web::post().to(add_collaborator))
+                .route(
+                    "/{id}/collaborators/{username}",
+                    web::delete().to(remove_collaborator),
+                )
+                .route("/{id}/owner", web::post().to(transfer_ownership))
+                .route(
+                    "/{id}/write-ins/{option_id}/visibility",
+                    web::post().to(set_write_in_visibility),
+                )
+                .route(
+                    "/{id}/write-ins/{option_id}/merge",
+                    web::post().to(merge_write_in),
+                )
+                .route("/{id}/answers", web::post().to(submit_text_answer))
+                .route(
+                    "/{id}/answers/{answer_id}/moderation",
+                    web::post().to(moderate_text_answer),
+                )
+                .route("/{id}/availability", web::post().to(submit_availability))
+                .route(
+                    "/{id}/schedule/choice",
+                    web::post().to(choose_schedule_slot),
+                ),
         );
-
-    ()
 }
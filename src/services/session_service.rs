@@ -0,0 +1,290 @@
+use actix_web::{
+    get,
+    web::{self, Data, Path},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use nanoid::nanoid;
+use std::sync::Mutex;
+
+use crate::{
+    config::{config::AppConfig, session_config::SessionError},
+    db::mongodb_repository::MongoDB,
+    middlewares::jwt_middleware::{claims_from_request, jwt_middleware},
+    models::{broadcaster_model::Broadcaster, session_model::Session},
+    utils::{
+        access_code_utility::{generate_access_code, normalize_access_code},
+        quiz_utility::open_quiz_question,
+        session_utility::{validate_session_creation, validate_session_polls},
+        types::{SessionCreation, SessionPosition},
+    },
+};
+
+// A fresh join code is drawn whenever the previous one is already in use.
+const JOIN_CODE_ATTEMPTS: usize = 5;
+
+fn viewer_from_request(req: &HttpRequest) -> String {
+    claims_from_request(req)
+        .map(|claims| claims.sub)
+        .unwrap_or_default()
+}
+
+// A quiz question presented in the session starts its clock when the presenter first
+// reaches it.
+async fn open_current_question(db: &Data<MongoDB>, session: &Session) -> Result<(), SessionError> {
+    let poll_id = match session.current_poll_id() {
+        Some(poll_id) => poll_id,
+        None => return Ok(()),
+    };
+
+    let poll = db
+        .poll_repository
+        .get_poll_by_id(poll_id)
+        .await
+        .map_err(|err| SessionError::GeneralError(err.to_string()))?;
+
+    if let Some(mut quiz) = poll.and_then(|poll| poll.quiz) {
+        if quiz.opened_at.is_none() {
+            open_quiz_question(&mut quiz, Utc::now());
+            db.poll_repository
+                .open_quiz_question(poll_id, &quiz)
+                .await
+                .map_err(|err| SessionError::GeneralError(err.to_string()))?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_session_utility(db: &Data<MongoDB>, id: &str) -> Result<Session, SessionError> {
+    db.session_repository
+        .get_session_by_id(id)
+        .await?
+        .ok_or_else(|| SessionError::SessionNotFound(id.to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the session")
+    ),
+    responses(
+        (status = 200, description = "Current state of the session", body = SessionState),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sessions",
+    operation_id = "getSessionState"
+)]
+#[get("/sessions/{id}")]
+async fn get_session_state(
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, SessionError> {
+    let session = get_session_utility(&db, &id).await?;
+
+    Ok(HttpResponse::Ok().json(session.state()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sessions/join/{code}",
+    params(
+        ("code" = String, Path, description = "The short code shown by the presenter")
+    ),
+    responses(
+        (status = 200, description = "State of the session to follow", body = SessionState),
+        (status = 404, description = "No running session uses this code", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sessions",
+    operation_id = "joinSessionByCode"
+)]
+#[get("/sessions/join/{code}")]
+async fn join_session_by_code(
+    db: Data<MongoDB>,
+    code: Path<String>,
+) -> Result<HttpResponse, SessionError> {
+    let code = normalize_access_code(&code);
+
+    let session = db
+        .session_repository
+        .get_session_by_join_code(&code)
+        .await?
+        .ok_or(SessionError::SessionNotFound(code))?;
+
+    Ok(HttpResponse::Ok().json(session.state()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sessions/",
+    request_body = SessionCreation,
+    responses(
+        (status = 200, description = "Session created, not started yet", body = Session),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 422, description = "Session failed validation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sessions",
+    operation_id = "createSession",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn create_new_session(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    data: web::Json<SessionCreation>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, SessionError> {
+    let username = viewer_from_request(&req);
+
+    validate_session_creation(&data, &app_config.poll_limits)?;
+
+    let mut polls = Vec::with_capacity(data.poll_ids.len());
+    for poll_id in &data.poll_ids {
+        let poll = db
+            .poll_repository
+            .get_poll_by_id(poll_id)
+            .await
+            .map_err(|err| SessionError::GeneralError(err.to_string()))?;
+        polls.push(poll);
+    }
+    validate_session_polls(&username, &polls)?;
+
+    let now = Utc::now();
+    let mut session = Session {
+        session_id: nanoid!(10),
+        username: username.clone(),
+        title: data.title.trim().to_string(),
+        poll_ids: data.poll_ids.clone(),
+        current_index: None,
+        join_code: String::new(),
+        is_active: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let mut created = false;
+    for _ in 0..JOIN_CODE_ATTEMPTS {
+        session.join_code = generate_access_code();
+
+        if db
+            .session_repository
+            .create_session(&session)
+            .await?
+            .is_some()
+        {
+            created = true;
+            break;
+        }
+    }
+    if !created {
+        return Err(SessionError::SessionCreationError(
+            "Could not allocate a join code.".to_string(),
+        ));
+    }
+
+    db.poll_repository
+        .attach_polls_to_session(&session.poll_ids, &session.username, &session.session_id)
+        .await
+        .map_err(|err| SessionError::GeneralError(err.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(session))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/position",
+    request_body = SessionPosition,
+    responses(
+        (status = 200, description = "Presenter moved to the poll at the given index", body = SessionState),
+        (status = 403, description = "Only the presenter can control the session", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+        (status = 409, description = "The session has ended", body = ErrorResponse),
+        (status = 422, description = "Index is outside the session", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sessions",
+    operation_id = "moveSession",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn move_session(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<SessionPosition>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, SessionError> {
+    let username = viewer_from_request(&req);
+
+    let session = get_session_utility(&db, &id).await?;
+
+    if data.index >= session.poll_ids.len() {
+        return Err(SessionError::InvalidRequest(format!(
+            "The session has {} polls, index {} is out of range.",
+            session.poll_ids.len(),
+            data.index
+        )));
+    }
+
+    let session = db
+        .session_repository
+        .set_current_index(&id, &username, data.index)
+        .await?;
+    open_current_question(&db, &session).await?;
+
+    let state = session.state();
+    broadcaster.lock().unwrap().send_session_state(&state);
+
+    Ok(HttpResponse::Ok().json(state))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/end",
+    responses(
+        (status = 200, description = "Session ended, none of its polls take votes anymore", body = SessionState),
+        (status = 403, description = "Only the presenter can control the session", body = ErrorResponse),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+        (status = 409, description = "The session has already ended", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Sessions",
+    operation_id = "endSession",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn end_session(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, SessionError> {
+    let username = viewer_from_request(&req);
+
+    let session = db.session_repository.end_session(&id, &username).await?;
+
+    let state = session.state();
+    broadcaster.lock().unwrap().send_session_state(&state);
+
+    Ok(HttpResponse::Ok().json(state))
+}
+
+pub fn init(config: &mut web::ServiceConfig) {
+    config
+        .service(join_session_by_code)
+        .service(get_session_state)
+        .service(
+            web::scope("/sessions")
+                .wrap(actix_web::middleware::from_fn(jwt_middleware))
+                .route("/", web::post().to(create_new_session))
+                .route("/{id}/position", web::post().to(move_session))
+                .route("/{id}/end", web::post().to(end_session)),
+        );
+}
@@ -1,24 +1,85 @@
 use actix_web::{
     get, post,
-    web::{self, Data},
-    HttpResponse, Responder,
+    web::{self, Data, Query},
+    HttpRequest, HttpResponse, Responder,
 };
 use std::sync::Mutex;
 
-use crate::models::broadcaster_model::Broadcaster;
+use crate::{
+    config::{poll_config::PollError, session_config::SessionError},
+    db::mongodb_repository::MongoDB,
+    middlewares::jwt_middleware::claims_from_request,
+    models::{broadcaster_model::Broadcaster, poll_model::SubscriptionParams},
+};
 
 pub async fn protected_route() -> HttpResponse {
     HttpResponse::Ok().body("Protected data")
 }
 
 #[get("/create-client")]
-async fn create_client(broadcaster: Data<Mutex<Broadcaster>>) -> impl Responder {
+async fn create_client(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    query: Query<SubscriptionParams>,
+    broadcaster: Data<Mutex<Broadcaster>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let viewer = claims_from_request(&req).map(|claims| claims.sub);
+    let SubscriptionParams {
+        poll_id,
+        session_id,
+    } = query.into_inner();
+
+    let org_ids = match &viewer {
+        Some(viewer) => {
+            db.organization_repository
+                .get_organization_ids_for_user(viewer)
+                .await?
+        }
+        None => vec![],
+    };
+
+    if let Some(session_id) = session_id {
+        if db
+            .session_repository
+            .get_session_by_id(&session_id)
+            .await?
+            .is_none()
+        {
+            return Err(SessionError::SessionNotFound(session_id).into());
+        }
+
+        let mut broadcaster = broadcaster.lock().unwrap();
+        let client = broadcaster.new_session_client(viewer.clone(), session_id);
+        if let Some(viewer) = &viewer {
+            broadcaster.set_viewer_organizations(viewer, &org_ids);
+        }
+
+        return Ok(HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .streaming(client));
+    }
+
+    if let Some(poll_id) = &poll_id {
+        let visible = db
+            .poll_repository
+            .get_poll_by_id(poll_id)
+            .await?
+            .is_some_and(|poll| poll.can_view_in_organizations(viewer.as_deref(), &org_ids));
+
+        if !visible {
+            return Err(PollError::PollNotFound(poll_id.to_string()).into());
+        }
+    }
+
     let mut broadcaster = broadcaster.lock().unwrap();
-    let client = broadcaster.new_client();
+    let client = broadcaster.new_client(viewer.clone(), poll_id);
+    if let Some(viewer) = &viewer {
+        broadcaster.set_viewer_organizations(viewer, &org_ids);
+    }
 
-    HttpResponse::Ok()
+    Ok(HttpResponse::Ok()
         .content_type("text/event-stream")
-        .streaming(client)
+        .streaming(client))
 }
 
 #[post("/send")]
@@ -29,8 +90,6 @@ async fn send_message(broadcaster: Data<Mutex<Broadcaster>>) -> impl Responder {
         .send("This is coming from backend.");
     HttpResponse::Ok().body("Message sent")
 }
-pub fn init(config: &mut web::ServiceConfig) -> () {
+pub fn init(config: &mut web::ServiceConfig) {
     config.service(create_client).service(send_message);
-
-    ()
 }
@@ -0,0 +1,265 @@
+use actix_web::{
+    get,
+    web::{self, Data, Path},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use nanoid::nanoid;
+
+use crate::{
+    config::{config::AppConfig, survey_config::SurveyError},
+    db::mongodb_repository::MongoDB,
+    middlewares::jwt_middleware::{claims_from_request, jwt_middleware},
+    models::survey_model::{Survey, SurveyResponse},
+    utils::{
+        survey_utility::{
+            build_survey, calculate_survey_results, export_survey_csv, validate_submission,
+            validate_survey_creation,
+        },
+        types::{SurveyCreation, SurveySubmission},
+    },
+};
+
+fn viewer_from_request(req: &HttpRequest) -> String {
+    claims_from_request(req)
+        .map(|claims| claims.sub)
+        .unwrap_or_default()
+}
+
+async fn get_survey_utility(db: &Data<MongoDB>, id: &str) -> Result<Survey, SurveyError> {
+    db.survey_repository
+        .get_survey_by_id(id)
+        .await?
+        .ok_or_else(|| SurveyError::SurveyNotFound(id.to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/surveys/{id}",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the survey")
+    ),
+    responses(
+        (status = 200, description = "Successfully fetched survey details", body = Survey),
+        (status = 404, description = "Survey not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Surveys",
+    operation_id = "getSurveyById"
+)]
+#[get("/surveys/{id}")]
+async fn get_survey_by_id(
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, SurveyError> {
+    let survey = get_survey_utility(&db, &id).await?;
+
+    Ok(HttpResponse::Ok().json(survey))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/surveys/{id}/results",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the survey")
+    ),
+    responses(
+        (status = 200, description = "Successfully fetched survey results", body = SurveyResults),
+        (status = 404, description = "Survey not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Surveys",
+    operation_id = "getSurveyResults"
+)]
+#[get("/surveys/{id}/results")]
+async fn fetch_survey_results(
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, SurveyError> {
+    let survey = get_survey_utility(&db, &id).await?;
+
+    let responses = db.survey_repository.get_responses_by_survey_id(&id).await?;
+
+    Ok(HttpResponse::Ok().json(calculate_survey_results(&survey, &responses)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/surveys/",
+    request_body = SurveyCreation,
+    responses(
+        (status = 200, description = "Survey created successfully", body = Survey),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 422, description = "Survey failed validation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Surveys",
+    operation_id = "createSurvey",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn create_new_survey(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    data: web::Json<SurveyCreation>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, SurveyError> {
+    let username = viewer_from_request(&req);
+
+    validate_survey_creation(&data, &app_config.poll_limits)?;
+
+    let survey = build_survey(&data, &username, Utc::now());
+
+    db.survey_repository.create_survey(&survey).await?;
+
+    Ok(HttpResponse::Ok().json(survey))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/surveys/{id}/responses",
+    request_body = SurveySubmission,
+    responses(
+        (status = 200, description = "Response submitted successfully"),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 404, description = "Survey not found", body = ErrorResponse),
+        (status = 409, description = "Survey is closed or already answered", body = ErrorResponse),
+        (status = 422, description = "Answers failed validation", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Surveys",
+    operation_id = "submitSurveyResponse",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn submit_survey_response(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    data: web::Json<SurveySubmission>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, SurveyError> {
+    let username = viewer_from_request(&req);
+
+    let survey = get_survey_utility(&db, &id).await?;
+
+    if !survey.is_active {
+        return Err(SurveyError::SurveyClosed(
+            "Cannot respond to a closed survey.".to_string(),
+        ));
+    }
+
+    if survey.respondents.contains(&username) {
+        return Err(SurveyError::AlreadyResponded(
+            "Survey already answered by this user.".to_string(),
+        ));
+    }
+
+    let answers = validate_submission(&survey, &data.answers, &app_config.poll_limits)?;
+
+    let response = SurveyResponse {
+        response_id: nanoid!(10),
+        survey_id: survey.survey_id,
+        username: username.clone(),
+        answers,
+        created_at: Utc::now(),
+    };
+
+    db.survey_repository.submit_response(&response).await?;
+
+    Ok(HttpResponse::Ok().body("Response submitted successfully."))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/surveys/{id}/export",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the survey")
+    ),
+    responses(
+        (status = 200, description = "CSV export with one row per response", content_type = "text/csv", body = String),
+        (status = 403, description = "Only the creator can export the survey", body = ErrorResponse),
+        (status = 404, description = "Survey not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Surveys",
+    operation_id = "exportSurvey",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn export_survey(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, SurveyError> {
+    let survey = get_survey_utility(&db, &id).await?;
+
+    let viewer = claims_from_request(&req).map(|claims| claims.sub);
+    if viewer.as_deref() != Some(survey.username.as_str()) {
+        return Err(SurveyError::SurveyUnauthorizedAccess(
+            "Only the creator can export the survey.".to_string(),
+        ));
+    }
+
+    let responses = db.survey_repository.get_responses_by_survey_id(&id).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"survey-{}.csv\"", survey.survey_id),
+        ))
+        .body(export_survey_csv(&survey, &responses)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/surveys/{id}/close",
+    responses(
+        (status = 200, description = "Survey closed successfully"),
+        (status = 403, description = "Only the creator can close the survey", body = ErrorResponse),
+        (status = 404, description = "Survey not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Surveys",
+    operation_id = "closeSurvey",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn close_survey_by_id(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, SurveyError> {
+    let username = viewer_from_request(&req);
+
+    let survey = get_survey_utility(&db, &id).await?;
+
+    if survey.username != username {
+        return Err(SurveyError::SurveyUnauthorizedAccess(
+            "Only the creator can close the survey.".to_string(),
+        ));
+    }
+
+    db.survey_repository.close_survey_by_id(&id).await?;
+
+    Ok(HttpResponse::Ok().body("Closed survey successfully."))
+}
+
+pub fn init(config: &mut web::ServiceConfig) {
+    config
+        .service(get_survey_by_id)
+        .service(fetch_survey_results)
+        .service(
+            web::scope("/surveys")
+                .wrap(actix_web::middleware::from_fn(jwt_middleware))
+                .route("/", web::post().to(create_new_survey))
+                .route("/{id}/responses", web::post().to(submit_survey_response))
+                .route("/{id}/export", web::get().to(export_survey))
+                .route("/{id}/close", web::post().to(close_survey_by_id)),
+        );
+}
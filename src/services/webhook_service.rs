@@ -0,0 +1,333 @@
+use actix_web::{
+    web::{self, Data, Path, Query},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use log::error;
+
+use crate::{
+    config::{config::AppConfig, poll_config::PollError, webhook_config::WebhookError},
+    db::mongodb_repository::MongoDB,
+    middlewares::jwt_middleware::{claims_from_request, jwt_middleware},
+    models::{
+        poll_model::{PageParams, Poll, PollRole},
+        webhook_model::{Webhook, WebhookDeliveryPage, WebhookEvent, WebhookSummary},
+    },
+    services::poll_service::{ensure_poll_role, poll_results},
+    utils::{
+        types::{WebhookCreation, WebhookRegistration},
+        webhook_utility::{
+            build_delivery, build_event_body, build_redelivery, build_webhook,
+            validate_webhook_creation,
+        },
+    },
+};
+
+const DEFAULT_DELIVERY_PAGE_SIZE: u64 = 20;
+const MAX_DELIVERY_PAGE_SIZE: u64 = 100;
+
+fn map_poll_error(err: PollError) -> WebhookError {
+    match err {
+        PollError::PollNotFound(poll_id) => WebhookError::PollNotFound(poll_id),
+        PollError::PollUnauthorizedAccess(msg) => WebhookError::WebhookUnauthorizedAccess(msg),
+        err => WebhookError::GeneralError(err.to_string()),
+    }
+}
+
+fn viewer_from_request(req: &HttpRequest) -> String {
+    claims_from_request(req)
+        .map(|claims| claims.sub)
+        .unwrap_or_default()
+}
+
+// Webhooks are reported as missing to anyone but their owner.
+async fn get_owned_webhook(
+    db: &Data<MongoDB>,
+    id: &str,
+    username: &str,
+) -> Result<Webhook, WebhookError> {
+    db.webhook_repository
+        .get_webhook_by_id(id)
+        .await?
+        .filter(|webhook| webhook.owner == username)
+        .ok_or_else(|| WebhookError::WebhookNotFound(id.to_string()))
+}
+
+// Queues a delivery of the event for every webhook subscribed to it. Webhooks are
+// notified on a best-effort basis: failing to queue is logged and never fails the
+// request that triggered the event.
+pub(crate) async fn queue_webhook_event(db: &Data<MongoDB>, event: WebhookEvent, poll: &Poll) {
+    if let Err(err) = try_queue_webhook_event(db, event, poll).await {
+        error!(
+            "Failed to queue {} webhooks for poll {}: {}",
+            event.name(),
+            poll.poll_id,
+            err
+        );
+    }
+}
+
+async fn try_queue_webhook_event(
+    db: &Data<MongoDB>,
+    event: WebhookEvent,
+    poll: &Poll,
+) -> Result<(), WebhookError> {
+    let webhooks = db
+        .webhook_repository
+        .get_subscribed_webhooks(&poll.poll_id, &poll.username, event)
+        .await?;
+
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let results = poll_results(db, poll).await.map_err(map_poll_error)?;
+    let body = build_event_body(event, poll, &results, now);
+
+    let mut deliveries = vec![];
+    for webhook in &webhooks {
+        // Poll webhooks stop receiving events once their owner no longer manages the
+        // poll, since the payload includes its results.
+        if webhook.poll_id.is_some()
+            && ensure_poll_role(db, poll, &webhook.owner, PollRole::Editor, "")
+                .await
+                .is_err()
+        {
+            continue;
+        }
+
+        deliveries.push(build_delivery(webhook, event, &poll.poll_id, &body, now));
+    }
+
+    db.webhook_repository.queue_deliveries(&deliveries).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/",
+    request_body = WebhookCreation,
+    responses(
+        (status = 200, description = "Webhook registered, with the secret its deliveries are signed with", body = WebhookRegistration),
+        (status = 400, description = "Malformed request body", body = ErrorResponse),
+        (status = 403, description = "Caller cannot manage the poll", body = ErrorResponse),
+        (status = 404, description = "Poll not found", body = ErrorResponse),
+        (status = 422, description = "Webhook failed validation or too many webhooks", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Webhooks",
+    operation_id = "createWebhook",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn create_webhook(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    data: web::Json<WebhookCreation>,
+    app_config: Data<AppConfig>,
+) -> Result<HttpResponse, WebhookError> {
+    let username = viewer_from_request(&req);
+
+    validate_webhook_creation(&data, app_config.webhooks.allow_private_targets)?;
+
+    if let Some(poll_id) = &data.poll_id {
+        let poll = db
+            .poll_repository
+            .get_poll_by_id(poll_id)
+            .await
+            .map_err(map_poll_error)?
+            .ok_or_else(|| WebhookError::PollNotFound(poll_id.clone()))?;
+
+        ensure_poll_role(
+            &db,
+            &poll,
+            &username,
+            PollRole::Editor,
+            "Only owners and editors can register webhooks for this poll.",
+        )
+        .await
+        .map_err(map_poll_error)?;
+    }
+
+    let registered = db.webhook_repository.count_webhooks(&username).await?;
+
+    if registered >= app_config.webhooks.max_per_user {
+        return Err(WebhookError::InvalidRequest(format!(
+            "An account can register at most {} webhooks.",
+            app_config.webhooks.max_per_user
+        )));
+    }
+
+    let webhook = build_webhook(&data, &username, Utc::now());
+
+    db.webhook_repository.create_webhook(&webhook).await?;
+
+    Ok(HttpResponse::Ok().json(WebhookRegistration {
+        secret: webhook.secret.clone(),
+        webhook: webhook.into(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/",
+    responses(
+        (status = 200, description = "Webhooks registered by the caller, newest first", body = Vec<WebhookSummary>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Webhooks",
+    operation_id = "getWebhooks",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_webhooks(req: HttpRequest, db: Data<MongoDB>) -> Result<HttpResponse, WebhookError> {
+    let viewer = viewer_from_request(&req);
+
+    let webhooks: Vec<WebhookSummary> = db
+        .webhook_repository
+        .get_webhooks_by_owner(&viewer)
+        .await?
+        .into_iter()
+        .map(WebhookSummary::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(webhooks))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the webhook")
+    ),
+    responses(
+        (status = 200, description = "Webhook and its delivery log deleted"),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Webhooks",
+    operation_id = "deleteWebhook",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn delete_webhook(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+) -> Result<HttpResponse, WebhookError> {
+    let viewer = viewer_from_request(&req);
+    let webhook = get_owned_webhook(&db, &id, &viewer).await?;
+
+    db.webhook_repository
+        .delete_webhook(&webhook.webhook_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().body("Webhook deleted successfully."))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{id}/deliveries",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the webhook"),
+        ("page" = Option<u64>, Query, description = "Page number, starting at 1"),
+        ("limit" = Option<u64>, Query, description = "Number of deliveries per page")
+    ),
+    responses(
+        (status = 200, description = "Deliveries of the webhook with every attempt, newest first", body = WebhookDeliveryPage),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Webhooks",
+    operation_id = "getWebhookDeliveries",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn get_webhook_deliveries(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    id: Path<String>,
+    query: Query<PageParams>,
+) -> Result<HttpResponse, WebhookError> {
+    let viewer = viewer_from_request(&req);
+    let webhook = get_owned_webhook(&db, &id, &viewer).await?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_PAGE_SIZE)
+        .clamp(1, MAX_DELIVERY_PAGE_SIZE);
+
+    let (total, deliveries) = db
+        .webhook_repository
+        .get_deliveries(&webhook.webhook_id, (page - 1) * limit, limit as i64)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(WebhookDeliveryPage {
+        page,
+        limit,
+        total,
+        deliveries,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{id}/deliveries/{delivery_id}/redeliver",
+    params(
+        ("id" = String, Path, description = "The unique identifier of the webhook"),
+        ("delivery_id" = String, Path, description = "The delivery to send again")
+    ),
+    responses(
+        (status = 200, description = "Delivery queued again with the same body", body = WebhookDelivery),
+        (status = 404, description = "Webhook or delivery not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "Webhooks",
+    operation_id = "redeliverWebhook",
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+async fn redeliver_webhook(
+    req: HttpRequest,
+    db: Data<MongoDB>,
+    path: Path<(String, String)>,
+) -> Result<HttpResponse, WebhookError> {
+    let (id, delivery_id) = path.into_inner();
+    let viewer = viewer_from_request(&req);
+    let webhook = get_owned_webhook(&db, &id, &viewer).await?;
+
+    let original = db
+        .webhook_repository
+        .get_delivery(&webhook.webhook_id, &delivery_id)
+        .await?
+        .ok_or_else(|| WebhookError::DeliveryNotFound(delivery_id.clone()))?;
+
+    let redelivery = build_redelivery(&original, Utc::now());
+
+    db.webhook_repository
+        .queue_deliveries(std::slice::from_ref(&redelivery))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(redelivery))
+}
+
+pub fn init(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/webhooks")
+            .wrap(actix_web::middleware::from_fn(jwt_middleware))
+            .route("/", web::post().to(create_webhook))
+            .route("/", web::get().to(get_webhooks))
+            .route("/{id}", web::delete().to(delete_webhook))
+            .route("/{id}/deliveries", web::get().to(get_webhook_deliveries))
+            .route(
+                "/{id}/deliveries/{delivery_id}/redeliver",
+                web::post().to(redeliver_webhook),
+            ),
+    );
+}
@@ -0,0 +1,17 @@
+use nanoid::nanoid;
+
+// Access codes are typed by hand, so leave out characters that are easy to confuse.
+const ACCESS_CODE_ALPHABET: [char; 31] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J', 'K', 'L', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U',
+    'V', 'W', 'X', 'Y', 'Z', '2', '3', '4', '5', '6', '7', '8',
+];
+const ACCESS_CODE_LENGTH: usize = 6;
+
+pub fn generate_access_code() -> String {
+    nanoid!(ACCESS_CODE_LENGTH, &ACCESS_CODE_ALPHABET)
+}
+
+// Codes are shown in upper case but accepted however they are typed.
+pub fn normalize_access_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
@@ -0,0 +1,81 @@
+use chrono::Utc;
+use nanoid::nanoid;
+
+use crate::models::{
+    audit_model::{AuditAction, AuditEntry},
+    user_model::{User, UserRole},
+};
+
+// Actor recorded for changes made on behalf of the deployment configuration.
+pub const CONFIG_ACTOR: &str = "config";
+
+pub fn audit_entry(
+    actor: &str,
+    action: AuditAction,
+    target: &str,
+    details: Option<String>,
+) -> AuditEntry {
+    AuditEntry {
+        audit_id: nanoid!(10),
+        actor: actor.to_string(),
+        action,
+        target: target.to_string(),
+        details,
+        created_at: Utc::now(),
+    }
+}
+
+// The configured admin is promoted when they sign in while the site has no admin at
+// all. Once admins exist they manage the role themselves, so a revoked bootstrap admin
+// stays revoked.
+pub fn needs_admin_bootstrap(
+    admin_username: Option<&str>,
+    user: &User,
+    admin_exists: bool,
+) -> bool {
+    !admin_exists && admin_username == Some(user.username.as_str()) && user.role != UserRole::Admin
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(username: &str, role: UserRole) -> User {
+        User {
+            username: username.to_string(),
+            sk: serde_json::Value::Null,
+            role,
+            disabled: false,
+        }
+    }
+
+    #[test]
+    fn test_needs_admin_bootstrap() {
+        assert!(needs_admin_bootstrap(
+            Some("root"),
+            &user("root", UserRole::User),
+            false
+        ));
+        assert!(!needs_admin_bootstrap(
+            Some("root"),
+            &user("root", UserRole::Admin),
+            false
+        ));
+        assert!(!needs_admin_bootstrap(
+            Some("root"),
+            &user("alice", UserRole::User),
+            false
+        ));
+        assert!(!needs_admin_bootstrap(
+            None,
+            &user("root", UserRole::User),
+            false
+        ));
+        // Revoked by another admin.
+        assert!(!needs_admin_bootstrap(
+            Some("root"),
+            &user("root", UserRole::User),
+            true
+        ));
+    }
+}
@@ -0,0 +1,36 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Derives the participation marker stored for a ballot in an anonymous poll. The marker
+// is keyed with a server secret so it cannot be recomputed from a list of usernames, and
+// it includes the poll id so markers of the same voter cannot be linked across polls.
+pub fn participation_marker(secret: &str, poll_id: &str, username: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+
+    mac.update(poll_id.as_bytes());
+    mac.update(b":");
+    mac.update(username.as_bytes());
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_participation_marker_is_stable_and_unlinkable() {
+        let marker = participation_marker("secret", "poll1", "Azeem");
+
+        assert_eq!(marker, participation_marker("secret", "poll1", "Azeem"));
+        assert_eq!(marker.len(), 64);
+        assert!(!marker.contains("Azeem"));
+
+        assert_ne!(marker, participation_marker("secret", "poll2", "Azeem"));
+        assert_ne!(marker, participation_marker("secret", "poll1", "Other"));
+        assert_ne!(marker, participation_marker("other", "poll1", "Azeem"));
+    }
+}
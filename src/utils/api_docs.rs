@@ -4,14 +4,78 @@ use utoipa::OpenApi;
 #[openapi(
         paths(
             crate::services::poll_service::get_all_polls,
+            crate::services::poll_service::search_polls,
             crate::services::poll_service::get_poll_by_id,
+            crate::services::poll_service::get_vote_challenge,
+            crate::services::poll_service::get_poll_revisions,
             crate::services::poll_service::fetch_results_by_id,
             crate::services::poll_service::get_all_polls,
             crate::services::poll_service::cast_vote_to_poll,
+            crate::services::poll_service::start_guest_session,
+            crate::services::poll_service::cast_guest_vote,
+            crate::services::poll_service::retract_vote,
             crate::services::poll_service::close_poll_by_id,
             crate::services::poll_service::reset_votes_by_id,
+            crate::services::poll_service::edit_poll_by_id,
+            crate::services::poll_service::delete_poll_by_id,
+            crate::services::poll_service::restore_poll_by_id,
+            crate::services::poll_service::report_poll,
+            crate::services::poll_service::create_poll_invite,
+            crate::services::poll_service::get_poll_invites,
+            crate::services::poll_service::revoke_poll_invite,
+            crate::services::poll_service::create_access_code,
+            crate::services::poll_service::revoke_access_code,
+            crate::services::poll_service::join_poll,
+            crate::services::poll_service::get_collaborators,
+            crate::services::poll_service::add_collaborator,
+            crate::services::poll_service::remove_collaborator,
+            crate::services::poll_service::transfer_ownership,
+            crate::services::poll_service::set_write_in_visibility,
+            crate::services::poll_service::merge_write_in,
+            crate::services::poll_service::get_text_answers,
+            crate::services::poll_service::submit_text_answer,
+            crate::services::poll_service::moderate_text_answer,
+            crate::services::poll_service::get_quiz_leaderboard,
+            crate::services::poll_service::submit_availability,
+            crate::services::poll_service::choose_schedule_slot,
+            crate::services::poll_service::export_schedule_ics,
+            crate::services::session_service::get_session_state,
+            crate::services::session_service::join_session_by_code,
+            crate::services::session_service::create_new_session,
+            crate::services::session_service::move_session,
+            crate::services::session_service::end_session,
+            crate::services::admin_service::list_users,
+            crate::services::admin_service::disable_user,
+            crate::services::admin_service::enable_user,
+            crate::services::admin_service::set_user_role,
+            crate::services::admin_service::force_close_poll,
+            crate::services::admin_service::force_delete_poll,
+            crate::services::admin_service::get_reports,
+            crate::services::admin_service::resolve_report,
+            crate::services::admin_service::get_audit_log,
+            crate::services::organization_service::create_organization,
+            crate::services::organization_service::get_organizations,
+            crate::services::organization_service::get_organization,
+            crate::services::organization_service::get_organization_polls,
+            crate::services::organization_service::add_member,
+            crate::services::organization_service::remove_member,
+            crate::services::survey_service::get_survey_by_id,
+            crate::services::survey_service::fetch_survey_results,
+            crate::services::survey_service::create_new_survey,
+            crate::services::survey_service::submit_survey_response,
+            crate::services::survey_service::export_survey,
+            crate::services::survey_service::close_survey_by_id,
+            crate::services::webhook_service::create_webhook,
+            crate::services::webhook_service::get_webhooks,
+            crate::services::webhook_service::delete_webhook,
+            crate::services::webhook_service::get_webhook_deliveries,
+            crate::services::webhook_service::redeliver_webhook,
+            crate::services::chat_service::handle_chat_command,
+            crate::services::chat_service::handle_chat_action,
         ),
         components(schemas(
+            crate::config::error_response::ErrorResponse,
+            crate::config::error_response::FieldError,
             crate::models::poll_model::Poll,
             crate::models::poll_model::OptionItem,
             crate::models::user_model::User,
@@ -19,12 +83,108 @@ use utoipa::OpenApi;
             crate::models::poll_model::PollResults,
             crate::models::poll_model::PollQueryParams,
             crate::models::poll_model::ResultsOptionItem,
+            crate::models::poll_model::PollSearchParams,
+            crate::models::poll_model::PollSearchResults,
+            crate::models::poll_model::PollSearchHit,
+            crate::models::poll_model::SearchHighlight,
+            crate::models::poll_model::PollRevision,
+            crate::models::poll_model::VoteChallenge,
+            crate::models::poll_model::GuestSession,
+            crate::models::poll_model::RevisionChange,
+            crate::models::poll_model::PollVisibility,
+            crate::models::poll_model::ResultsVisibility,
+            crate::models::poll_model::PollInvite,
+            crate::models::poll_model::InviteKind,
+            crate::models::poll_model::PollRole,
+            crate::models::poll_model::Collaborator,
+            crate::models::poll_model::PollKind,
+            crate::models::poll_model::TextAnswer,
+            crate::models::poll_model::TextAnswerPage,
+            crate::models::poll_model::WordFrequency,
+            crate::models::poll_model::PageParams,
+            crate::models::poll_model::QuizSettings,
+            crate::models::poll_model::Leaderboard,
+            crate::models::poll_model::LeaderboardEntry,
+            crate::models::poll_model::OptionPayload,
+            crate::models::poll_model::TimeSlot,
+            crate::models::poll_model::Availability,
+            crate::models::poll_model::SlotAnswer,
+            crate::models::poll_model::AvailabilityBallot,
             crate::utils::types::PollCreation,
             crate::utils::types::VoteOption,
-            crate::utils::types::UserNameRequest
+            crate::utils::types::ProofOfWork,
+            crate::utils::types::GuestVote,
+            crate::utils::types::PollEdit,
+            crate::utils::types::OptionRename,
+            crate::utils::types::VotedOptionPolicy,
+            crate::utils::types::InviteCreation,
+            crate::utils::types::JoinPollRequest,
+            crate::utils::types::CollaboratorInvite,
+            crate::utils::types::OwnershipTransfer,
+            crate::utils::types::AccessCodeResponse,
+            crate::utils::types::WriteInMerge,
+            crate::utils::types::WriteInVisibility,
+            crate::utils::types::TextAnswerSubmission,
+            crate::utils::types::TextAnswerModeration,
+            crate::utils::types::QuizCreation,
+            crate::utils::types::AvailabilitySubmission,
+            crate::utils::types::SlotChoice,
+            crate::models::session_model::Session,
+            crate::models::session_model::SessionState,
+            crate::utils::types::SessionCreation,
+            crate::utils::types::SessionPosition,
+            crate::models::organization_model::Organization,
+            crate::models::organization_model::OrganizationMember,
+            crate::models::organization_model::OrganizationRole,
+            crate::utils::types::OrganizationCreation,
+            crate::models::user_model::UserRole,
+            crate::models::user_model::UserSummary,
+            crate::models::audit_model::AuditEntry,
+            crate::models::audit_model::AuditAction,
+            crate::models::audit_model::AuditLogPage,
+            crate::models::report_model::Report,
+            crate::models::report_model::ReportCategory,
+            crate::models::report_model::ReportStatus,
+            crate::models::report_model::ReportAction,
+            crate::models::report_model::ReportTransition,
+            crate::models::report_model::ReportPage,
+            crate::utils::types::PollReportRequest,
+            crate::utils::types::ReportResolution,
+            crate::utils::types::UserRoleChange,
+            crate::utils::types::OrganizationMemberInvite,
+            crate::models::survey_model::Survey,
+            crate::models::survey_model::QuestionKind,
+            crate::models::survey_model::SurveyQuestion,
+            crate::models::survey_model::SurveyAnswer,
+            crate::models::survey_model::SurveyResults,
+            crate::models::survey_model::QuestionResults,
+            crate::utils::types::SurveyCreation,
+            crate::utils::types::QuestionCreation,
+            crate::utils::types::SurveySubmission,
+            crate::models::webhook_model::Webhook,
+            crate::models::webhook_model::WebhookSummary,
+            crate::models::webhook_model::WebhookEvent,
+            crate::models::webhook_model::DeliveryStatus,
+            crate::models::webhook_model::WebhookDelivery,
+            crate::models::webhook_model::DeliveryAttempt,
+            crate::models::webhook_model::WebhookDeliveryPage,
+            crate::utils::types::WebhookCreation,
+            crate::utils::types::WebhookRegistration,
+            crate::utils::types::ChatCommand,
+            crate::utils::types::ChatActionForm,
+            crate::utils::types::ChatActionPayload,
+            crate::utils::types::ChatTeam,
+            crate::utils::types::ChatUser,
+            crate::utils::types::ChatAction
         )),
         tags(
             (name = "Polls", description = "Operations related to polls, including creation, voting, and results."),
+            (name = "Surveys", description = "Multi-question surveys submitted as a single response."),
+            (name = "Sessions", description = "Live presentations moving through an ordered list of polls."),
+            (name = "Organizations", description = "Teams sharing a workspace of polls."),
+            (name = "Admin", description = "Site administration, restricted to admins."),
+            (name = "Webhooks", description = "Signed notifications of poll events sent to external endpoints."),
+            (name = "Integrations", description = "Polls run from a team chat with slash commands and vote buttons."),
         )
     )]
 pub struct ApiDoc;
@@ -0,0 +1,396 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::models::poll_model::{Poll, PollKind, PollVisibility, ResultsVisibility};
+use crate::utils::types::PollCreation;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Requests are signed the way Slack signs them: `v0=` and the hex HMAC-SHA256 of
+// `v0:timestamp:body`, keyed with the app's signing secret.
+pub const SIGNATURE_HEADER: &str = "X-Slack-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Slack-Request-Timestamp";
+const SIGNATURE_VERSION: &str = "v0";
+
+pub const CHAT_USAGE: &str =
+    "Create a poll with a quoted question followed by its options, for example `/poll \"Lunch?\" Pizza Sushi Tacos`. Quote options that contain spaces.";
+
+// Vote buttons per row of the message.
+const BUTTONS_PER_ROW: usize = 5;
+// Longest option text shown on a button; chat platforms cut button labels short.
+const BUTTON_TEXT_MAX_LENGTH: usize = 60;
+
+fn signing_mac(secret: &str, timestamp: &str, body: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+
+    mac.update(SIGNATURE_VERSION.as_bytes());
+    mac.update(b":");
+    mac.update(timestamp.as_bytes());
+    mac.update(b":");
+    mac.update(body);
+
+    mac
+}
+
+pub fn chat_signature(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    format!(
+        "{}={}",
+        SIGNATURE_VERSION,
+        hex::encode(signing_mac(secret, timestamp, body).finalize().into_bytes())
+    )
+}
+
+// Checks the signature in constant time, and refuses requests signed more than
+// `max_age_secs` away from now so a captured request cannot be replayed.
+pub fn verify_chat_signature(
+    secret: &str,
+    timestamp: &str,
+    signature: &str,
+    body: &[u8],
+    max_age_secs: i64,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let signed_at: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| "The request timestamp is malformed.".to_string())?;
+
+    if (now.timestamp() - signed_at).abs() > max_age_secs {
+        return Err("The request is too old.".to_string());
+    }
+
+    let digest = signature
+        .strip_prefix(SIGNATURE_VERSION)
+        .and_then(|rest| rest.strip_prefix('='))
+        .and_then(|digest| hex::decode(digest).ok())
+        .ok_or_else(|| "The signature is malformed.".to_string())?;
+
+    signing_mac(secret, timestamp, body)
+        .verify_slice(&digest)
+        .map_err(|_| "The signature does not match.".to_string())
+}
+
+const CHAT_USERNAME_PREFIX: &str = "chat:";
+
+// Chat users vote under an identity of their workspace. Accounts cannot be registered
+// with such a name, so nobody can sign in as a chat user.
+pub fn chat_username(team_id: &str, user_id: &str) -> String {
+    format!("{}{}:{}", CHAT_USERNAME_PREFIX, team_id, user_id)
+}
+
+pub fn is_chat_username(username: &str) -> bool {
+    username.starts_with(CHAT_USERNAME_PREFIX)
+}
+
+// Splits command text into words, keeping double quoted phrases together. Chat clients
+// often turn straight quotes into curly ones, so both are accepted.
+pub fn split_command_text(text: &str) -> Result<Vec<String>, String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_word = false;
+
+    for c in text.chars() {
+        match c {
+            '"' | '\u{201C}' | '\u{201D}' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quoted {
+        return Err("A quote is never closed.".to_string());
+    }
+    if in_word {
+        words.push(current);
+    }
+
+    Ok(words
+        .into_iter()
+        .map(|word| word.trim().to_string())
+        .filter(|word| !word.is_empty())
+        .collect())
+}
+
+// `"Question?" Option Option ...` as a poll of the chat user. Chat polls are unlisted:
+// they are reachable from the channel but not listed or searchable. The option count and
+// lengths are left to the usual poll validation.
+pub fn parse_poll_command(text: &str) -> Result<PollCreation, String> {
+    let mut words = split_command_text(text)?.into_iter();
+
+    let title = words.next().ok_or_else(|| CHAT_USAGE.to_string())?;
+    let options: Vec<String> = words.collect();
+
+    if options.is_empty() {
+        return Err(CHAT_USAGE.to_string());
+    }
+
+    Ok(PollCreation {
+        title,
+        description: None,
+        options,
+        visibility: PollVisibility::Unlisted,
+        anonymous: false,
+        results_visibility: ResultsVisibility::Always,
+        allow_write_ins: false,
+        kind: PollKind::default(),
+        quiz: None,
+        slots: vec![],
+        lock_votes: false,
+        require_proof_of_work: false,
+        allow_guests: false,
+        org_id: None,
+    })
+}
+
+// Button values carry the poll and the option, so callbacks need no other state.
+pub fn vote_button_value(poll_id: &str, option_id: &str) -> String {
+    format!("{}:{}", poll_id, option_id)
+}
+
+pub fn parse_vote_button_value(value: &str) -> Option<(String, String)> {
+    let (poll_id, option_id) = value.split_once(':')?;
+
+    if poll_id.is_empty() || option_id.is_empty() {
+        return None;
+    }
+
+    Some((poll_id.to_string(), option_id.to_string()))
+}
+
+fn button_text(text: &str, votes: Option<u64>) -> String {
+    let mut label: String = text.chars().take(BUTTON_TEXT_MAX_LENGTH).collect();
+    if label.len() < text.len() {
+        label.push('…');
+    }
+
+    match votes {
+        Some(votes) => format!("{} ({})", label, votes),
+        None => label,
+    }
+}
+
+// The channel message of a poll: its question, a row of vote buttons while it is open,
+// and the vote count. Counts are only shown when `show_counts` is set, since everyone in
+// the channel sees the message.
+pub fn build_poll_message(poll: &Poll, show_counts: bool) -> Value {
+    let options: Vec<_> = poll
+        .options
+        .iter()
+        .filter(|option| !option.hidden)
+        .collect();
+
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": { "type": "mrkdwn", "text": format!("*{}*", poll.title) }
+    })];
+
+    if poll.is_active {
+        for (row, chunk) in options.chunks(BUTTONS_PER_ROW).enumerate() {
+            let buttons: Vec<Value> = chunk
+                .iter()
+                .map(|option| {
+                    json!({
+                        "type": "button",
+                        "action_id": format!("vote_{}", option.option_id),
+                        "text": {
+                            "type": "plain_text",
+                            "text": button_text(&option.text, show_counts.then_some(option.votes as u64)),
+                            "emoji": true
+                        },
+                        "value": vote_button_value(&poll.poll_id, &option.option_id)
+                    })
+                })
+                .collect();
+
+            blocks.push(json!({
+                "type": "actions",
+                "block_id": format!("poll_{}_{}", poll.poll_id, row),
+                "elements": buttons
+            }));
+        }
+    } else if show_counts {
+        let lines: Vec<String> = options
+            .iter()
+            .map(|option| format!("{}: {}", option.text, option.votes))
+            .collect();
+
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "plain_text", "text": lines.join("\n") }
+        }));
+    }
+
+    let status = if poll.is_active { "Open" } else { "Closed" };
+    let context = if show_counts {
+        let total: u64 = options.iter().map(|option| option.votes as u64).sum();
+        format!("{} · {} vote(s)", status, total)
+    } else {
+        format!("{} · results are hidden", status)
+    };
+
+    blocks.push(json!({
+        "type": "context",
+        "elements": [{ "type": "mrkdwn", "text": context }]
+    }));
+
+    json!({
+        "response_type": "in_channel",
+        "text": poll.title,
+        "blocks": blocks
+    })
+}
+
+// A reply only shown to the user who ran the command or clicked the button.
+pub fn ephemeral_message(text: &str) -> Value {
+    json!({
+        "response_type": "ephemeral",
+        "replace_original": false,
+        "text": text
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::poll_model::OptionItem;
+    use chrono::Duration;
+
+    #[test]
+    fn test_verify_chat_signature() {
+        let now = Utc::now();
+        let timestamp = now.timestamp().to_string();
+        let body = b"team_id=T1&user_id=U1&text=%22Lunch%3F%22+Pizza+Sushi";
+        let signature = chat_signature("secret", &timestamp, body);
+
+        assert!(signature.starts_with("v0="));
+        assert!(verify_chat_signature("secret", &timestamp, &signature, body, 300, now).is_ok());
+
+        assert!(verify_chat_signature("other", &timestamp, &signature, body, 300, now).is_err());
+        assert!(
+            verify_chat_signature("secret", &timestamp, &signature, b"tampered", 300, now).is_err()
+        );
+        assert!(verify_chat_signature("secret", &timestamp, "v0=zz", body, 300, now).is_err());
+        assert!(verify_chat_signature("secret", "soon", &signature, body, 300, now).is_err());
+        assert!(verify_chat_signature(
+            "secret",
+            &timestamp,
+            &signature,
+            body,
+            300,
+            now + Duration::seconds(301)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_split_command_text() {
+        assert_eq!(
+            split_command_text(r#""Lunch?" Pizza Sushi   Tacos"#).unwrap(),
+            vec!["Lunch?", "Pizza", "Sushi", "Tacos"]
+        );
+        assert_eq!(
+            split_command_text("\u{201C}Where to eat?\u{201D} \"Thai food\" Pizza").unwrap(),
+            vec!["Where to eat?", "Thai food", "Pizza"]
+        );
+        assert_eq!(split_command_text("  ").unwrap(), Vec::<String>::new());
+        assert!(split_command_text(r#""Lunch? Pizza"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_poll_command() {
+        let poll = parse_poll_command(r#""Lunch?" Pizza Sushi Tacos"#).unwrap();
+
+        assert_eq!(poll.title, "Lunch?");
+        assert_eq!(poll.options, vec!["Pizza", "Sushi", "Tacos"]);
+        assert_eq!(poll.visibility, PollVisibility::Unlisted);
+
+        assert!(parse_poll_command("").is_err());
+        assert!(parse_poll_command(r#""Lunch?""#).is_err());
+    }
+
+    #[test]
+    fn test_chat_username() {
+        assert_eq!(chat_username("T1", "U1"), "chat:T1:U1");
+        assert!(is_chat_username(&chat_username("T1", "U1")));
+        assert!(!is_chat_username("Azeem"));
+    }
+
+    #[test]
+    fn test_vote_button_value_round_trip() {
+        let value = vote_button_value("poll_1", "opt-2");
+
+        assert_eq!(
+            parse_vote_button_value(&value),
+            Some(("poll_1".to_string(), "opt-2".to_string()))
+        );
+        assert_eq!(parse_vote_button_value("poll_1"), None);
+        assert_eq!(parse_vote_button_value(":opt"), None);
+    }
+
+    #[test]
+    fn test_button_text() {
+        assert_eq!(button_text("Pizza", Some(3)), "Pizza (3)");
+        assert_eq!(button_text("Pizza", None), "Pizza");
+
+        let long = "a".repeat(BUTTON_TEXT_MAX_LENGTH + 10);
+        assert_eq!(
+            button_text(&long, None),
+            format!("{}…", "a".repeat(BUTTON_TEXT_MAX_LENGTH))
+        );
+    }
+
+    #[test]
+    fn test_build_poll_message() {
+        let mut poll = Poll {
+            poll_id: "p1".to_string(),
+            title: "Lunch?".to_string(),
+            is_active: true,
+            options: ["Pizza", "Sushi", "Tacos", "Thai", "Curry", "Salad"]
+                .iter()
+                .enumerate()
+                .map(|(index, text)| OptionItem {
+                    option_id: format!("o{}", index),
+                    text: text.to_string(),
+                    votes: index as u32,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let message = build_poll_message(&poll, true);
+        let blocks = message["blocks"].as_array().unwrap();
+
+        assert_eq!(message["response_type"], "in_channel");
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[1]["elements"].as_array().unwrap().len(), 5);
+        assert_eq!(blocks[1]["elements"][1]["text"]["text"], "Sushi (1)");
+        assert_eq!(blocks[1]["elements"][1]["value"], "p1:o1");
+        assert_eq!(blocks[2]["elements"].as_array().unwrap().len(), 1);
+        assert_eq!(blocks[3]["elements"][0]["text"], "Open · 15 vote(s)");
+
+        let hidden = build_poll_message(&poll, false);
+        assert_eq!(hidden["blocks"][1]["elements"][1]["text"]["text"], "Sushi");
+
+        poll.is_active = false;
+        let closed = build_poll_message(&poll, true);
+        let blocks = closed["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert!(blocks.iter().all(|block| block["type"] != "actions"));
+    }
+}
@@ -0,0 +1,272 @@
+use crate::config::{
+    config::{ContentFilterConfig, FilterPolicy},
+    error_response::FieldError,
+    poll_config::PollError,
+};
+
+// A single check of the content pipeline. Returns why the text is not acceptable, or
+// `None` when it passes.
+pub trait ContentFilter: Send + Sync {
+    fn check(&self, text: &str) -> Option<String>;
+}
+
+// Invisible characters used to split words past a filter: zero-width spaces and
+// joiners, bidi controls, word joiners, soft hyphens and the byte order mark.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+    )
+}
+
+pub fn scrub_text(text: &str) -> String {
+    text.chars().filter(|c| !is_invisible(*c)).collect()
+}
+
+// Maps look-alike letters from other scripts and full-width forms to the ASCII letter
+// they imitate.
+fn fold_confusable(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        'а' | 'А' | 'α' | 'Α' => 'a',
+        'в' | 'В' | 'β' | 'Β' => 'b',
+        'с' | 'С' | 'ϲ' => 'c',
+        'е' | 'Е' | 'ε' | 'Ε' => 'e',
+        'н' | 'Н' | 'Η' => 'h',
+        'і' | 'І' | 'ι' | 'Ι' => 'i',
+        'ј' | 'Ј' => 'j',
+        'к' | 'К' | 'κ' | 'Κ' => 'k',
+        'м' | 'М' | 'Μ' => 'm',
+        'п' | 'ν' | 'Ν' => 'n',
+        'о' | 'О' | 'ο' | 'Ο' => 'o',
+        'р' | 'Р' | 'ρ' | 'Ρ' => 'p',
+        'ѕ' | 'Ѕ' => 's',
+        'т' | 'Т' | 'τ' | 'Τ' => 't',
+        'у' | 'У' | 'υ' | 'Υ' => 'y',
+        'х' | 'Х' | 'χ' | 'Χ' => 'x',
+        'ԁ' => 'd',
+        'ɡ' => 'g',
+        'ℓ' => 'l',
+        _ => c,
+    }
+}
+
+// Symbols only stand for letters when a letter or digit follows, so a trailing `!`
+// still ends the word.
+fn fold_leetspeak(c: char, next: Option<char>) -> char {
+    let in_word = next.is_some_and(char::is_alphanumeric);
+
+    match c {
+        '0' => 'o',
+        '1' => 'i',
+        '3' => 'e',
+        '4' => 'a',
+        '5' => 's',
+        '7' => 't',
+        '8' => 'b',
+        '!' if in_word => 'i',
+        '@' if in_word => 'a',
+        '$' if in_word => 's',
+        _ => c,
+    }
+}
+
+// Lower-cased words of the text with invisible characters removed and confusables and
+// leetspeak folded, so `Fr3e   m0ney` and `free money` compare equal.
+pub fn normalized_words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = scrub_text(text)
+        .chars()
+        .map(fold_confusable)
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    let folded: String = chars
+        .iter()
+        .enumerate()
+        .map(|(index, c)| fold_leetspeak(*c, chars.get(index + 1).copied()))
+        .collect();
+
+    folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Matches whole words only, so a denied `ass` does not catch `class`. Terms can span
+// several words.
+pub struct DenyListFilter {
+    terms: Vec<Vec<String>>,
+}
+
+impl DenyListFilter {
+    pub fn new(terms: &[String]) -> Self {
+        DenyListFilter {
+            terms: terms
+                .iter()
+                .map(|term| normalized_words(term))
+                .filter(|words| !words.is_empty())
+                .collect(),
+        }
+    }
+}
+
+impl ContentFilter for DenyListFilter {
+    fn check(&self, text: &str) -> Option<String> {
+        let words = normalized_words(text);
+
+        self.terms
+            .iter()
+            .find(|term| {
+                words
+                    .windows(term.len())
+                    .any(|window| window == term.as_slice())
+            })
+            .map(|_| "Contains a term that is not allowed.".to_string())
+    }
+}
+
+pub struct UrlLimitFilter {
+    max_urls: usize,
+}
+
+impl UrlLimitFilter {
+    pub fn new(max_urls: usize) -> Self {
+        UrlLimitFilter { max_urls }
+    }
+}
+
+impl ContentFilter for UrlLimitFilter {
+    fn check(&self, text: &str) -> Option<String> {
+        let urls = scrub_text(text)
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .filter(|word| {
+                word.contains("http://") || word.contains("https://") || word.starts_with("www.")
+            })
+            .count();
+
+        (urls > self.max_urls).then(|| format!("Contains more than {} links.", self.max_urls))
+    }
+}
+
+// Runs every filter over the given fields and applies the policy to what they catch.
+pub struct ContentPipeline {
+    filters: Vec<Box<dyn ContentFilter>>,
+    policy: FilterPolicy,
+}
+
+impl ContentPipeline {
+    pub fn new(policy: FilterPolicy) -> Self {
+        ContentPipeline {
+            filters: Vec::new(),
+            policy,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Box<dyn ContentFilter>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub fn from_config(config: &ContentFilterConfig) -> Self {
+        let pipeline = ContentPipeline::new(config.policy)
+            .with_filter(Box::new(UrlLimitFilter::new(config.max_urls)));
+
+        if config.deny_list.is_empty() {
+            pipeline
+        } else {
+            pipeline.with_filter(Box::new(DenyListFilter::new(&config.deny_list)))
+        }
+    }
+
+    pub fn matches<'a>(
+        &self,
+        fields: impl IntoIterator<Item = (String, &'a str)>,
+    ) -> Vec<FieldError> {
+        fields
+            .into_iter()
+            .filter_map(|(field, text)| {
+                self.filters
+                    .iter()
+                    .find_map(|filter| filter.check(text))
+                    .map(|message| FieldError::new(field, message))
+            })
+            .collect()
+    }
+
+    // Rejected content fails like any other validation error. Under the flag policy the
+    // matches are returned so the caller can queue the poll for review.
+    pub fn review<'a>(
+        &self,
+        fields: impl IntoIterator<Item = (String, &'a str)>,
+    ) -> Result<Vec<FieldError>, PollError> {
+        let matches = self.matches(fields);
+
+        if !matches.is_empty() && self.policy == FilterPolicy::Reject {
+            return Err(PollError::ValidationError(matches));
+        }
+
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(policy: FilterPolicy) -> ContentFilterConfig {
+        ContentFilterConfig {
+            deny_list: vec!["free money".to_string(), "scam".to_string()],
+            max_urls: 1,
+            policy,
+        }
+    }
+
+    #[test]
+    fn test_normalized_words_fold_evasions() {
+        assert_eq!(
+            normalized_words("Fr3e\u{200B}   M0NEY!"),
+            vec!["free".to_string(), "money".to_string()]
+        );
+        assert_eq!(normalized_words("ѕсаm"), vec!["scam".to_string()]);
+        assert_eq!(normalized_words("ＳＣＡＭ"), vec!["scam".to_string()]);
+    }
+
+    #[test]
+    fn test_deny_list_matches_whole_words() {
+        let filter = DenyListFilter::new(&["scam".to_string(), "free money".to_string()]);
+
+        assert!(filter.check("Is this a $cam?").is_some());
+        assert!(filter.check("Total sc@m!").is_some());
+        assert!(filter.check("Get fr33 m0ney now").is_some());
+        assert!(filter.check("Scampi for dinner?").is_none());
+        assert!(filter.check("Free lunch, money back").is_none());
+    }
+
+    #[test]
+    fn test_pipeline_policies() {
+        let fields = || {
+            vec![
+                ("title".to_string(), "Best lunch spot?"),
+                (
+                    "description".to_string(),
+                    "See https://a.example and https://b.example",
+                ),
+                ("options[0]".to_string(), "s c a m"),
+                ("options[1]".to_string(), "sc\u{200D}am"),
+            ]
+        };
+
+        let flagged = ContentPipeline::from_config(&config(FilterPolicy::Flag))
+            .review(fields())
+            .unwrap();
+        let fields_flagged: Vec<&str> = flagged.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields_flagged, vec!["description", "options[1]"]);
+
+        assert!(matches!(
+            ContentPipeline::from_config(&config(FilterPolicy::Reject)).review(fields()),
+            Err(PollError::ValidationError(errors)) if errors.len() == 2
+        ));
+    }
+}
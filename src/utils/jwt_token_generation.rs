@@ -1,22 +1,28 @@
 use std::error::Error;
 
-use chrono::Utc;
-use jsonwebtoken::{encode, EncodingKey, Header};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use log::info;
 use serde::{Deserialize, Serialize};
 
+use crate::models::user_model::UserRole;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
+    // Tokens issued before roles existed belong to regular users.
+    #[serde(default)]
+    pub role: UserRole,
 }
 
 impl Claims {
-    pub fn generate_token(username: &String) -> Result<String, Box<dyn Error>> {
+    pub fn generate_token(username: &str, role: UserRole) -> Result<String, Box<dyn Error>> {
         let now = Utc::now();
         let claims = Claims {
-            sub: username.clone(),
+            sub: username.to_string(),
             exp: (now + chrono::Duration::hours(1)).timestamp() as usize,
+            role,
         };
 
         let secret = std::env::var("JWT_SECRET").unwrap();
@@ -34,4 +40,117 @@ impl Claims {
 
         Ok(token)
     }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == UserRole::Admin
+    }
+}
+
+// Audience of guest tokens. Session tokens carry no audience, so neither kind of token
+// is accepted in place of the other.
+pub const GUEST_AUDIENCE: &str = "guest";
+
+// Identifies a visitor voting without an account in a poll that allows guests. The
+// token is only valid for the poll it was issued for.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GuestClaims {
+    pub sub: String,
+    pub poll_id: String,
+    pub aud: String,
+    pub exp: usize,
+}
+
+impl GuestClaims {
+    pub fn new(guest_id: &str, poll_id: &str, expires_at: DateTime<Utc>) -> Self {
+        GuestClaims {
+            sub: guest_id.to_string(),
+            poll_id: poll_id.to_string(),
+            aud: GUEST_AUDIENCE.to_string(),
+            exp: expires_at.timestamp() as usize,
+        }
+    }
+
+    pub fn generate_token(&self, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
+        encode(
+            &Header::default(),
+            self,
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+    }
+
+    // Returns the guest behind the token, if it is valid and was issued for this poll.
+    pub fn from_token(secret: &str, token: &str, poll_id: &str) -> Option<GuestClaims> {
+        let mut validation = Validation::default();
+        validation.set_audience(&[GUEST_AUDIENCE]);
+
+        decode::<GuestClaims>(
+            token,
+            &DecodingKey::from_secret(secret.as_ref()),
+            &validation,
+        )
+        .ok()
+        .map(|token_data| token_data.claims)
+        .filter(|claims| claims.poll_id == poll_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_token(secret: &str) -> String {
+        let claims = Claims {
+            sub: "Azeem".to_string(),
+            exp: (Utc::now() + chrono::Duration::hours(1)).timestamp() as usize,
+            role: UserRole::User,
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_guest_token_is_scoped_to_its_poll() {
+        let expires_at = Utc::now() + chrono::Duration::days(1);
+        let token = GuestClaims::new("guest1", "poll1", expires_at)
+            .generate_token("secret")
+            .unwrap();
+
+        let claims = GuestClaims::from_token("secret", &token, "poll1").unwrap();
+        assert_eq!(claims.sub, "guest1");
+
+        assert!(GuestClaims::from_token("secret", &token, "poll2").is_none());
+        assert!(GuestClaims::from_token("other", &token, "poll1").is_none());
+    }
+
+    #[test]
+    fn test_guest_and_session_tokens_are_not_interchangeable() {
+        let expires_at = Utc::now() + chrono::Duration::days(1);
+        let guest_token = GuestClaims::new("guest1", "poll1", expires_at)
+            .generate_token("secret")
+            .unwrap();
+
+        let session = decode::<Claims>(
+            &guest_token,
+            &DecodingKey::from_secret("secret".as_ref()),
+            &Validation::default(),
+        );
+        assert!(session.is_err());
+
+        assert!(GuestClaims::from_token("secret", &session_token("secret"), "poll1").is_none());
+    }
+
+    #[test]
+    fn test_expired_guest_token_is_refused() {
+        let expires_at = Utc::now() - chrono::Duration::hours(1);
+        let token = GuestClaims::new("guest1", "poll1", expires_at)
+            .generate_token("secret")
+            .unwrap();
+
+        assert!(GuestClaims::from_token("secret", &token, "poll1").is_none());
+    }
 }
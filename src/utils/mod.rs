@@ -1,4 +1,24 @@
+pub mod access_code_utility;
+pub mod admin_utility;
+pub mod anonymity_utility;
 pub mod api_docs;
+pub mod chat_utility;
+pub mod content_filter;
 pub mod jwt_token_generation;
+pub mod organization_utility;
+pub mod poll_edit_utility;
 pub mod poll_results_utility;
+pub mod pow_utility;
+pub mod purge_job;
+pub mod quiz_utility;
+pub mod rate_limit_utility;
+pub mod report_utility;
+pub mod schedule_utility;
+pub mod search_utility;
+pub mod session_utility;
+pub mod survey_utility;
 pub mod types;
+pub mod validation;
+pub mod webhook_job;
+pub mod webhook_utility;
+pub mod write_in_utility;
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use nanoid::nanoid;
+
+use crate::config::{config::PollLimits, organization_config::OrganizationError};
+use crate::models::organization_model::{Organization, OrganizationMember, OrganizationRole};
+use crate::utils::types::OrganizationCreation;
+use crate::utils::validation::validate_text;
+
+pub fn validate_organization_creation(
+    data: &OrganizationCreation,
+    limits: &PollLimits,
+) -> Result<(), OrganizationError> {
+    let mut errors = Vec::new();
+
+    validate_text(
+        &mut errors,
+        "name",
+        &data.name,
+        limits.title_max_length,
+        true,
+    );
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(OrganizationError::ValidationError(errors))
+    }
+}
+
+// The creator of an organization is its first admin.
+pub fn build_organization(
+    data: &OrganizationCreation,
+    username: &str,
+    now: DateTime<Utc>,
+) -> Organization {
+    Organization {
+        org_id: nanoid!(10),
+        name: data.name.trim().to_string(),
+        created_by: username.to_string(),
+        members: vec![OrganizationMember {
+            username: username.to_string(),
+            role: OrganizationRole::Admin,
+            joined_at: now,
+        }],
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_organization() {
+        let limits = PollLimits {
+            title_max_length: 10,
+            description_max_length: 20,
+            option_max_length: 10,
+            answer_max_length: 20,
+            min_options: 2,
+            max_options: 4,
+        };
+        let data = OrganizationCreation {
+            name: "  Platform  ".to_string(),
+        };
+
+        assert!(validate_organization_creation(&data, &limits).is_ok());
+
+        let organization = build_organization(&data, "founder", Utc::now());
+        assert_eq!(organization.name, "Platform");
+        assert!(organization.is_admin("founder"));
+        assert_eq!(organization.role_of("someone"), None);
+
+        let too_long = OrganizationCreation {
+            name: "Platform engineering".to_string(),
+        };
+        assert!(matches!(
+            validate_organization_creation(&too_long, &limits),
+            Err(OrganizationError::ValidationError(_))
+        ));
+    }
+}
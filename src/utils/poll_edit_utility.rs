@@ -0,0 +1,416 @@
+use std::collections::HashSet;
+
+use nanoid::nanoid;
+use serde::Serialize;
+
+use crate::config::poll_config::PollError;
+use crate::models::poll_model::{
+    OptionItem, Poll, PollVisibility, ResultsVisibility, RevisionChange, VoteHistory,
+};
+use crate::utils::types::{PollEdit, VotedOptionPolicy};
+
+#[derive(Debug)]
+pub struct PollEditOutcome {
+    pub title: String,
+    pub description: Option<String>,
+    pub visibility: PollVisibility,
+    pub results_visibility: ResultsVisibility,
+    pub lock_votes: bool,
+    pub options: Vec<OptionItem>,
+    pub voters: Vec<VoteHistory>,
+    // Set when reset votes came from ballots that do not record the option they picked.
+    pub clear_participation: bool,
+    pub clear_guest_ballots: bool,
+    pub changes: Vec<RevisionChange>,
+}
+
+// Applies an edit to a copy of the poll and records every field that changed.
+// Options that already have votes can only be renamed or removed when the edit
+// explicitly asks for their votes to be reset. Texts are trimmed as on creation.
+pub fn apply_poll_edit(poll: &Poll, edit: &PollEdit) -> Result<PollEditOutcome, PollError> {
+    let mut changes = Vec::new();
+
+    let mut title = poll.title.clone();
+    if let Some(new_title) = &edit.title {
+        let new_title = new_title.trim();
+        if new_title != poll.title {
+            changes.push(RevisionChange {
+                field: "title".to_string(),
+                before: Some(poll.title.clone()),
+                after: Some(new_title.to_string()),
+            });
+            title = new_title.to_string();
+        }
+    }
+
+    let mut description = poll.description.clone();
+    if let Some(new_description) = &edit.description {
+        let new_description = new_description.trim();
+        if Some(new_description) != poll.description.as_deref() {
+            changes.push(RevisionChange {
+                field: "description".to_string(),
+                before: poll.description.clone(),
+                after: Some(new_description.to_string()),
+            });
+            description = Some(new_description.to_string());
+        }
+    }
+
+    let mut visibility = poll.visibility;
+    if let Some(new_visibility) = edit.visibility {
+        if new_visibility == PollVisibility::Organization && poll.org_id.is_none() {
+            return Err(PollError::InvalidRequest(
+                "Only polls created in an organization can be restricted to it.".to_string(),
+            ));
+        }
+
+        if new_visibility != poll.visibility {
+            changes.push(RevisionChange {
+                field: "visibility".to_string(),
+                before: setting_label(&poll.visibility),
+                after: setting_label(&new_visibility),
+            });
+            visibility = new_visibility;
+        }
+    }
+
+    let mut results_visibility = poll.results_visibility;
+    if let Some(new_results_visibility) = edit.results_visibility {
+        if new_results_visibility != poll.results_visibility {
+            changes.push(RevisionChange {
+                field: "resultsVisibility".to_string(),
+                before: setting_label(&poll.results_visibility),
+                after: setting_label(&new_results_visibility),
+            });
+            results_visibility = new_results_visibility;
+        }
+    }
+
+    let mut lock_votes = poll.lock_votes;
+    if let Some(new_lock_votes) = edit.lock_votes {
+        if new_lock_votes != poll.lock_votes {
+            changes.push(RevisionChange {
+                field: "lockVotes".to_string(),
+                before: Some(poll.lock_votes.to_string()),
+                after: Some(new_lock_votes.to_string()),
+            });
+            lock_votes = new_lock_votes;
+        }
+    }
+
+    let mut options = poll.options.clone();
+    let mut reset_option_ids = HashSet::new();
+    let mut reset_guest_votes = false;
+
+    for rename in &edit.rename_options {
+        let option = options
+            .iter_mut()
+            .find(|option| option.option_id == rename.option_id)
+            .ok_or_else(|| PollError::InvalidRequest(unknown_option(&rename.option_id)))?;
+
+        let text = rename.text.trim();
+        if option.text == text {
+            continue;
+        }
+
+        if option.votes > 0 {
+            if edit.voted_option_policy == VotedOptionPolicy::Reject {
+                return Err(PollError::VotedOptionConflict(format!(
+                    "Option '{}' already has votes and cannot be renamed.",
+                    option.text
+                )));
+            }
+            reset_guest_votes |= option.guest_votes > 0;
+            option.votes = 0;
+            option.guest_votes = 0;
+            reset_option_ids.insert(option.option_id.clone());
+        }
+
+        changes.push(RevisionChange {
+            field: format!("options.{}", option.option_id),
+            before: Some(option.text.clone()),
+            after: Some(text.to_string()),
+        });
+        option.text = text.to_string();
+    }
+
+    for option_id in &edit.remove_options {
+        let index = options
+            .iter()
+            .position(|option| option.option_id == *option_id)
+            .ok_or_else(|| PollError::InvalidRequest(unknown_option(option_id)))?;
+
+        let option = options.remove(index);
+
+        if option.votes > 0 {
+            if edit.voted_option_policy == VotedOptionPolicy::Reject {
+                return Err(PollError::VotedOptionConflict(format!(
+                    "Option '{}' already has votes and cannot be removed.",
+                    option.text
+                )));
+            }
+            reset_guest_votes |= option.guest_votes > 0;
+            reset_option_ids.insert(option.option_id.clone());
+        }
+
+        changes.push(RevisionChange {
+            field: format!("options.{}", option.option_id),
+            before: Some(option.text),
+            after: None,
+        });
+    }
+
+    for text in &edit.add_options {
+        let option = OptionItem {
+            option_id: nanoid!(10),
+            text: text.trim().to_string(),
+            votes: 0,
+            guest_votes: 0,
+            write_in: false,
+            hidden: false,
+            payload: None,
+        };
+
+        changes.push(RevisionChange {
+            field: format!("options.{}", option.option_id),
+            before: None,
+            after: Some(option.text.clone()),
+        });
+        options.push(option);
+    }
+
+    if changes.is_empty() {
+        return Err(PollError::InvalidRequest(
+            "The edit does not change anything.".to_string(),
+        ));
+    }
+
+    let voters = poll
+        .voters
+        .iter()
+        .filter(|vote| !reset_option_ids.contains(&vote.option_id))
+        .cloned()
+        .collect();
+
+    // Anonymous and guest ballots do not say which option they picked, so once one of
+    // them is reset they all are, and everyone who cast one may vote again.
+    let clear_participation = poll.anonymous && !reset_option_ids.is_empty();
+    let clear_guest_ballots = clear_participation || reset_guest_votes;
+
+    for option in &mut options {
+        if clear_participation {
+            option.votes = 0;
+        } else if clear_guest_ballots {
+            option.votes = option.votes.saturating_sub(option.guest_votes);
+        }
+        if clear_guest_ballots {
+            option.guest_votes = 0;
+        }
+    }
+
+    Ok(PollEditOutcome {
+        title,
+        description,
+        visibility,
+        results_visibility,
+        lock_votes,
+        options,
+        voters,
+        clear_participation,
+        clear_guest_ballots,
+        changes,
+    })
+}
+
+// Poll settings are recorded in revisions with the same names the API uses.
+fn setting_label<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+}
+
+fn unknown_option(option_id: &str) -> String {
+    format!("Option '{}' does not exist in this poll.", option_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::types::OptionRename;
+
+    fn sample_poll() -> Poll {
+        Poll {
+            poll_id: "poll123".to_string(),
+            username: "Azeem".to_string(),
+            title: "Favorite Programming Languag".to_string(),
+            options: vec![
+                OptionItem {
+                    option_id: "1".to_string(),
+                    text: "Rust".to_string(),
+                    votes: 1,
+                    ..Default::default()
+                },
+                OptionItem {
+                    option_id: "2".to_string(),
+                    text: "Pyton".to_string(),
+                    votes: 0,
+                    ..Default::default()
+                },
+            ],
+            voters: vec![VoteHistory {
+                username: "voter".to_string(),
+                option_id: "1".to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_apply_poll_edit_records_changes() {
+        let poll = sample_poll();
+        let edit = PollEdit {
+            title: Some("Favorite Programming Language".to_string()),
+            rename_options: vec![OptionRename {
+                option_id: "2".to_string(),
+                text: "Python".to_string(),
+            }],
+            add_options: vec!["Go".to_string()],
+            ..Default::default()
+        };
+
+        let outcome = apply_poll_edit(&poll, &edit).unwrap();
+
+        assert_eq!(outcome.title, "Favorite Programming Language");
+        assert_eq!(outcome.options.len(), 3);
+        assert_eq!(outcome.options[1].text, "Python");
+        assert_eq!(outcome.options[2].text, "Go");
+        assert_eq!(outcome.voters.len(), 1);
+        assert_eq!(outcome.changes.len(), 3);
+        assert_eq!(
+            outcome.changes[1],
+            RevisionChange {
+                field: "options.2".to_string(),
+                before: Some("Pyton".to_string()),
+                after: Some("Python".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_apply_poll_edit_rejects_voted_option_by_default() {
+        let poll = sample_poll();
+        let edit = PollEdit {
+            remove_options: vec!["1".to_string()],
+            ..Default::default()
+        };
+
+        let result = apply_poll_edit(&poll, &edit);
+        assert!(matches!(result, Err(PollError::VotedOptionConflict(_))));
+    }
+
+    #[test]
+    fn test_apply_poll_edit_resets_affected_votes() {
+        let poll = sample_poll();
+        let edit = PollEdit {
+            rename_options: vec![OptionRename {
+                option_id: "1".to_string(),
+                text: "Rust 2024".to_string(),
+            }],
+            voted_option_policy: VotedOptionPolicy::ResetVotes,
+            ..Default::default()
+        };
+
+        let outcome = apply_poll_edit(&poll, &edit).unwrap();
+
+        assert_eq!(outcome.options[0].votes, 0);
+        assert!(outcome.voters.is_empty());
+    }
+
+    #[test]
+    fn test_apply_poll_edit_trims_texts() {
+        let poll = sample_poll();
+        let edit = PollEdit {
+            title: Some("  Favorite Programming Language ".to_string()),
+            rename_options: vec![OptionRename {
+                option_id: "2".to_string(),
+                text: " Python ".to_string(),
+            }],
+            add_options: vec!["  Go".to_string()],
+            ..Default::default()
+        };
+
+        let outcome = apply_poll_edit(&poll, &edit).unwrap();
+
+        assert_eq!(outcome.title, "Favorite Programming Language");
+        assert_eq!(outcome.options[1].text, "Python");
+        assert_eq!(outcome.options[2].text, "Go");
+        assert_eq!(
+            outcome.changes[0].after.as_deref(),
+            Some("Favorite Programming Language")
+        );
+    }
+
+    #[test]
+    fn test_apply_poll_edit_resets_guest_and_anonymous_ballots() {
+        let mut poll = sample_poll();
+        poll.options[0].guest_votes = 1;
+        poll.options[1].votes = 3;
+        poll.options[1].guest_votes = 2;
+        let edit = PollEdit {
+            remove_options: vec!["1".to_string()],
+            voted_option_policy: VotedOptionPolicy::ResetVotes,
+            ..Default::default()
+        };
+
+        let outcome = apply_poll_edit(&poll, &edit).unwrap();
+
+        assert!(outcome.clear_guest_ballots);
+        assert!(!outcome.clear_participation);
+        assert_eq!(outcome.options[0].votes, 1);
+        assert_eq!(outcome.options[0].guest_votes, 0);
+
+        poll.anonymous = true;
+        poll.options[0].guest_votes = 0;
+        let outcome = apply_poll_edit(&poll, &edit).unwrap();
+
+        assert!(outcome.clear_participation);
+        assert!(outcome.clear_guest_ballots);
+        assert_eq!(outcome.options[0].votes, 0);
+    }
+
+    #[test]
+    fn test_apply_poll_edit_locks_votes() {
+        let poll = sample_poll();
+        let edit = PollEdit {
+            lock_votes: Some(true),
+            ..Default::default()
+        };
+
+        let outcome = apply_poll_edit(&poll, &edit).unwrap();
+
+        assert!(outcome.lock_votes);
+        assert_eq!(outcome.voters.len(), poll.voters.len());
+        assert_eq!(
+            outcome.changes,
+            vec![RevisionChange {
+                field: "lockVotes".to_string(),
+                before: Some("false".to_string()),
+                after: Some("true".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_apply_poll_edit_without_changes() {
+        let poll = sample_poll();
+        let edit = PollEdit {
+            title: Some(poll.title.clone()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            apply_poll_edit(&poll, &edit),
+            Err(PollError::InvalidRequest(_))
+        ));
+    }
+}
@@ -1,14 +1,30 @@
+use std::collections::HashMap;
+
 use chrono::{Duration, Utc};
 use serde_json::json;
 
-use crate::models::poll_model::Poll;
+use crate::models::poll_model::{Availability, Poll, WordFrequency};
+
+const SUMMARY_WORD_LIMIT: usize = 20;
+const MIN_SUMMARY_WORD_LENGTH: usize = 3;
+
+const STOP_WORDS: [&str; 40] = [
+    "the", "and", "for", "are", "but", "not", "you", "your", "with", "this", "that", "was", "were",
+    "have", "has", "had", "its", "it's", "they", "them", "their", "there", "from", "what", "which",
+    "when", "who", "will", "would", "could", "should", "about", "into", "than", "then", "just",
+    "very", "also", "our", "all",
+];
 
 pub fn calculate_poll_results(poll: &Poll) -> serde_json::Value {
-    let total_votes: usize = poll.options.iter().map(|opt| opt.votes as usize).sum();
+    let visible_options = poll.options.iter().filter(|opt| !opt.hidden);
 
-    let options_with_percentages: Vec<_> = poll
-        .options
-        .iter()
+    let total_votes: usize = visible_options.clone().map(|opt| opt.votes as usize).sum();
+    let guest_votes: usize = visible_options
+        .clone()
+        .map(|opt| opt.guest_votes as usize)
+        .sum();
+
+    let options_with_percentages: Vec<_> = visible_options
         .map(|opt| {
             let percentage = if total_votes > 0 {
                 (opt.votes as f64 / total_votes as f64) * 100.0
@@ -19,7 +35,9 @@ pub fn calculate_poll_results(poll: &Poll) -> serde_json::Value {
                 "option_id": opt.option_id,
                 "text": opt.text,
                 "votes": opt.votes,
-                "percentage": percentage
+                "guest_votes": opt.guest_votes,
+                "percentage": percentage,
+                "write_in": opt.write_in
             })
         })
         .collect();
@@ -30,11 +48,126 @@ pub fn calculate_poll_results(poll: &Poll) -> serde_json::Value {
         "pollId": poll.poll_id,
         "title": poll.title,
         "totalVotes": total_votes,
+        "guestVotes": guest_votes,
         "options": options_with_percentages,
         "timeElapsed": time_elapsed,
     })
 }
 
+// Results of a scheduling poll: per slot how many participants are available, available
+// if need be or not available. Slots are ranked by how many can attend at all, then by
+// how many answered yes, then chronologically.
+pub fn calculate_schedule_results(poll: &Poll) -> serde_json::Value {
+    let mut slots: Vec<_> = poll
+        .options
+        .iter()
+        .filter(|option| !option.hidden)
+        .filter_map(|option| {
+            let slot = option.time_slot()?;
+            let count = |availability: Availability| {
+                poll.availability
+                    .iter()
+                    .flat_map(|ballot| &ballot.answers)
+                    .filter(|answer| {
+                        answer.option_id == option.option_id && answer.availability == availability
+                    })
+                    .count()
+            };
+
+            Some((
+                option,
+                slot,
+                count(Availability::Yes),
+                count(Availability::IfNeedBe),
+            ))
+        })
+        .collect();
+
+    slots.sort_by(|a, b| {
+        (b.2 + b.3)
+            .cmp(&(a.2 + a.3))
+            .then(b.2.cmp(&a.2))
+            .then(a.1.starts_at.cmp(&b.1.starts_at))
+    });
+
+    let participants = poll.availability.len();
+
+    let ranked_slots: Vec<_> = slots
+        .iter()
+        .enumerate()
+        .map(|(index, (option, slot, yes, if_need_be))| {
+            json!({
+                "rank": index + 1,
+                "option_id": option.option_id,
+                "text": option.text,
+                "starts_at": slot.starts_at,
+                "ends_at": slot.ends_at,
+                "yes": yes,
+                "if_need_be": if_need_be,
+                "no": participants - yes - if_need_be,
+                "available": yes + if_need_be,
+            })
+        })
+        .collect();
+
+    let time_elapsed = format_duration(Utc::now().signed_duration_since(poll.created_at));
+
+    json!({
+        "pollId": poll.poll_id,
+        "title": poll.title,
+        "totalParticipants": participants,
+        "chosenOptionId": poll.chosen_option_id,
+        "slots": ranked_slots,
+        "timeElapsed": time_elapsed,
+    })
+}
+
+// Results of a free-text poll: the number of visible answers and the words used most
+// often across them.
+pub fn calculate_text_results(poll: &Poll, answers: &[String]) -> serde_json::Value {
+    let time_elapsed = format_duration(Utc::now().signed_duration_since(poll.created_at));
+
+    json!({
+        "pollId": poll.poll_id,
+        "title": poll.title,
+        "totalAnswers": answers.len(),
+        "words": calculate_word_frequencies(answers, SUMMARY_WORD_LIMIT),
+        "timeElapsed": time_elapsed,
+    })
+}
+
+// Counts how many answers use each word, ignoring case, punctuation, short words and
+// common stop words. Sorted by count, then alphabetically.
+pub fn calculate_word_frequencies(answers: &[String], limit: usize) -> Vec<WordFrequency> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+
+    for answer in answers {
+        let mut words: Vec<String> = answer
+            .split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .map(|word| word.trim_matches('\'').to_lowercase())
+            .filter(|word| {
+                word.chars().count() >= MIN_SUMMARY_WORD_LENGTH
+                    && !STOP_WORDS.contains(&word.as_str())
+            })
+            .collect();
+        words.sort();
+        words.dedup();
+
+        for word in words {
+            *counts.entry(word).or_insert(0) += 1;
+        }
+    }
+
+    let mut frequencies: Vec<WordFrequency> = counts
+        .into_iter()
+        .map(|(word, count)| WordFrequency { word, count })
+        .collect();
+    frequencies.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    frequencies.truncate(limit);
+
+    frequencies
+}
+
 pub fn format_duration(duration: Duration) -> String {
     let secs = duration.num_seconds();
     let days = secs / 86400;
@@ -82,19 +215,24 @@ mod tests {
                     option_id: "1".to_string(),
                     text: "Rust".to_string(),
                     votes: 70,
+                    guest_votes: 20,
+                    ..Default::default()
                 },
                 OptionItem {
                     option_id: "2".to_string(),
                     text: "Python".to_string(),
                     votes: 30,
+                    ..Default::default()
                 },
             ],
+            ..Default::default()
         };
 
         let result = calculate_poll_results(&poll);
 
-        // Check total votes
+        // Check total votes, guest ballots are counted in the total and on their own
         assert_eq!(result["totalVotes"], 100);
+        assert_eq!(result["guestVotes"], 20);
 
         // Check individual options
         let options = result["options"].as_array().unwrap();
@@ -103,6 +241,7 @@ mod tests {
         assert_eq!(options[0]["option_id"], "1");
         assert_eq!(options[0]["text"], "Rust");
         assert_eq!(options[0]["votes"], 70);
+        assert_eq!(options[0]["guest_votes"], 20);
         assert_eq!(options[0]["percentage"], 70.0);
 
         assert_eq!(options[1]["option_id"], "2");
@@ -131,13 +270,16 @@ mod tests {
                     option_id: "1".to_string(),
                     text: "Rust".to_string(),
                     votes: 70,
+                    ..Default::default()
                 },
                 OptionItem {
                     option_id: "2".to_string(),
                     text: "Python".to_string(),
                     votes: 30,
+                    ..Default::default()
                 },
             ],
+            ..Default::default()
         };
 
         let result = calculate_poll_results(&poll);
@@ -148,4 +290,86 @@ mod tests {
         assert_eq!(options[0]["percentage"], 70.0);
         assert_eq!(options[1]["percentage"], 30.0);
     }
+
+    #[test]
+    fn test_calculate_schedule_results_ranks_by_availability() {
+        use crate::models::poll_model::{AvailabilityBallot, SlotAnswer, TimeSlot};
+        use crate::utils::schedule_utility::build_slot_options;
+
+        let slots: Vec<TimeSlot> = (0..3)
+            .map(|hour| TimeSlot {
+                starts_at: Utc::now() + Duration::hours(hour),
+                ends_at: Utc::now() + Duration::hours(hour + 1),
+            })
+            .collect();
+        let options = build_slot_options(&slots);
+
+        let ballot = |username: &str, answers: [Availability; 3]| AvailabilityBallot {
+            username: username.to_string(),
+            answers: options
+                .iter()
+                .zip(answers)
+                .map(|(option, availability)| SlotAnswer {
+                    option_id: option.option_id.clone(),
+                    availability,
+                })
+                .collect(),
+            updated_at: Utc::now(),
+        };
+
+        let poll = Poll {
+            availability: vec![
+                ballot(
+                    "a",
+                    [Availability::No, Availability::IfNeedBe, Availability::Yes],
+                ),
+                ballot(
+                    "b",
+                    [Availability::Yes, Availability::Yes, Availability::IfNeedBe],
+                ),
+            ],
+            options: options.clone(),
+            ..Default::default()
+        };
+
+        let result = calculate_schedule_results(&poll);
+        let ranked = result["slots"].as_array().unwrap();
+
+        assert_eq!(result["totalParticipants"], 2);
+        assert_eq!(ranked[0]["option_id"], options[1].option_id.as_str());
+        assert_eq!(ranked[1]["option_id"], options[2].option_id.as_str());
+        assert_eq!(ranked[2]["option_id"], options[0].option_id.as_str());
+        assert_eq!(ranked[0]["yes"], 1);
+        assert_eq!(ranked[0]["if_need_be"], 1);
+        assert_eq!(ranked[2]["no"], 1);
+    }
+
+    #[test]
+    fn test_calculate_word_frequencies() {
+        let answers = vec![
+            "Faster builds, please!".to_string(),
+            "The builds are slow. Builds!".to_string(),
+            "More docs and faster CI".to_string(),
+        ];
+
+        let words = calculate_word_frequencies(&answers, 3);
+
+        assert_eq!(
+            words,
+            vec![
+                WordFrequency {
+                    word: "builds".to_string(),
+                    count: 2
+                },
+                WordFrequency {
+                    word: "faster".to_string(),
+                    count: 2
+                },
+                WordFrequency {
+                    word: "docs".to_string(),
+                    count: 1
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use nanoid::nanoid;
+use sha2::{Digest, Sha256};
+
+use crate::config::config::PowConfig;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const CHALLENGE_VERSION: &str = "v1";
+
+// Adds a bit, doubling the work, once the poll takes `votes_per_step` votes a minute and
+// another each time that rate doubles, so a flood of ballots slows down as it grows.
+pub fn difficulty_for_rate(recent_votes: u64, config: &PowConfig) -> u32 {
+    let steps = (1 + recent_votes / config.votes_per_step.max(1)).ilog2();
+
+    (config.base_difficulty + steps).min(config.max_difficulty)
+}
+
+fn challenge_mac(secret: &str, payload: &str) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+
+    mac.update(payload.as_bytes());
+
+    mac
+}
+
+// Challenges are signed rather than stored: the poll, difficulty and expiry travel with
+// the challenge, and the signature keeps clients from lowering the difficulty.
+pub fn issue_challenge(
+    secret: &str,
+    poll_id: &str,
+    difficulty: u32,
+    expires_at: DateTime<Utc>,
+) -> String {
+    let payload = format!(
+        "{}.{}.{}.{}.{}",
+        CHALLENGE_VERSION,
+        poll_id,
+        difficulty,
+        expires_at.timestamp(),
+        nanoid!(16)
+    );
+    let signature = hex::encode(challenge_mac(secret, &payload).finalize().into_bytes());
+
+    format!("{}.{}", payload, signature)
+}
+
+pub fn leading_zero_bits(digest: &[u8]) -> u32 {
+    let mut bits = 0;
+
+    for byte in digest {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+
+    bits
+}
+
+// A solution is any string for which SHA-256 of `challenge:solution` starts with at
+// least `difficulty` zero bits.
+pub fn solution_difficulty(challenge: &str, solution: &str) -> u32 {
+    let digest = Sha256::digest(format!("{}:{}", challenge, solution).as_bytes());
+
+    leading_zero_bits(&digest)
+}
+
+// Key under which a spent challenge is remembered, so it cannot be replayed.
+pub fn challenge_digest(challenge: &str) -> String {
+    hex::encode(Sha256::digest(challenge.as_bytes()))
+}
+
+// Checks that the challenge was issued by this server for the poll, has not expired and
+// is solved. Returns the reason a proof is refused.
+pub fn verify_proof_of_work(
+    secret: &str,
+    poll_id: &str,
+    challenge: &str,
+    solution: &str,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let (payload, signature) = challenge
+        .rsplit_once('.')
+        .ok_or_else(|| "Malformed challenge.".to_string())?;
+
+    // Compared in constant time, so the signature cannot be guessed byte by byte.
+    let signature = hex::decode(signature)
+        .map_err(|_| "Challenge was not issued by this server.".to_string())?;
+    challenge_mac(secret, payload)
+        .verify_slice(&signature)
+        .map_err(|_| "Challenge was not issued by this server.".to_string())?;
+
+    let parts: Vec<&str> = payload.split('.').collect();
+    let [CHALLENGE_VERSION, challenge_poll_id, difficulty, expires_at, _nonce] = parts[..] else {
+        return Err("Malformed challenge.".to_string());
+    };
+    let (Ok(difficulty), Ok(expires_at)) = (difficulty.parse::<u32>(), expires_at.parse::<i64>())
+    else {
+        return Err("Malformed challenge.".to_string());
+    };
+
+    if challenge_poll_id != poll_id {
+        return Err("Challenge was issued for another poll.".to_string());
+    }
+
+    if now.timestamp() >= expires_at {
+        return Err("Challenge has expired.".to_string());
+    }
+
+    if solution_difficulty(challenge, solution) < difficulty {
+        return Err("Solution does not meet the challenge difficulty.".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn config() -> PowConfig {
+        PowConfig {
+            secret: "secret".to_string(),
+            base_difficulty: 16,
+            max_difficulty: 20,
+            votes_per_step: 20,
+            challenge_ttl_secs: 300,
+        }
+    }
+
+    fn solve(challenge: &str, difficulty: u32) -> String {
+        (0u64..)
+            .map(|counter| counter.to_string())
+            .find(|solution| solution_difficulty(challenge, solution) >= difficulty)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_difficulty_grows_with_vote_rate() {
+        let config = config();
+
+        assert_eq!(difficulty_for_rate(0, &config), 16);
+        assert_eq!(difficulty_for_rate(19, &config), 16);
+        assert_eq!(difficulty_for_rate(20, &config), 17);
+        assert_eq!(difficulty_for_rate(60, &config), 18);
+        assert_eq!(difficulty_for_rate(140, &config), 19);
+        assert_eq!(difficulty_for_rate(100_000, &config), 20);
+    }
+
+    #[test]
+    fn test_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0x00, 0x00, 0xff]), 16);
+        assert_eq!(leading_zero_bits(&[0x00, 0x1f]), 11);
+        assert_eq!(leading_zero_bits(&[0x80]), 0);
+        assert_eq!(leading_zero_bits(&[0x00, 0x00]), 16);
+    }
+
+    #[test]
+    fn test_solved_challenge_is_accepted() {
+        let now = Utc::now();
+        let challenge = issue_challenge("secret", "poll1", 8, now + Duration::minutes(5));
+        let solution = solve(&challenge, 8);
+
+        assert!(verify_proof_of_work("secret", "poll1", &challenge, &solution, now).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_proofs_are_refused() {
+        let now = Utc::now();
+        let challenge = issue_challenge("secret", "poll1", 8, now + Duration::minutes(5));
+        let solution = solve(&challenge, 8);
+
+        // Wrong poll, wrong secret, expired, or an easier difficulty spliced in.
+        assert!(verify_proof_of_work("secret", "poll2", &challenge, &solution, now).is_err());
+        assert!(verify_proof_of_work("other", "poll1", &challenge, &solution, now).is_err());
+        assert!(verify_proof_of_work(
+            "secret",
+            "poll1",
+            &challenge,
+            &solution,
+            now + Duration::minutes(6)
+        )
+        .is_err());
+
+        let tampered = challenge.replacen(".8.", ".0.", 1);
+        assert!(verify_proof_of_work("secret", "poll1", &tampered, "x", now).is_err());
+        assert!(verify_proof_of_work("secret", "poll1", "garbage", "x", now).is_err());
+        let not_hex = format!("{}zz", challenge);
+        assert!(verify_proof_of_work("secret", "poll1", &not_hex, &solution, now).is_err());
+
+        let unsolved = (0u64..)
+            .map(|counter| counter.to_string())
+            .find(|solution| solution_difficulty(&challenge, solution) < 8)
+            .unwrap();
+        assert!(verify_proof_of_work("secret", "poll1", &challenge, &unsolved, now).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use actix_web::web::Data;
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use tokio::time::{interval, Duration};
+
+use crate::db::mongodb_repository::MongoDB;
+
+const PURGE_INTERVAL_SECS: u64 = 60 * 60;
+
+// Permanently removes soft deleted polls once they are past the retention window.
+pub fn spawn_purge_job(db: Data<MongoDB>, retention_days: i64) {
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(PURGE_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            let cutoff = Utc::now() - chrono::Duration::days(retention_days);
+
+            match purge_expired_polls(&db, cutoff).await {
+                Ok(purged) if purged > 0 => {
+                    info!("Purged {} deleted polls.", purged);
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    error!("Failed to purge deleted polls: {}", err);
+                }
+            }
+        }
+    });
+}
+
+// Reports and webhooks of the polls go before the polls themselves, so a purge that fails
+// halfway is completed by the next pass instead of leaving orphans behind.
+async fn purge_expired_polls(db: &MongoDB, cutoff: DateTime<Utc>) -> Result<u64, String> {
+    let poll_ids = db
+        .poll_repository
+        .get_expired_poll_ids(cutoff)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if poll_ids.is_empty() {
+        return Ok(0);
+    }
+
+    db.report_repository
+        .delete_poll_reports(&poll_ids)
+        .await
+        .map_err(|err| err.to_string())?;
+    db.webhook_repository
+        .delete_poll_webhooks(&poll_ids)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let result = db
+        .poll_repository
+        .purge_polls(&poll_ids)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    Ok(result.deleted_count)
+}
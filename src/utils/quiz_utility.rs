@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::poll_model::{
+    Leaderboard, LeaderboardEntry, OptionItem, Poll, QuizScore, QuizSettings,
+};
+use crate::utils::types::QuizCreation;
+
+pub const MAX_QUIZ_TIME_LIMIT_SECS: u32 = 3600;
+
+const CORRECT_ANSWER_POINTS: u32 = 500;
+const MAX_SPEED_BONUS_POINTS: u32 = 500;
+// Questions without a time limit still reward speed, over this window.
+const DEFAULT_SPEED_WINDOW_SECS: u32 = 30;
+
+// Resolves the correct option positions of a new quiz question to the generated ids. The
+// question opens to answers at `opened_at`.
+pub fn build_quiz_settings(
+    quiz: &QuizCreation,
+    quiz_id: String,
+    options: &[OptionItem],
+    opened_at: DateTime<Utc>,
+) -> QuizSettings {
+    let correct_option_ids = options
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| quiz.correct_options.contains(index))
+        .map(|(_, option)| option.option_id.clone())
+        .collect();
+
+    let mut settings = QuizSettings {
+        quiz_id,
+        correct_option_ids,
+        time_limit_secs: quiz.time_limit_secs,
+        opened_at: None,
+        closes_at: None,
+    };
+    open_quiz_question(&mut settings, opened_at);
+
+    settings
+}
+
+// The time limit and the speed bonus both run from the moment the question opens.
+pub fn open_quiz_question(quiz: &mut QuizSettings, now: DateTime<Utc>) {
+    quiz.opened_at = Some(now);
+    quiz.closes_at = quiz
+        .time_limit_secs
+        .map(|secs| now + Duration::seconds(secs as i64));
+}
+
+// A correct answer earns fixed points plus a bonus that shrinks linearly with the time
+// taken to answer; a wrong answer earns nothing.
+pub fn score_quiz_answer(correct: bool, elapsed: Duration, time_limit_secs: Option<u32>) -> u32 {
+    if !correct {
+        return 0;
+    }
+
+    let window_ms = time_limit_secs.unwrap_or(DEFAULT_SPEED_WINDOW_SECS) as i64 * 1000;
+    let elapsed_ms = elapsed.num_milliseconds().clamp(0, window_ms);
+    let remaining = (window_ms - elapsed_ms) as f64 / window_ms as f64;
+
+    CORRECT_ANSWER_POINTS + (MAX_SPEED_BONUS_POINTS as f64 * remaining).round() as u32
+}
+
+// Answers to questions that are still open are left out of the standings, as their
+// changes would tell players which option is correct.
+pub fn closed_question_scores(
+    scores: &[QuizScore],
+    questions: &[Poll],
+    now: DateTime<Utc>,
+) -> Vec<QuizScore> {
+    scores
+        .iter()
+        .filter(|score| {
+            questions.iter().any(|question| {
+                question.poll_id == score.poll_id && question.is_question_closed(now)
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+// Ranks players by total score, breaking ties by the total time they took. Players with
+// the same score and time share a rank.
+pub fn build_leaderboard(quiz_id: &str, scores: &[QuizScore]) -> Leaderboard {
+    let mut totals: HashMap<&str, LeaderboardEntry> = HashMap::new();
+
+    for score in scores {
+        let entry = totals
+            .entry(score.username.as_str())
+            .or_insert_with(|| LeaderboardEntry {
+                rank: 0,
+                username: score.username.clone(),
+                score: 0,
+                correct_answers: 0,
+                answered: 0,
+                total_elapsed_ms: 0,
+            });
+
+        entry.score += score.score;
+        entry.correct_answers += score.correct as u32;
+        entry.answered += 1;
+        entry.total_elapsed_ms += score.elapsed_ms;
+    }
+
+    let mut entries: Vec<LeaderboardEntry> = totals.into_values().collect();
+    entries.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(a.total_elapsed_ms.cmp(&b.total_elapsed_ms))
+            .then_with(|| a.username.cmp(&b.username))
+    });
+
+    for index in 0..entries.len() {
+        entries[index].rank = match index {
+            0 => 1,
+            _ if entries[index].score == entries[index - 1].score
+                && entries[index].total_elapsed_ms == entries[index - 1].total_elapsed_ms =>
+            {
+                entries[index - 1].rank
+            }
+            _ => index + 1,
+        };
+    }
+
+    Leaderboard {
+        quiz_id: quiz_id.to_string(),
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::poll_model::VoteHistory;
+
+    fn score(username: &str, correct: bool, points: u32, elapsed_ms: i64) -> QuizScore {
+        QuizScore {
+            quiz_id: "quiz".to_string(),
+            poll_id: "question".to_string(),
+            username: username.to_string(),
+            option_id: "1".to_string(),
+            correct,
+            score: points,
+            elapsed_ms,
+            answered_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_score_quiz_answer_rewards_correctness_and_speed() {
+        assert_eq!(score_quiz_answer(false, Duration::seconds(1), Some(10)), 0);
+        assert_eq!(score_quiz_answer(true, Duration::zero(), Some(10)), 1000);
+        assert_eq!(score_quiz_answer(true, Duration::seconds(5), Some(10)), 750);
+        assert_eq!(
+            score_quiz_answer(true, Duration::seconds(60), Some(10)),
+            500
+        );
+        assert_eq!(score_quiz_answer(true, Duration::seconds(15), None), 750);
+    }
+
+    #[test]
+    fn test_time_limit_runs_from_the_opening() {
+        let created_at = Utc::now() - Duration::minutes(10);
+        let quiz = QuizCreation {
+            quiz_id: None,
+            correct_options: vec![1],
+            time_limit_secs: Some(20),
+        };
+        let options: Vec<OptionItem> = ["a", "b"]
+            .iter()
+            .enumerate()
+            .map(|(index, text)| OptionItem {
+                option_id: index.to_string(),
+                text: text.to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        let mut settings = build_quiz_settings(&quiz, "quiz".to_string(), &options, created_at);
+        assert_eq!(settings.correct_option_ids, vec!["1".to_string()]);
+        assert_eq!(settings.opened_at, Some(created_at));
+        assert_eq!(settings.closes_at, Some(created_at + Duration::seconds(20)));
+
+        let presented_at = Utc::now();
+        open_quiz_question(&mut settings, presented_at);
+        assert_eq!(settings.opened_at, Some(presented_at));
+        assert_eq!(
+            settings.closes_at,
+            Some(presented_at + Duration::seconds(20))
+        );
+    }
+
+    #[test]
+    fn test_correct_options_are_hidden_until_the_question_closes() {
+        let now = Utc::now();
+        let poll = Poll {
+            username: "host".to_string(),
+            is_active: true,
+            created_at: now,
+            quiz: Some(QuizSettings {
+                quiz_id: "quiz".to_string(),
+                correct_option_ids: vec!["1".to_string()],
+                time_limit_secs: Some(20),
+                opened_at: Some(now),
+                closes_at: Some(now + Duration::seconds(20)),
+            }),
+            voters: ["player", "rival"]
+                .iter()
+                .map(|username| VoteHistory {
+                    username: username.to_string(),
+                    option_id: "1".to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let correct = |poll: &Poll, viewer| {
+            poll.for_viewer(viewer, false)
+                .quiz
+                .map(|quiz| quiz.correct_option_ids.len())
+        };
+
+        assert_eq!(correct(&poll, Some("player")), Some(0));
+        assert_eq!(correct(&poll, Some("host")), Some(1));
+        assert_eq!(poll.for_viewer(Some("player"), true).voters.len(), 1);
+        assert_eq!(poll.for_viewer(Some("host"), true).voters.len(), 2);
+
+        let timed_out = Poll {
+            quiz: Some(QuizSettings {
+                closes_at: Some(now - Duration::seconds(1)),
+                ..poll.quiz.clone().unwrap()
+            }),
+            ..poll.clone()
+        };
+        assert!(timed_out.is_question_closed(now));
+        assert_eq!(correct(&timed_out, Some("player")), Some(1));
+        assert_eq!(timed_out.for_viewer(Some("player"), true).voters.len(), 2);
+    }
+
+    #[test]
+    fn test_open_questions_are_left_out_of_the_standings() {
+        let now = Utc::now();
+        let question = |poll_id: &str, is_active| Poll {
+            poll_id: poll_id.to_string(),
+            is_active,
+            ..Default::default()
+        };
+        let questions = vec![question("closed", false), question("open", true)];
+        let scores = vec![
+            QuizScore {
+                poll_id: "closed".to_string(),
+                ..score("ana", true, 900, 2000)
+            },
+            QuizScore {
+                poll_id: "open".to_string(),
+                ..score("ben", true, 900, 1000)
+            },
+        ];
+
+        let counted = closed_question_scores(&scores, &questions, now);
+        assert_eq!(counted.len(), 1);
+        assert_eq!(counted[0].username, "ana");
+    }
+
+    #[test]
+    fn test_build_leaderboard_ranks_by_score_then_time() {
+        let scores = vec![
+            score("ana", true, 900, 2000),
+            score("ben", true, 700, 6000),
+            score("ana", false, 0, 4000),
+            score("cy", true, 900, 1000),
+            score("ben", true, 200, 3000),
+            score("dee", true, 900, 6000),
+        ];
+
+        let leaderboard = build_leaderboard("quiz", &scores);
+        let ranking: Vec<(&str, usize)> = leaderboard
+            .entries
+            .iter()
+            .map(|entry| (entry.username.as_str(), entry.rank))
+            .collect();
+
+        assert_eq!(ranking, vec![("cy", 1), ("ana", 2), ("dee", 2), ("ben", 4)]);
+        assert_eq!(leaderboard.entries[1].answered, 2);
+        assert_eq!(leaderboard.entries[1].correct_answers, 1);
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use actix_web::http::Method;
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+
+use crate::config::config::BucketLimit;
+
+// Buckets idle for this long have refilled completely and can be forgotten.
+const IDLE_BUCKET_MINUTES: i64 = 60;
+// The in-memory store starts dropping idle buckets once it tracks this many keys.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteGroup {
+    Auth,
+    Create,
+    Vote,
+    Results,
+}
+
+impl RouteGroup {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RouteGroup::Auth => "auth",
+            RouteGroup::Create => "create",
+            RouteGroup::Vote => "vote",
+            RouteGroup::Results => "results",
+        }
+    }
+}
+
+// Sorts a request into the group whose bucket it draws from. Routes outside every
+// group are not limited.
+pub fn route_group(method: &Method, path: &str) -> Option<RouteGroup> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    match (method, segments.as_slice()) {
+        (_, ["api", "auth", ..]) | (&Method::POST, ["api", "polls", _, "guest"]) => {
+            Some(RouteGroup::Auth)
+        }
+        (&Method::POST, ["api", "polls" | "surveys" | "sessions" | "orgs" | "webhooks"])
+        | (&Method::PATCH, ["api", "polls", _])
+        | (&Method::POST, ["api", "integrations", "chat", "command"]) => Some(RouteGroup::Create),
+        (&Method::POST | &Method::DELETE, ["api", "polls", _, "vote"])
+        | (&Method::POST, ["api", "integrations", "chat", "actions"])
+        | (&Method::POST, ["api", "polls", _, "guest", "vote"])
+        | (&Method::POST, ["api", "polls", _, "answers" | "availability"])
+        | (&Method::POST, ["api", "surveys", _, "responses"]) => Some(RouteGroup::Vote),
+        (&Method::GET, ["api", "polls", _, "results" | "answers" | "challenge"])
+        | (&Method::GET, ["api", "quizzes", _, "leaderboard"])
+        | (&Method::GET, ["api", "surveys", _, "results"]) => Some(RouteGroup::Results),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateDecision {
+    Allowed,
+    Limited { retry_after_secs: u64 },
+}
+
+fn tokens_per_ms(limit: &BucketLimit) -> f64 {
+    limit.refill_per_minute as f64 / 60_000.0
+}
+
+// Seconds until a bucket holding `tokens` has one whole token again.
+pub fn retry_after_secs(tokens: f64, limit: &BucketLimit) -> u64 {
+    let rate = tokens_per_ms(limit);
+    if rate <= 0.0 {
+        return 60;
+    }
+
+    (((1.0 - tokens).max(0.0) / rate) / 1000.0).ceil().max(1.0) as u64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TokenBucket {
+    pub fn full(limit: &BucketLimit, now: DateTime<Utc>) -> Self {
+        TokenBucket {
+            tokens: limit.capacity as f64,
+            updated_at: now,
+        }
+    }
+
+    // Refills the bucket for the time passed since it was last used, then takes a token
+    // for the request if one is left.
+    pub fn take(&mut self, limit: &BucketLimit, now: DateTime<Utc>) -> RateDecision {
+        let elapsed = (now - self.updated_at).num_milliseconds().max(0) as f64;
+
+        self.tokens = (self.tokens + elapsed * tokens_per_ms(limit)).min(limit.capacity as f64);
+        self.updated_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            RateDecision::Allowed
+        } else {
+            RateDecision::Limited {
+                retry_after_secs: retry_after_secs(self.tokens, limit),
+            }
+        }
+    }
+}
+
+// Keeps the buckets of the rate limiter. The in-memory store serves a single instance;
+// deployments running several instances plug in a shared store instead.
+pub trait RateLimitStore: Send + Sync {
+    fn take<'a>(
+        &'a self,
+        key: &'a str,
+        limit: &'a BucketLimit,
+    ) -> BoxFuture<'a, Result<RateDecision, String>>;
+}
+
+#[derive(Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn take_at(&self, key: &str, limit: &BucketLimit, now: DateTime<Utc>) -> RateDecision {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= MAX_TRACKED_KEYS {
+            let idle_since = now - Duration::minutes(IDLE_BUCKET_MINUTES);
+            buckets.retain(|_, bucket| bucket.updated_at > idle_since);
+        }
+
+        buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::full(limit, now))
+            .take(limit, now)
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn take<'a>(
+        &'a self,
+        key: &'a str,
+        limit: &'a BucketLimit,
+    ) -> BoxFuture<'a, Result<RateDecision, String>> {
+        let decision = self.take_at(key, limit, Utc::now());
+        Box::pin(async move { Ok(decision) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: BucketLimit = BucketLimit {
+        capacity: 2,
+        refill_per_minute: 6,
+    };
+
+    #[test]
+    fn test_route_group() {
+        assert_eq!(
+            route_group(&Method::POST, "/api/auth/login/start/alice"),
+            Some(RouteGroup::Auth)
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/api/polls/"),
+            Some(RouteGroup::Create)
+        );
+        assert_eq!(
+            route_group(&Method::DELETE, "/api/polls/abc/vote"),
+            Some(RouteGroup::Vote)
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/api/polls/abc/answers"),
+            Some(RouteGroup::Vote)
+        );
+        assert_eq!(
+            route_group(&Method::GET, "/api/polls/abc/answers"),
+            Some(RouteGroup::Results)
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/api/polls/abc/guest"),
+            Some(RouteGroup::Auth)
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/api/polls/abc/guest/vote"),
+            Some(RouteGroup::Vote)
+        );
+        assert_eq!(
+            route_group(&Method::PATCH, "/api/polls/abc"),
+            Some(RouteGroup::Create)
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/api/integrations/chat/command"),
+            Some(RouteGroup::Create)
+        );
+        assert_eq!(
+            route_group(&Method::POST, "/api/integrations/chat/actions"),
+            Some(RouteGroup::Vote)
+        );
+        assert_eq!(route_group(&Method::GET, "/api/polls/abc"), None);
+    }
+
+    #[test]
+    fn test_token_bucket_refills_over_time() {
+        let store = InMemoryRateLimitStore::default();
+        let start = Utc::now();
+
+        assert_eq!(
+            store.take_at("vote:ip:1", &LIMIT, start),
+            RateDecision::Allowed
+        );
+        assert_eq!(
+            store.take_at("vote:ip:1", &LIMIT, start),
+            RateDecision::Allowed
+        );
+        assert_eq!(
+            store.take_at("vote:ip:1", &LIMIT, start),
+            RateDecision::Limited {
+                retry_after_secs: 10
+            }
+        );
+        assert_eq!(
+            store.take_at("vote:ip:2", &LIMIT, start),
+            RateDecision::Allowed
+        );
+
+        let later = start + Duration::seconds(4);
+        assert_eq!(
+            store.take_at("vote:ip:1", &LIMIT, later),
+            RateDecision::Limited {
+                retry_after_secs: 6
+            }
+        );
+
+        let refilled = start + Duration::seconds(10);
+        assert_eq!(
+            store.take_at("vote:ip:1", &LIMIT, refilled),
+            RateDecision::Allowed
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use nanoid::nanoid;
+
+use crate::config::{config::PollLimits, error_response::FieldError, poll_config::PollError};
+use crate::models::poll_model::Poll;
+use crate::models::report_model::{Report, ReportAction, ReportCategory, ReportStatus};
+use crate::utils::types::PollReportRequest;
+
+// Reporter recorded on reports filed by the content filter.
+pub const CONTENT_FILTER_REPORTER: &str = "content_filter";
+
+pub fn validate_report(data: &PollReportRequest, limits: &PollLimits) -> Result<(), PollError> {
+    if data.category == ReportCategory::ContentFilter {
+        return Err(PollError::ValidationError(vec![FieldError::new(
+            "category",
+            "This category is reserved for automatic reports.",
+        )]));
+    }
+
+    if let Some(comment) = &data.comment {
+        if comment.chars().count() > limits.answer_max_length {
+            return Err(PollError::ValidationError(vec![FieldError::new(
+                "comment",
+                format!(
+                    "Comment must be at most {} characters.",
+                    limits.answer_max_length
+                ),
+            )]));
+        }
+    }
+
+    Ok(())
+}
+
+// Open reports can be resolved any way. A hidden poll can still be dismissed, which
+// shows it again, or deleted; dismissed and deleted reports are final.
+pub fn next_report_status(current: ReportStatus, action: ReportAction) -> Option<ReportStatus> {
+    match (current, action) {
+        (ReportStatus::Open | ReportStatus::Hidden, ReportAction::Dismiss) => {
+            Some(ReportStatus::Dismissed)
+        }
+        (ReportStatus::Open, ReportAction::Hide) => Some(ReportStatus::Hidden),
+        (ReportStatus::Open | ReportStatus::Hidden, ReportAction::Delete) => {
+            Some(ReportStatus::Deleted)
+        }
+        _ => None,
+    }
+}
+
+// Queues a poll whose content was flagged, listing what was caught in the comment.
+pub fn build_filter_report(poll: &Poll, matches: &[FieldError], now: DateTime<Utc>) -> Report {
+    let comment = matches
+        .iter()
+        .map(|m| format!("{}: {}", m.field, m.message))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Report {
+        report_id: nanoid!(10),
+        poll_id: poll.poll_id.clone(),
+        poll_title: poll.title.clone(),
+        reporter: CONTENT_FILTER_REPORTER.to_string(),
+        category: ReportCategory::ContentFilter,
+        comment: Some(comment),
+        status: ReportStatus::Open,
+        history: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_report_status() {
+        assert_eq!(
+            next_report_status(ReportStatus::Open, ReportAction::Hide),
+            Some(ReportStatus::Hidden)
+        );
+        assert_eq!(
+            next_report_status(ReportStatus::Hidden, ReportAction::Dismiss),
+            Some(ReportStatus::Dismissed)
+        );
+        assert_eq!(
+            next_report_status(ReportStatus::Hidden, ReportAction::Delete),
+            Some(ReportStatus::Deleted)
+        );
+        assert_eq!(
+            next_report_status(ReportStatus::Hidden, ReportAction::Hide),
+            None
+        );
+        assert_eq!(
+            next_report_status(ReportStatus::Dismissed, ReportAction::Delete),
+            None
+        );
+        assert_eq!(
+            next_report_status(ReportStatus::Deleted, ReportAction::Dismiss),
+            None
+        );
+    }
+
+    #[test]
+    fn test_build_filter_report() {
+        let poll = Poll {
+            poll_id: "poll".to_string(),
+            title: "Lunch?".to_string(),
+            ..Default::default()
+        };
+        let matches = vec![
+            FieldError::new("title", "Contains a term that is not allowed."),
+            FieldError::new("options[1]", "Contains more than 3 links."),
+        ];
+
+        let report = build_filter_report(&poll, &matches, Utc::now());
+
+        assert_eq!(report.reporter, CONTENT_FILTER_REPORTER);
+        assert_eq!(report.category, ReportCategory::ContentFilter);
+        assert_eq!(report.status, ReportStatus::Open);
+        assert_eq!(
+            report.comment.as_deref(),
+            Some("title: Contains a term that is not allowed.\noptions[1]: Contains more than 3 links.")
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use nanoid::nanoid;
+
+use crate::config::{error_response::FieldError, poll_config::PollError};
+use crate::models::poll_model::{
+    Availability, OptionItem, OptionPayload, Poll, SlotAnswer, TimeSlot,
+};
+
+const ICS_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+// iCalendar lines longer than this many octets must be folded.
+const ICS_LINE_LIMIT: usize = 75;
+
+pub fn slot_label(slot: &TimeSlot) -> String {
+    if slot.starts_at.date_naive() == slot.ends_at.date_naive() {
+        format!(
+            "{} - {} UTC",
+            slot.starts_at.format("%Y-%m-%d %H:%M"),
+            slot.ends_at.format("%H:%M")
+        )
+    } else {
+        format!(
+            "{} - {} UTC",
+            slot.starts_at.format("%Y-%m-%d %H:%M"),
+            slot.ends_at.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
+
+// Slots are listed in chronological order, each labelled with its time range.
+pub fn build_slot_options(slots: &[TimeSlot]) -> Vec<OptionItem> {
+    let mut slots = slots.to_vec();
+    slots.sort_by_key(|slot| (slot.starts_at, slot.ends_at));
+
+    slots
+        .into_iter()
+        .map(|slot| OptionItem {
+            option_id: nanoid!(10),
+            text: slot_label(&slot),
+            votes: 0,
+            guest_votes: 0,
+            write_in: false,
+            hidden: false,
+            payload: Some(OptionPayload::TimeSlot(slot)),
+        })
+        .collect()
+}
+
+// Checks a participant's answers and fills in `no` for every slot left out, so stored
+// ballots always cover the whole poll.
+pub fn validate_availability(
+    poll: &Poll,
+    answers: &[SlotAnswer],
+) -> Result<Vec<SlotAnswer>, PollError> {
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (index, answer) in answers.iter().enumerate() {
+        let field = format!("answers[{}]", index);
+
+        if !poll
+            .options
+            .iter()
+            .any(|option| option.option_id == answer.option_id)
+        {
+            errors.push(FieldError::new(field, "Slot does not exist in this poll."));
+        } else if !seen.insert(answer.option_id.as_str()) {
+            errors.push(FieldError::new(field, "Slot answered more than once."));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(PollError::ValidationError(errors));
+    }
+
+    Ok(poll
+        .options
+        .iter()
+        .map(|option| SlotAnswer {
+            option_id: option.option_id.clone(),
+            availability: answers
+                .iter()
+                .find(|answer| answer.option_id == option.option_id)
+                .map(|answer| answer.availability)
+                .unwrap_or(Availability::No),
+        })
+        .collect())
+}
+
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > ICS_LINE_LIMIT {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
+
+// A single-event calendar for the chosen slot of a scheduling poll.
+pub fn build_ics_event(poll: &Poll, option: &OptionItem, now: DateTime<Utc>) -> Option<String> {
+    let slot = option.time_slot()?;
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Polling Application//Scheduling//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!(
+            "UID:{}-{}@polling-application",
+            poll.poll_id, option.option_id
+        ),
+        format!("DTSTAMP:{}", now.format(ICS_DATE_FORMAT)),
+        format!("DTSTART:{}", slot.starts_at.format(ICS_DATE_FORMAT)),
+        format!("DTEND:{}", slot.ends_at.format(ICS_DATE_FORMAT)),
+        format!("SUMMARY:{}", escape_ics_text(&poll.title)),
+    ];
+
+    if let Some(description) = &poll.description {
+        lines.push(format!("DESCRIPTION:{}", escape_ics_text(description)));
+    }
+
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+
+    Some(lines.iter().map(|line| fold_ics_line(line)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(start: &str, end: &str) -> TimeSlot {
+        TimeSlot {
+            starts_at: start.parse().unwrap(),
+            ends_at: end.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_validate_availability_fills_missing_slots() {
+        let poll = Poll {
+            options: build_slot_options(&[
+                slot("2026-10-21T09:00:00Z", "2026-10-21T10:00:00Z"),
+                slot("2026-10-20T09:00:00Z", "2026-10-20T10:00:00Z"),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(poll.options[0].text, "2026-10-20 09:00 - 10:00 UTC");
+
+        let first = poll.options[0].option_id.clone();
+        let answers = validate_availability(
+            &poll,
+            &[SlotAnswer {
+                option_id: first.clone(),
+                availability: Availability::IfNeedBe,
+            }],
+        )
+        .unwrap();
+
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[0].availability, Availability::IfNeedBe);
+        assert_eq!(answers[1].availability, Availability::No);
+
+        let duplicate = SlotAnswer {
+            option_id: first,
+            availability: Availability::Yes,
+        };
+        assert!(matches!(
+            validate_availability(&poll, &[duplicate.clone(), duplicate]),
+            Err(PollError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_ics_event() {
+        let poll = Poll {
+            poll_id: "poll".to_string(),
+            title: "Sync; planning, review".to_string(),
+            options: build_slot_options(&[slot("2026-10-20T09:00:00Z", "2026-10-20T10:30:00Z")]),
+            ..Default::default()
+        };
+
+        let ics = build_ics_event(&poll, &poll.options[0], Utc::now()).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20261020T090000Z\r\n"));
+        assert!(ics.contains("DTEND:20261020T103000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Sync\\; planning\\, review\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+}
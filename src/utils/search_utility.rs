@@ -0,0 +1,196 @@
+use crate::models::poll_model::{Poll, PollSearchHit, SearchHighlight};
+
+const SNIPPET_RADIUS: usize = 40;
+
+// Splits a raw search query into lowercase terms, dropping negated terms and quotes
+// since those only affect matching on the MongoDB side.
+pub fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .filter(|term| !term.starts_with('-'))
+        .map(|term| {
+            term.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+fn lowercase_chars(text: &str) -> Vec<char> {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+fn find_matches(haystack: &[char], terms: &[Vec<char>]) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let mut i = 0;
+
+    while i < haystack.len() {
+        let found = terms
+            .iter()
+            .filter(|term| !term.is_empty() && haystack[i..].starts_with(term))
+            .map(|term| term.len())
+            .max();
+
+        match found {
+            Some(len) => {
+                matches.push((i, i + len));
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+
+    matches
+}
+
+// Builds a snippet around the first matching term, wrapping every match inside the
+// snippet in <mark> tags. Returns None when no term occurs in the text.
+pub fn highlight_snippet(text: &str, terms: &[String]) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let lowered = lowercase_chars(text);
+    let terms: Vec<Vec<char>> = terms.iter().map(|term| lowercase_chars(term)).collect();
+
+    let matches = find_matches(&lowered, &terms);
+    let (first_start, _) = *matches.first()?;
+
+    let start = first_start.saturating_sub(SNIPPET_RADIUS);
+    let end = (first_start + SNIPPET_RADIUS * 2).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+
+    let mut cursor = start;
+    for (match_start, match_end) in matches {
+        if match_start < start || match_start >= end {
+            continue;
+        }
+        let match_end = match_end.min(end);
+        snippet.extend(&chars[cursor..match_start]);
+        snippet.push_str("<mark>");
+        snippet.extend(&chars[match_start..match_end]);
+        snippet.push_str("</mark>");
+        cursor = match_end;
+    }
+    snippet.extend(&chars[cursor..end]);
+
+    if end < chars.len() {
+        snippet.push('…');
+    }
+
+    Some(snippet)
+}
+
+pub fn build_search_hit(poll: Poll, score: f64, terms: &[String]) -> PollSearchHit {
+    let mut highlights = Vec::new();
+
+    if let Some(snippet) = highlight_snippet(&poll.title, terms) {
+        highlights.push(SearchHighlight {
+            field: "title".to_string(),
+            snippet,
+        });
+    }
+
+    if let Some(description) = &poll.description {
+        if let Some(snippet) = highlight_snippet(description, terms) {
+            highlights.push(SearchHighlight {
+                field: "description".to_string(),
+                snippet,
+            });
+        }
+    }
+
+    for option in poll.options.iter().filter(|option| !option.hidden) {
+        if let Some(snippet) = highlight_snippet(&option.text, terms) {
+            highlights.push(SearchHighlight {
+                field: format!("options.{}", option.option_id),
+                snippet,
+            });
+        }
+    }
+
+    PollSearchHit {
+        poll_id: poll.poll_id,
+        username: poll.username,
+        title: poll.title,
+        description: poll.description,
+        is_active: poll.is_active,
+        created_at: poll.created_at,
+        score,
+        highlights,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::poll_model::OptionItem;
+
+    #[test]
+    fn test_search_terms() {
+        let terms = search_terms("Rust \"web framework\" -python");
+        assert_eq!(terms, vec!["rust", "web", "framework"]);
+
+        assert!(search_terms("   ").is_empty());
+    }
+
+    #[test]
+    fn test_highlight_snippet() {
+        let terms = search_terms("rust");
+
+        let snippet = highlight_snippet("Is Rust better than rust-lang?", &terms).unwrap();
+        assert_eq!(
+            snippet,
+            "Is <mark>Rust</mark> better than <mark>rust</mark>-lang?"
+        );
+
+        assert!(highlight_snippet("Favorite language", &terms).is_none());
+    }
+
+    #[test]
+    fn test_highlight_snippet_truncates_long_text() {
+        let text = format!("{} rust {}", "a".repeat(100), "b".repeat(100));
+        let snippet = highlight_snippet(&text, &search_terms("rust")).unwrap();
+
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>rust</mark>"));
+    }
+
+    #[test]
+    fn test_build_search_hit() {
+        let poll = Poll {
+            poll_id: "poll123".to_string(),
+            title: "Favorite Programming Language".to_string(),
+            options: vec![
+                OptionItem {
+                    option_id: "1".to_string(),
+                    text: "Rust".to_string(),
+                    votes: 0,
+                    ..Default::default()
+                },
+                OptionItem {
+                    option_id: "2".to_string(),
+                    text: "Python".to_string(),
+                    votes: 0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let hit = build_search_hit(poll, 1.5, &search_terms("rust language"));
+
+        assert_eq!(hit.highlights.len(), 2);
+        assert_eq!(hit.highlights[0].field, "title");
+        assert_eq!(
+            hit.highlights[0].snippet,
+            "Favorite Programming <mark>Language</mark>"
+        );
+        assert_eq!(hit.highlights[1].field, "options.1");
+        assert_eq!(hit.highlights[1].snippet, "<mark>Rust</mark>");
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use crate::config::{config::PollLimits, error_response::FieldError, session_config::SessionError};
+use crate::models::poll_model::Poll;
+use crate::utils::types::SessionCreation;
+use crate::utils::validation::validate_text;
+
+pub const MAX_SESSION_POLLS: usize = 50;
+
+pub fn validate_session_creation(
+    data: &SessionCreation,
+    limits: &PollLimits,
+) -> Result<(), SessionError> {
+    let mut errors = Vec::new();
+
+    validate_text(
+        &mut errors,
+        "title",
+        &data.title,
+        limits.title_max_length,
+        true,
+    );
+
+    if data.poll_ids.is_empty() || data.poll_ids.len() > MAX_SESSION_POLLS {
+        errors.push(FieldError::new(
+            "pollIds",
+            format!(
+                "A session must present between 1 and {} polls.",
+                MAX_SESSION_POLLS
+            ),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    for (index, poll_id) in data.poll_ids.iter().enumerate() {
+        if !seen.insert(poll_id) {
+            errors.push(FieldError::new(
+                format!("pollIds[{}]", index),
+                "Poll is listed more than once.",
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(SessionError::ValidationError(errors))
+    }
+}
+
+// Every poll of a session must exist, belong to the presenter and not already be part of
+// another session. `polls` holds the lookup result for each id, in order.
+pub fn validate_session_polls(username: &str, polls: &[Option<Poll>]) -> Result<(), SessionError> {
+    let errors: Vec<FieldError> = polls
+        .iter()
+        .enumerate()
+        .filter_map(|(index, poll)| {
+            let message = match poll {
+                None => "Poll does not exist.",
+                Some(poll) if poll.username != username => "Only your own polls can be presented.",
+                Some(poll) if poll.session_id.is_some() => {
+                    "Poll is already part of another session."
+                }
+                Some(_) => return None,
+            };
+
+            Some(FieldError::new(format!("pollIds[{}]", index), message))
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(SessionError::ValidationError(errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_session_polls() {
+        let poll = |username: &str, session_id: Option<&str>| {
+            Some(Poll {
+                username: username.to_string(),
+                session_id: session_id.map(str::to_string),
+                ..Default::default()
+            })
+        };
+
+        assert!(validate_session_polls("host", &[poll("host", None)]).is_ok());
+
+        let fields = match validate_session_polls(
+            "host",
+            &[
+                poll("host", None),
+                None,
+                poll("guest", None),
+                poll("host", Some("other")),
+            ],
+        ) {
+            Err(SessionError::ValidationError(errors)) => {
+                errors.into_iter().map(|error| error.field).collect()
+            }
+            _ => vec![],
+        };
+
+        assert_eq!(fields, vec!["pollIds[1]", "pollIds[2]", "pollIds[3]"]);
+    }
+}
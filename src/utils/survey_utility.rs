@@ -0,0 +1,556 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use nanoid::nanoid;
+
+use crate::config::{config::PollLimits, error_response::FieldError, survey_config::SurveyError};
+use crate::models::poll_model::{OptionItem, Poll};
+use crate::models::survey_model::{
+    QuestionKind, QuestionResults, Survey, SurveyAnswer, SurveyQuestion, SurveyResponse,
+    SurveyResults,
+};
+use crate::utils::poll_results_utility::calculate_poll_results;
+use crate::utils::types::{QuestionCreation, SurveyCreation};
+use crate::utils::validation::{validate_options, validate_text};
+
+pub const MAX_SURVEY_QUESTIONS: usize = 50;
+pub const DEFAULT_RATING_SCALE: u32 = 5;
+const RATING_SCALES: std::ops::RangeInclusive<u32> = 2..=10;
+
+fn validation_result(errors: Vec<FieldError>) -> Result<(), SurveyError> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(SurveyError::ValidationError(errors))
+    }
+}
+
+// Prompts follow the same limits as poll titles and choice questions the same rules as
+// poll options. Errors of a question are reported under `questions[i]`.
+pub fn validate_survey_creation(
+    data: &SurveyCreation,
+    limits: &PollLimits,
+) -> Result<(), SurveyError> {
+    let mut errors = Vec::new();
+
+    validate_text(
+        &mut errors,
+        "title",
+        &data.title,
+        limits.title_max_length,
+        true,
+    );
+
+    if let Some(description) = &data.description {
+        validate_text(
+            &mut errors,
+            "description",
+            description,
+            limits.description_max_length,
+            false,
+        );
+    }
+
+    if data.questions.is_empty() || data.questions.len() > MAX_SURVEY_QUESTIONS {
+        errors.push(FieldError::new(
+            "questions",
+            format!(
+                "A survey must have between 1 and {} questions.",
+                MAX_SURVEY_QUESTIONS
+            ),
+        ));
+    }
+
+    for (index, question) in data.questions.iter().enumerate() {
+        let prefix = format!("questions[{}]", index);
+
+        validate_text(
+            &mut errors,
+            &format!("{}.prompt", prefix),
+            &question.prompt,
+            limits.title_max_length,
+            true,
+        );
+
+        if question.kind == QuestionKind::SingleChoice {
+            let mut option_errors = Vec::new();
+            validate_options(&mut option_errors, &question.options, limits);
+
+            errors.extend(option_errors.into_iter().map(|error| {
+                FieldError::new(format!("{}.{}", prefix, error.field), error.message)
+            }));
+        } else if !question.options.is_empty() {
+            errors.push(FieldError::new(
+                format!("{}.options", prefix),
+                "Only single choice questions take options.",
+            ));
+        }
+
+        if question.kind == QuestionKind::Rating {
+            let scale = question.scale.unwrap_or(DEFAULT_RATING_SCALE);
+            if !RATING_SCALES.contains(&scale) {
+                errors.push(FieldError::new(
+                    format!("{}.scale", prefix),
+                    format!(
+                        "Must be between {} and {}.",
+                        RATING_SCALES.start(),
+                        RATING_SCALES.end()
+                    ),
+                ));
+            }
+        }
+    }
+
+    validation_result(errors)
+}
+
+fn build_question(question: &QuestionCreation) -> SurveyQuestion {
+    let options = match question.kind {
+        QuestionKind::SingleChoice => question
+            .options
+            .iter()
+            .map(|text| OptionItem {
+                option_id: nanoid!(10),
+                text: text.trim().to_string(),
+                votes: 0,
+                guest_votes: 0,
+                write_in: false,
+                hidden: false,
+                payload: None,
+            })
+            .collect(),
+        QuestionKind::Rating => (1..=question.scale.unwrap_or(DEFAULT_RATING_SCALE))
+            .map(|value| OptionItem {
+                option_id: value.to_string(),
+                text: value.to_string(),
+                votes: 0,
+                guest_votes: 0,
+                write_in: false,
+                hidden: false,
+                payload: None,
+            })
+            .collect(),
+        QuestionKind::FreeText => vec![],
+    };
+
+    SurveyQuestion {
+        question_id: nanoid!(10),
+        kind: question.kind,
+        prompt: question.prompt.trim().to_string(),
+        options,
+        required: question.required,
+    }
+}
+
+pub fn build_survey(data: &SurveyCreation, username: &str, now: DateTime<Utc>) -> Survey {
+    Survey {
+        survey_id: nanoid!(10),
+        username: username.to_string(),
+        title: data.title.trim().to_string(),
+        description: data
+            .description
+            .as_ref()
+            .map(|description| description.trim().to_string()),
+        questions: data.questions.iter().map(build_question).collect(),
+        is_active: true,
+        respondents: vec![],
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+// Checks a submission against the survey and returns the answers to store, with free
+// text trimmed and unanswered optional questions left out.
+pub fn validate_submission(
+    survey: &Survey,
+    answers: &[SurveyAnswer],
+    limits: &PollLimits,
+) -> Result<Vec<SurveyAnswer>, SurveyError> {
+    let mut errors = Vec::new();
+    let mut accepted = Vec::new();
+    let mut answered = HashSet::new();
+
+    for (index, answer) in answers.iter().enumerate() {
+        let field = format!("answers[{}]", index);
+
+        let question = match survey
+            .questions
+            .iter()
+            .find(|question| question.question_id == answer.question_id)
+        {
+            Some(question) => question,
+            None => {
+                errors.push(FieldError::new(
+                    field,
+                    "Question does not exist in this survey.",
+                ));
+                continue;
+            }
+        };
+
+        if !answered.insert(question.question_id.clone()) {
+            errors.push(FieldError::new(field, "Question was answered twice."));
+            continue;
+        }
+
+        match question.kind {
+            QuestionKind::SingleChoice | QuestionKind::Rating => {
+                let option_id = answer.option_id.as_deref().unwrap_or_default();
+
+                if question
+                    .options
+                    .iter()
+                    .any(|option| option.option_id == option_id)
+                {
+                    accepted.push(SurveyAnswer {
+                        question_id: question.question_id.clone(),
+                        option_id: Some(option_id.to_string()),
+                        text: None,
+                    });
+                } else {
+                    errors.push(FieldError::new(
+                        format!("{}.optionId", field),
+                        "Option does not exist in this question.",
+                    ));
+                }
+            }
+            QuestionKind::FreeText => {
+                let text = answer.text.as_deref().unwrap_or_default().trim();
+                let length = text.chars().count();
+
+                if length == 0 {
+                    errors.push(FieldError::new(
+                        format!("{}.text", field),
+                        "Must not be empty.",
+                    ));
+                } else if length > limits.description_max_length {
+                    errors.push(FieldError::new(
+                        format!("{}.text", field),
+                        format!(
+                            "Must be at most {} characters long.",
+                            limits.description_max_length
+                        ),
+                    ));
+                } else {
+                    accepted.push(SurveyAnswer {
+                        question_id: question.question_id.clone(),
+                        option_id: None,
+                        text: Some(text.to_string()),
+                    });
+                }
+            }
+        }
+    }
+
+    for question in &survey.questions {
+        if question.required && !answered.contains(&question.question_id) {
+            errors.push(FieldError::new(
+                format!("questions.{}", question.question_id),
+                "This question requires an answer.",
+            ));
+        }
+    }
+
+    validation_result(errors)?;
+
+    Ok(accepted)
+}
+
+// Presents a tallied question as a poll so it can go through `calculate_poll_results`.
+fn question_as_poll(survey: &Survey, question: &SurveyQuestion) -> Poll {
+    Poll {
+        poll_id: question.question_id.clone(),
+        username: survey.username.clone(),
+        title: question.prompt.clone(),
+        options: question.options.clone(),
+        is_active: survey.is_active,
+        created_at: survey.created_at,
+        updated_at: survey.updated_at,
+        ..Default::default()
+    }
+}
+
+fn rating_average(question: &SurveyQuestion) -> Option<f64> {
+    let (total, count) = question
+        .options
+        .iter()
+        .fold((0.0, 0u32), |(total, count), option| {
+            let value: f64 = option.option_id.parse().unwrap_or(0.0);
+            (total + value * option.votes as f64, count + option.votes)
+        });
+
+    (count > 0).then(|| total / count as f64)
+}
+
+pub fn calculate_survey_results(survey: &Survey, responses: &[SurveyResponse]) -> SurveyResults {
+    let questions = survey
+        .questions
+        .iter()
+        .map(|question| {
+            let answers = match question.kind {
+                QuestionKind::FreeText => responses
+                    .iter()
+                    .flat_map(|response| &response.answers)
+                    .filter(|answer| answer.question_id == question.question_id)
+                    .filter_map(|answer| answer.text.clone())
+                    .collect(),
+                _ => vec![],
+            };
+
+            QuestionResults {
+                question_id: question.question_id.clone(),
+                kind: question.kind,
+                prompt: question.prompt.clone(),
+                tally: (question.kind != QuestionKind::FreeText)
+                    .then(|| calculate_poll_results(&question_as_poll(survey, question))),
+                average: match question.kind {
+                    QuestionKind::Rating => rating_average(question),
+                    _ => None,
+                },
+                answers,
+            }
+        })
+        .collect();
+
+    SurveyResults {
+        survey_id: survey.survey_id.clone(),
+        title: survey.title.clone(),
+        total_responses: survey.respondents.len(),
+        questions,
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// One row per response and one column per question, holding the option text or the
+// free-text answer.
+pub fn export_survey_csv(survey: &Survey, responses: &[SurveyResponse]) -> String {
+    let mut header = vec![
+        "responseId".to_string(),
+        "username".to_string(),
+        "createdAt".to_string(),
+    ];
+    header.extend(
+        survey
+            .questions
+            .iter()
+            .map(|question| question.prompt.clone()),
+    );
+
+    let mut lines = vec![header
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")];
+
+    for response in responses {
+        let mut row = vec![
+            response.response_id.clone(),
+            response.username.clone(),
+            response.created_at.to_rfc3339(),
+        ];
+
+        for question in &survey.questions {
+            let answer = response
+                .answers
+                .iter()
+                .find(|answer| answer.question_id == question.question_id);
+
+            let value = match answer {
+                Some(SurveyAnswer {
+                    option_id: Some(option_id),
+                    ..
+                }) => question
+                    .options
+                    .iter()
+                    .find(|option| &option.option_id == option_id)
+                    .map(|option| option.text.clone())
+                    .unwrap_or_default(),
+                Some(SurveyAnswer {
+                    text: Some(text), ..
+                }) => text.clone(),
+                _ => String::new(),
+            };
+
+            row.push(value);
+        }
+
+        lines.push(
+            row.iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> PollLimits {
+        PollLimits {
+            title_max_length: 20,
+            description_max_length: 20,
+            min_options: 2,
+            max_options: 3,
+            option_max_length: 10,
+            answer_max_length: 20,
+        }
+    }
+
+    fn sample_survey() -> Survey {
+        let data = SurveyCreation {
+            title: "Retro".to_string(),
+            description: None,
+            questions: vec![
+                QuestionCreation {
+                    kind: QuestionKind::SingleChoice,
+                    prompt: "Sprint went".to_string(),
+                    options: vec!["Well".to_string(), "Badly".to_string()],
+                    required: true,
+                    ..Default::default()
+                },
+                QuestionCreation {
+                    kind: QuestionKind::Rating,
+                    prompt: "Mood".to_string(),
+                    scale: Some(3),
+                    ..Default::default()
+                },
+                QuestionCreation {
+                    kind: QuestionKind::FreeText,
+                    prompt: "Comments".to_string(),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        assert!(validate_survey_creation(&data, &limits()).is_ok());
+        build_survey(&data, "Azeem", Utc::now())
+    }
+
+    fn answer(
+        question: &SurveyQuestion,
+        option_id: Option<&str>,
+        text: Option<&str>,
+    ) -> SurveyAnswer {
+        SurveyAnswer {
+            question_id: question.question_id.clone(),
+            option_id: option_id.map(str::to_string),
+            text: text.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_validate_survey_creation_reports_question_fields() {
+        let data = SurveyCreation {
+            title: "Retro".to_string(),
+            description: None,
+            questions: vec![
+                QuestionCreation {
+                    kind: QuestionKind::SingleChoice,
+                    prompt: " ".to_string(),
+                    options: vec!["Well".to_string()],
+                    ..Default::default()
+                },
+                QuestionCreation {
+                    kind: QuestionKind::Rating,
+                    prompt: "Mood".to_string(),
+                    scale: Some(20),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let fields: Vec<String> = match validate_survey_creation(&data, &limits()) {
+            Err(SurveyError::ValidationError(errors)) => {
+                errors.into_iter().map(|error| error.field).collect()
+            }
+            _ => vec![],
+        };
+
+        assert_eq!(
+            fields,
+            vec![
+                "questions[0].prompt",
+                "questions[0].options",
+                "questions[1].scale"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_submission() {
+        let survey = sample_survey();
+        let [choice, rating, free_text] = &survey.questions[..] else {
+            panic!("expected three questions");
+        };
+
+        let accepted = validate_submission(
+            &survey,
+            &[
+                answer(choice, Some(&choice.options[0].option_id), None),
+                answer(rating, Some("3"), None),
+                answer(free_text, None, Some("  Ship it ")),
+            ],
+            &limits(),
+        )
+        .unwrap();
+        assert_eq!(accepted[2].text.as_deref(), Some("Ship it"));
+
+        assert!(matches!(
+            validate_submission(&survey, &[answer(rating, Some("4"), None)], &limits()),
+            Err(SurveyError::ValidationError(errors)) if errors.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_survey_results_and_export() {
+        let mut survey = sample_survey();
+        survey.questions[1].options[2].votes = 1;
+        survey.questions[1].options[0].votes = 1;
+        survey.respondents = vec!["a".to_string(), "b".to_string()];
+
+        let free_text = survey.questions[2].clone();
+        let response = SurveyResponse {
+            response_id: "r1".to_string(),
+            survey_id: survey.survey_id.clone(),
+            username: "a".to_string(),
+            answers: vec![
+                answer(&survey.questions[1], Some("3"), None),
+                answer(&free_text, None, Some("Fewer \"meetings\", please")),
+            ],
+            created_at: Utc::now(),
+        };
+
+        let results = calculate_survey_results(&survey, std::slice::from_ref(&response));
+        assert_eq!(results.total_responses, 2);
+        assert_eq!(results.questions[1].average, Some(2.0));
+        assert_eq!(
+            results.questions[1].tally.as_ref().unwrap()["totalVotes"],
+            2
+        );
+        assert!(results.questions[2].tally.is_none());
+        assert_eq!(
+            results.questions[2].answers,
+            vec!["Fewer \"meetings\", please"]
+        );
+
+        let csv = export_survey_csv(&survey, &[response]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "responseId,username,createdAt,Sprint went,Mood,Comments"
+        );
+        assert!(lines[1].starts_with("r1,a,"));
+        assert!(lines[1].ends_with(",,3,\"Fewer \"\"meetings\"\", please\""));
+    }
+}
@@ -1,22 +1,331 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::models::organization_model::OrganizationRole;
+use crate::models::poll_model::{
+    PollKind, PollRole, PollVisibility, ResultsVisibility, SlotAnswer, TimeSlot,
+};
+use crate::models::report_model::{ReportAction, ReportCategory};
+use crate::models::survey_model::{QuestionKind, SurveyAnswer};
+use crate::models::user_model::UserRole;
+use crate::models::webhook_model::{WebhookEvent, WebhookSummary};
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PollCreation {
     pub title: String,
-    pub username: String,
+    #[serde(default)]
+    pub description: Option<String>,
     pub options: Vec<String>,
+    #[serde(default)]
+    pub visibility: PollVisibility,
+    #[serde(default)]
+    pub anonymous: bool,
+    #[serde(default)]
+    pub results_visibility: ResultsVisibility,
+    #[serde(default)]
+    pub allow_write_ins: bool,
+    #[serde(default)]
+    pub kind: PollKind,
+    #[serde(default)]
+    pub quiz: Option<QuizCreation>,
+    #[serde(default)]
+    pub slots: Vec<TimeSlot>,
+    #[serde(default)]
+    pub lock_votes: bool,
+    #[serde(default)]
+    pub require_proof_of_work: bool,
+    #[serde(default)]
+    pub allow_guests: bool,
+    // Creates the poll inside this organization; the creator must be a member.
+    #[serde(default)]
+    pub org_id: Option<String>,
+}
+
+// Correct options are given by their position in `options`, since option ids are only
+// assigned once the poll is created. Questions sharing a `quizId` form one quiz.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuizCreation {
+    pub quiz_id: Option<String>,
+    pub correct_options: Vec<usize>,
+    pub time_limit_secs: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct VoteOption {
+    #[serde(default)]
+    pub option_id: String,
+    // Free-text option submitted instead of an option id, for polls allowing write-ins.
+    pub write_in: Option<String>,
+    // Solved challenge, for polls requiring proof of work.
+    #[serde(default)]
+    pub proof_of_work: Option<ProofOfWork>,
+}
+
+// A ballot cast with a guest token. Guests pick one of the existing options.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestVote {
     pub option_id: String,
-    pub username: String,
+    #[serde(default)]
+    pub proof_of_work: Option<ProofOfWork>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofOfWork {
+    pub challenge: String,
+    pub solution: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PollEdit {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub visibility: Option<PollVisibility>,
+    pub results_visibility: Option<ResultsVisibility>,
+    pub lock_votes: Option<bool>,
+    #[serde(default)]
+    pub add_options: Vec<String>,
+    #[serde(default)]
+    pub remove_options: Vec<String>,
+    #[serde(default)]
+    pub rename_options: Vec<OptionRename>,
+    #[serde(default)]
+    pub voted_option_policy: VotedOptionPolicy,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionRename {
+    pub option_id: String,
+    pub text: String,
+}
+
+// What to do when an edit renames or removes an option that already has votes. Resetting
+// votes that came from anonymous or guest ballots lets all such voters vote again.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum VotedOptionPolicy {
+    #[default]
+    Reject,
+    ResetVotes,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteCreation {
+    pub label: Option<String>,
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CollaboratorInvite {
+    pub collaborator: String,
+    pub role: PollRole,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipTransfer {
+    pub new_owner: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinPollRequest {
+    pub token: Option<String>,
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessCodeResponse {
+    pub access_code: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteInMerge {
+    pub target_option_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteInVisibility {
+    pub hidden: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SurveyCreation {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub questions: Vec<QuestionCreation>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionCreation {
+    pub kind: QuestionKind,
+    pub prompt: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    // Number of points of a rating question, defaults to 5.
+    pub scale: Option<u32>,
+    #[serde(default)]
+    pub required: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct UserNameRequest {
-    pub username: String,
+pub struct SurveySubmission {
+    pub answers: Vec<SurveyAnswer>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextAnswerSubmission {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextAnswerModeration {
+    pub hidden: Option<bool>,
+    pub pinned: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailabilitySubmission {
+    pub answers: Vec<SlotAnswer>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotChoice {
+    pub option_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCreation {
+    pub title: String,
+    pub poll_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPosition {
+    pub index: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationCreation {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationMemberInvite {
+    pub member: String,
+    #[serde(default)]
+    pub role: OrganizationRole,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserRoleChange {
+    pub role: UserRole,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PollReportRequest {
+    pub category: ReportCategory,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportResolution {
+    pub action: ReportAction,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+// Registers a webhook for one poll, or for every poll of the account when `pollId` is
+// left out.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookCreation {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub poll_id: Option<String>,
+}
+
+// Returned once when a webhook is registered, with the secret its deliveries are
+// signed with.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookRegistration {
+    pub webhook: WebhookSummary,
+    pub secret: String,
+}
+
+// Slash command posted by the chat platform as a form. Field names follow the
+// platform, so they are not camel cased.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct ChatCommand {
+    pub team_id: String,
+    pub user_id: String,
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+// Button callback posted by the chat platform, as JSON in the `payload` form field.
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct ChatActionForm {
+    pub payload: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct ChatActionPayload {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub team: ChatTeam,
+    pub user: ChatUser,
+    #[serde(default)]
+    pub actions: Vec<ChatAction>,
+    // Where to send the updated message, since replies to callbacks may be ignored.
+    #[serde(default)]
+    pub response_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct ChatTeam {
+    pub id: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct ChatUser {
+    pub id: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct ChatAction {
+    pub action_id: String,
+    #[serde(default)]
+    pub value: String,
 }
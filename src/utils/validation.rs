@@ -0,0 +1,501 @@
+use std::collections::HashSet;
+
+use crate::config::{config::PollLimits, error_response::FieldError, poll_config::PollError};
+use crate::models::poll_model::{Poll, PollKind, PollVisibility};
+use crate::utils::quiz_utility::MAX_QUIZ_TIME_LIMIT_SECS;
+use crate::utils::types::PollCreation;
+
+pub fn validate_text(
+    errors: &mut Vec<FieldError>,
+    field: &str,
+    value: &str,
+    max_length: usize,
+    required: bool,
+) {
+    let length = value.trim().chars().count();
+
+    if required && length == 0 {
+        errors.push(FieldError::new(field, "Must not be empty."));
+    } else if length > max_length {
+        errors.push(FieldError::new(
+            field,
+            format!("Must be at most {} characters long.", max_length),
+        ));
+    }
+}
+
+// Checks the title, description and option texts of a poll against the configured
+// limits. Used both for new polls and for the result of an edit.
+pub fn validate_poll_contents(
+    title: &str,
+    description: Option<&str>,
+    options: &[String],
+    limits: &PollLimits,
+) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    validate_title_and_description(&mut errors, title, description, limits);
+    validate_options(&mut errors, options, limits);
+
+    errors
+}
+
+// Free-text polls collect answers instead of votes, so they must not have options.
+pub fn validate_free_text_contents(
+    title: &str,
+    description: Option<&str>,
+    options: &[String],
+    limits: &PollLimits,
+) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    validate_title_and_description(&mut errors, title, description, limits);
+
+    if !options.is_empty() {
+        errors.push(FieldError::new(
+            "options",
+            "A free-text poll must not have options.",
+        ));
+    }
+
+    errors
+}
+
+fn validate_schedule_contents(
+    title: &str,
+    description: Option<&str>,
+    options: &[String],
+    limits: &PollLimits,
+) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    validate_title_and_description(&mut errors, title, description, limits);
+
+    if !options.is_empty() {
+        errors.push(FieldError::new(
+            "options",
+            "The options of a scheduling poll are given as slots.",
+        ));
+    }
+
+    errors
+}
+
+fn validate_title_and_description(
+    errors: &mut Vec<FieldError>,
+    title: &str,
+    description: Option<&str>,
+    limits: &PollLimits,
+) {
+    validate_text(errors, "title", title, limits.title_max_length, true);
+
+    if let Some(description) = description {
+        validate_text(
+            errors,
+            "description",
+            description,
+            limits.description_max_length,
+            false,
+        );
+    }
+}
+
+// Checks the number of options, their length and that no two share the same text.
+pub fn validate_options(errors: &mut Vec<FieldError>, options: &[String], limits: &PollLimits) {
+    if options.len() < limits.min_options || options.len() > limits.max_options {
+        errors.push(FieldError::new(
+            "options",
+            format!(
+                "A poll must have between {} and {} options.",
+                limits.min_options, limits.max_options
+            ),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+
+    for (index, option) in options.iter().enumerate() {
+        let field = format!("options[{}]", index);
+
+        validate_text(errors, &field, option, limits.option_max_length, true);
+
+        let normalized = option.trim().to_lowercase();
+        if !normalized.is_empty() && !seen.insert(normalized) {
+            errors.push(FieldError::new(field, "Duplicate option text."));
+        }
+    }
+}
+
+pub fn validate_poll_creation(data: &PollCreation, limits: &PollLimits) -> Result<(), PollError> {
+    let mut errors = Vec::new();
+
+    errors.extend(match data.kind {
+        PollKind::FreeText => validate_free_text_contents(
+            &data.title,
+            data.description.as_deref(),
+            &data.options,
+            limits,
+        ),
+        PollKind::Schedule => validate_schedule_contents(
+            &data.title,
+            data.description.as_deref(),
+            &data.options,
+            limits,
+        ),
+        _ => validate_poll_contents(
+            &data.title,
+            data.description.as_deref(),
+            &data.options,
+            limits,
+        ),
+    });
+
+    if data.kind == PollKind::FreeText && data.allow_write_ins {
+        errors.push(FieldError::new(
+            "allowWriteIns",
+            "A free-text poll does not take write-in options.",
+        ));
+    }
+
+    if data.visibility == PollVisibility::Organization && data.org_id.is_none() {
+        errors.push(FieldError::new(
+            "visibility",
+            "Only polls created in an organization can be restricted to it.",
+        ));
+    }
+
+    validate_quiz(&mut errors, data);
+    validate_schedule(&mut errors, data, limits);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(PollError::ValidationError(errors))
+    }
+}
+
+fn validate_quiz(errors: &mut Vec<FieldError>, data: &PollCreation) {
+    let quiz = match (&data.quiz, data.kind) {
+        (Some(quiz), PollKind::Quiz) => quiz,
+        (None, PollKind::Quiz) => {
+            errors.push(FieldError::new(
+                "quiz",
+                "A quiz poll must mark its correct options.",
+            ));
+            return;
+        }
+        (Some(_), _) => {
+            errors.push(FieldError::new(
+                "quiz",
+                "Only quiz polls take quiz settings.",
+            ));
+            return;
+        }
+        (None, _) => return,
+    };
+
+    if quiz.correct_options.is_empty() {
+        errors.push(FieldError::new(
+            "quiz.correctOptions",
+            "At least one option must be marked as correct.",
+        ));
+    }
+
+    if quiz
+        .correct_options
+        .iter()
+        .any(|index| *index >= data.options.len())
+    {
+        errors.push(FieldError::new(
+            "quiz.correctOptions",
+            "Correct options must refer to options of the poll.",
+        ));
+    }
+
+    if let Some(time_limit) = quiz.time_limit_secs {
+        if time_limit == 0 || time_limit > MAX_QUIZ_TIME_LIMIT_SECS {
+            errors.push(FieldError::new(
+                "quiz.timeLimitSecs",
+                format!(
+                    "Must be between 1 and {} seconds.",
+                    MAX_QUIZ_TIME_LIMIT_SECS
+                ),
+            ));
+        }
+    }
+
+    if data.anonymous {
+        errors.push(FieldError::new(
+            "anonymous",
+            "A quiz cannot be anonymous, its leaderboard needs names.",
+        ));
+    }
+
+    if data.allow_write_ins {
+        errors.push(FieldError::new(
+            "allowWriteIns",
+            "A quiz does not take write-in options.",
+        ));
+    }
+}
+
+// Scheduling polls take their options as time slots, the labels are generated from them.
+fn validate_schedule(errors: &mut Vec<FieldError>, data: &PollCreation, limits: &PollLimits) {
+    if data.kind != PollKind::Schedule {
+        if !data.slots.is_empty() {
+            errors.push(FieldError::new(
+                "slots",
+                "Only scheduling polls take time slots.",
+            ));
+        }
+        return;
+    }
+
+    if data.slots.len() < limits.min_options || data.slots.len() > limits.max_options {
+        errors.push(FieldError::new(
+            "slots",
+            format!(
+                "A scheduling poll must have between {} and {} slots.",
+                limits.min_options, limits.max_options
+            ),
+        ));
+    }
+
+    for (index, slot) in data.slots.iter().enumerate() {
+        let field = format!("slots[{}]", index);
+
+        if slot.ends_at <= slot.starts_at {
+            errors.push(FieldError::new(field, "Must end after it starts."));
+        } else if data.slots[..index].contains(slot) {
+            errors.push(FieldError::new(field, "Duplicate time slot."));
+        }
+    }
+
+    if data.anonymous {
+        errors.push(FieldError::new(
+            "anonymous",
+            "A scheduling poll cannot be anonymous.",
+        ));
+    }
+
+    if data.allow_write_ins {
+        errors.push(FieldError::new(
+            "allowWriteIns",
+            "A scheduling poll does not take write-in options.",
+        ));
+    }
+}
+
+// Checks a free-text answer and returns it trimmed.
+pub fn validate_text_answer(text: &str, limits: &PollLimits) -> Result<String, PollError> {
+    let mut errors = Vec::new();
+
+    validate_text(&mut errors, "text", text, limits.answer_max_length, true);
+
+    if errors.is_empty() {
+        Ok(text.trim().to_string())
+    } else {
+        Err(PollError::ValidationError(errors))
+    }
+}
+
+pub fn validate_vote_option(poll: &Poll, option_id: &str) -> Result<(), PollError> {
+    if poll
+        .options
+        .iter()
+        .any(|option| option.option_id == option_id && !option.hidden)
+    {
+        Ok(())
+    } else {
+        Err(PollError::ValidationError(vec![FieldError::new(
+            "optionId",
+            "Option does not exist in this poll.",
+        )]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::poll_model::OptionItem;
+    use crate::models::poll_model::TimeSlot;
+    use crate::utils::types::QuizCreation;
+    use chrono::DateTime;
+
+    fn limits() -> PollLimits {
+        PollLimits {
+            title_max_length: 20,
+            description_max_length: 50,
+            min_options: 2,
+            max_options: 3,
+            option_max_length: 10,
+            answer_max_length: 20,
+        }
+    }
+
+    fn creation(title: &str, options: &[&str]) -> PollCreation {
+        PollCreation {
+            title: title.to_string(),
+            description: None,
+            options: options.iter().map(|option| option.to_string()).collect(),
+            visibility: Default::default(),
+            anonymous: false,
+            results_visibility: Default::default(),
+            allow_write_ins: false,
+            kind: Default::default(),
+            quiz: None,
+            slots: vec![],
+            lock_votes: false,
+            require_proof_of_work: false,
+            allow_guests: false,
+            org_id: None,
+        }
+    }
+
+    fn fields(result: Result<(), PollError>) -> Vec<String> {
+        match result {
+            Err(PollError::ValidationError(errors)) => {
+                errors.into_iter().map(|error| error.field).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_validate_poll_creation_accepts_valid_poll() {
+        let data = creation("Favorite language", &["Rust", "Python"]);
+        assert!(validate_poll_creation(&data, &limits()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_poll_creation_reports_field_errors() {
+        let data = creation("   ", &["Rust"]);
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["title", "options"]
+        );
+
+        let data = creation("This title is far too long", &["Rust", " rust ", ""]);
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["title", "options[1]", "options[2]"]
+        );
+
+        let data = creation("Languages", &["Rust", "A very long option"]);
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["options[1]"]
+        );
+    }
+
+    #[test]
+    fn test_validate_free_text_poll_creation() {
+        let mut data = creation("Feedback", &[]);
+        data.kind = PollKind::FreeText;
+        assert!(validate_poll_creation(&data, &limits()).is_ok());
+
+        let mut data = creation("Feedback", &["Rust"]);
+        data.kind = PollKind::FreeText;
+        data.allow_write_ins = true;
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["options", "allowWriteIns"]
+        );
+
+        assert_eq!(
+            validate_text_answer("  Great talk  ", &limits()).unwrap(),
+            "Great talk"
+        );
+        assert!(validate_text_answer("   ", &limits()).is_err());
+        assert!(validate_text_answer("This answer is far too long", &limits()).is_err());
+    }
+
+    #[test]
+    fn test_validate_quiz_poll_creation() {
+        let mut data = creation("Capital of France", &["Paris", "Rome"]);
+        data.kind = PollKind::Quiz;
+        data.quiz = Some(QuizCreation {
+            correct_options: vec![0],
+            time_limit_secs: Some(20),
+            ..Default::default()
+        });
+        assert!(validate_poll_creation(&data, &limits()).is_ok());
+
+        data.anonymous = true;
+        data.quiz = Some(QuizCreation {
+            correct_options: vec![2],
+            time_limit_secs: Some(0),
+            ..Default::default()
+        });
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["quiz.correctOptions", "quiz.timeLimitSecs", "anonymous"]
+        );
+
+        data.kind = PollKind::SingleChoice;
+        data.anonymous = false;
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["quiz"]
+        );
+    }
+
+    #[test]
+    fn test_validate_schedule_poll_creation() {
+        let slot = |start: i64, end: i64| TimeSlot {
+            starts_at: DateTime::from_timestamp(start, 0).unwrap(),
+            ends_at: DateTime::from_timestamp(end, 0).unwrap(),
+        };
+
+        let mut data = creation("Team sync", &[]);
+        data.kind = PollKind::Schedule;
+        data.slots = vec![slot(0, 3600), slot(3600, 7200)];
+        assert!(validate_poll_creation(&data, &limits()).is_ok());
+
+        data.slots = vec![slot(0, 3600), slot(0, 3600), slot(7200, 3600)];
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["slots[1]", "slots[2]"]
+        );
+
+        data.kind = PollKind::SingleChoice;
+        data.options = vec!["Rust".to_string(), "Go".to_string()];
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["slots"]
+        );
+    }
+
+    #[test]
+    fn test_validate_organization_poll_creation() {
+        let mut data = creation("Team lunch", &["Pizza", "Sushi"]);
+        data.visibility = PollVisibility::Organization;
+
+        assert_eq!(
+            fields(validate_poll_creation(&data, &limits())),
+            vec!["visibility"]
+        );
+
+        data.org_id = Some("team".to_string());
+        assert!(validate_poll_creation(&data, &limits()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_vote_option() {
+        let poll = Poll {
+            options: vec![OptionItem {
+                option_id: "1".to_string(),
+                text: "Rust".to_string(),
+                votes: 0,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(validate_vote_option(&poll, "1").is_ok());
+        assert!(matches!(
+            validate_vote_option(&poll, "2"),
+            Err(PollError::ValidationError(_))
+        ));
+    }
+}
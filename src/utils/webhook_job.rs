@@ -0,0 +1,312 @@
+use actix_web::web::Data;
+use chrono::Utc;
+use log::{error, info, warn};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect::Policy,
+    Client,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+
+use crate::config::config::WebhookConfig;
+use crate::db::mongodb_repository::MongoDB;
+use crate::models::webhook_model::{DeliveryAttempt, Webhook, WebhookDelivery};
+use crate::utils::webhook_utility::{
+    delivery_outcome, is_public_address, sign_payload, DELIVERY_HEADER, EVENT_HEADER,
+    SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+
+// Longest error message kept in the delivery log.
+const ATTEMPT_ERROR_MAX_LENGTH: usize = 500;
+
+// Resolves receiver names like the system resolver but drops loopback, private and
+// link-local addresses, so a name cannot be pointed at internal services once the
+// webhook is registered.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+// Sends queued webhook deliveries. Deliveries live in the database, so events queued
+// before a restart are still sent, and failed ones are retried with exponential backoff.
+pub fn spawn_webhook_dispatcher(db: Data<MongoDB>, config: WebhookConfig) {
+    tokio::spawn(async move {
+        // Redirects are not followed, they could lead to an address the resolver refuses.
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .redirect(Policy::none());
+        if !config.allow_private_targets {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+
+        let client = match builder.build() {
+            Ok(client) => client,
+            Err(err) => {
+                error!("Failed to create the webhook client: {}", err);
+                return;
+            }
+        };
+
+        let mut interval = interval(Duration::from_secs(config.poll_interval_secs.max(1)));
+
+        loop {
+            interval.tick().await;
+
+            // Drain everything that is due before waiting for the next tick.
+            loop {
+                let now = Utc::now();
+                let locked_until = now + chrono::Duration::seconds(config.timeout_secs as i64 + 30);
+
+                match db
+                    .webhook_repository
+                    .claim_due_delivery(now, locked_until)
+                    .await
+                {
+                    Ok(Some(delivery)) => deliver(&db, &client, &config, &delivery).await,
+                    Ok(None) => break,
+                    Err(err) => {
+                        error!("Failed to read the webhook queue: {}", err);
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+async fn deliver(
+    db: &Data<MongoDB>,
+    client: &Client,
+    config: &WebhookConfig,
+    delivery: &WebhookDelivery,
+) {
+    let webhook = match db
+        .webhook_repository
+        .get_webhook_by_id(&delivery.webhook_id)
+        .await
+    {
+        Ok(Some(webhook)) => webhook,
+        // The webhook was deleted with its deliveries while this one was in flight.
+        Ok(None) => return,
+        Err(err) => {
+            error!("Failed to load webhook {}: {}", delivery.webhook_id, err);
+            return;
+        }
+    };
+
+    let attempt = send_delivery(client, &webhook, delivery).await;
+
+    let attempts_made = delivery.attempts.len() as u32 + 1;
+    let (status, next_attempt_at) =
+        delivery_outcome(attempts_made, attempt.succeeded(), config, Utc::now());
+
+    if let Some(message) = &attempt.error {
+        warn!(
+            "Webhook delivery {} attempt {} failed: {}",
+            delivery.delivery_id, attempts_made, message
+        );
+    } else {
+        info!(
+            "Delivered {} to webhook {}.",
+            delivery.event.name(),
+            webhook.webhook_id
+        );
+    }
+
+    if let Err(err) = db
+        .webhook_repository
+        .record_attempt(&delivery.delivery_id, &attempt, status, next_attempt_at)
+        .await
+    {
+        error!(
+            "Failed to record webhook delivery {}: {}",
+            delivery.delivery_id, err
+        );
+    }
+}
+
+// POSTs the delivery's body to the webhook once, signed with its secret.
+async fn send_delivery(
+    client: &Client,
+    webhook: &Webhook,
+    delivery: &WebhookDelivery,
+) -> DeliveryAttempt {
+    let started_at = Utc::now();
+    let timestamp = started_at.timestamp();
+
+    let response = client
+        .post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header(EVENT_HEADER, delivery.event.name())
+        .header(DELIVERY_HEADER, &delivery.delivery_id)
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(
+            SIGNATURE_HEADER,
+            sign_payload(&webhook.secret, timestamp, &delivery.body),
+        )
+        .body(delivery.body.clone())
+        .send()
+        .await;
+
+    let finished_at = Utc::now();
+    let (status_code, error) = match response {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            Some(format!("Receiver answered {}", response.status())),
+        ),
+        Err(err) => (
+            None,
+            Some(
+                err.to_string()
+                    .chars()
+                    .take(ATTEMPT_ERROR_MAX_LENGTH)
+                    .collect(),
+            ),
+        ),
+    };
+
+    DeliveryAttempt {
+        attempted_at: started_at,
+        status_code,
+        error,
+        duration_ms: (finished_at - started_at).num_milliseconds(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::webhook_model::WebhookEvent;
+    use crate::utils::types::WebhookCreation;
+    use crate::utils::webhook_utility::{build_delivery, build_webhook};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // Accepts a single request on a local port, answers with `status_line` and hands
+    // back the raw request.
+    async fn local_receiver(
+        status_line: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            let mut buffer = [0u8; 4096];
+
+            loop {
+                let read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+
+                    if body.len() >= length || read == 0 {
+                        break;
+                    }
+                }
+            }
+
+            let response = format!(
+                "{}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                status_line
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        (url, handle)
+    }
+
+    fn webhook(url: &str) -> Webhook {
+        build_webhook(
+            &WebhookCreation {
+                url: url.to_string(),
+                events: vec![WebhookEvent::VoteCast],
+                poll_id: None,
+            },
+            "Azeem",
+            Utc::now(),
+        )
+    }
+
+    fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+        request.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_send_delivery_signs_the_body() {
+        let (url, receiver) = local_receiver("HTTP/1.1 204 No Content").await;
+        let webhook = webhook(&url);
+        let delivery = build_delivery(
+            &webhook,
+            WebhookEvent::VoteCast,
+            "poll1",
+            r#"{"event":"vote_cast"}"#,
+            Utc::now(),
+        );
+
+        let attempt = send_delivery(&Client::new(), &webhook, &delivery).await;
+        let request = receiver.await.unwrap();
+
+        assert!(attempt.succeeded());
+        assert_eq!(attempt.status_code, Some(204));
+        assert!(request.ends_with(&delivery.body));
+        assert_eq!(header(&request, EVENT_HEADER), Some("vote_cast"));
+        assert_eq!(
+            header(&request, DELIVERY_HEADER),
+            Some(delivery.delivery_id.as_str())
+        );
+
+        let timestamp: i64 = header(&request, TIMESTAMP_HEADER).unwrap().parse().unwrap();
+        assert_eq!(
+            header(&request, SIGNATURE_HEADER),
+            Some(sign_payload(&webhook.secret, timestamp, &delivery.body).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_delivery_records_failures() {
+        let (url, receiver) = local_receiver("HTTP/1.1 500 Internal Server Error").await;
+        let webhook = webhook(&url);
+        let delivery = build_delivery(&webhook, WebhookEvent::VoteCast, "poll1", "{}", Utc::now());
+
+        let attempt = send_delivery(&Client::new(), &webhook, &delivery).await;
+        receiver.await.unwrap();
+
+        assert!(!attempt.succeeded());
+        assert_eq!(attempt.status_code, Some(500));
+        assert!(attempt.error.is_some());
+    }
+}
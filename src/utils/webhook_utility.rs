@@ -0,0 +1,426 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use nanoid::nanoid;
+use reqwest::Url;
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::config::{
+    config::WebhookConfig, error_response::FieldError, webhook_config::WebhookError,
+};
+use crate::models::poll_model::Poll;
+use crate::models::webhook_model::{DeliveryStatus, Webhook, WebhookDelivery, WebhookEvent};
+use crate::utils::types::WebhookCreation;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+const WEBHOOK_URL_MAX_LENGTH: usize = 2048;
+
+// Webhooks may only call out to the public internet, never to the server itself or to
+// services on its private network. Names are checked again when they are resolved.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64;
+
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.octets()[0] == 0
+                || shared)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_address(IpAddr::V4(ip)),
+            None => {
+                let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+                let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || unique_local
+                    || link_local)
+            }
+        },
+    }
+}
+
+fn has_public_host(url: &Url) -> bool {
+    match url.host_str() {
+        Some(host) => match host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+        {
+            Ok(ip) => is_public_address(ip),
+            Err(_) => {
+                let host = host.trim_end_matches('.').to_lowercase();
+                host != "localhost" && !host.ends_with(".localhost")
+            }
+        },
+        None => false,
+    }
+}
+
+// Private targets are refused unless the deployment allows them.
+pub fn validate_webhook_creation(
+    data: &WebhookCreation,
+    allow_private_targets: bool,
+) -> Result<(), WebhookError> {
+    let mut errors = vec![];
+
+    let url = Url::parse(&data.url).ok().filter(|url| {
+        data.url.len() <= WEBHOOK_URL_MAX_LENGTH
+            && matches!(url.scheme(), "http" | "https")
+            && url.host_str().is_some()
+    });
+
+    match url {
+        None => errors.push(FieldError::new(
+            "url",
+            "URL must be an absolute http or https address.",
+        )),
+        Some(url) if !allow_private_targets && !has_public_host(&url) => errors.push(
+            FieldError::new("url", "URL must point to a public address."),
+        ),
+        Some(_) => {}
+    }
+
+    if data.events.is_empty() {
+        errors.push(FieldError::new(
+            "events",
+            "Subscribe to at least one event.",
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(WebhookError::ValidationError(errors))
+    }
+}
+
+pub fn build_webhook(data: &WebhookCreation, owner: &str, now: DateTime<Utc>) -> Webhook {
+    let mut seen = HashSet::new();
+    let events = data
+        .events
+        .iter()
+        .copied()
+        .filter(|event| seen.insert(*event))
+        .collect();
+
+    Webhook {
+        webhook_id: nanoid!(),
+        owner: owner.to_string(),
+        poll_id: data.poll_id.clone(),
+        url: data.url.trim().to_string(),
+        secret: format!("whsec_{}", nanoid!(32)),
+        events,
+        created_at: now,
+    }
+}
+
+// Signs `timestamp.body`, so a captured delivery cannot be replayed later with a new
+// timestamp. Receivers recompute the signature with their secret and compare.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+// The body shared by every webhook notified of one event. Voters are never included,
+// only the poll and its current results.
+pub fn build_event_body(
+    event: WebhookEvent,
+    poll: &Poll,
+    results: &Value,
+    now: DateTime<Utc>,
+) -> String {
+    json!({
+        "eventId": nanoid!(),
+        "event": event,
+        "createdAt": now,
+        "data": {
+            "pollId": poll.poll_id,
+            "title": poll.title,
+            "isActive": poll.is_active,
+            "results": results,
+        }
+    })
+    .to_string()
+}
+
+pub fn build_delivery(
+    webhook: &Webhook,
+    event: WebhookEvent,
+    poll_id: &str,
+    body: &str,
+    now: DateTime<Utc>,
+) -> WebhookDelivery {
+    WebhookDelivery {
+        delivery_id: nanoid!(),
+        webhook_id: webhook.webhook_id.clone(),
+        event,
+        poll_id: poll_id.to_string(),
+        body: body.to_string(),
+        status: DeliveryStatus::Pending,
+        attempts: vec![],
+        next_attempt_at: Some(now),
+        locked_until: None,
+        redelivery_of: None,
+        created_at: now,
+    }
+}
+
+// Queues the same body again as a new delivery, whatever became of the original.
+pub fn build_redelivery(original: &WebhookDelivery, now: DateTime<Utc>) -> WebhookDelivery {
+    WebhookDelivery {
+        delivery_id: nanoid!(),
+        status: DeliveryStatus::Pending,
+        attempts: vec![],
+        next_attempt_at: Some(now),
+        locked_until: None,
+        redelivery_of: Some(original.delivery_id.clone()),
+        created_at: now,
+        ..original.clone()
+    }
+}
+
+// Delay before retrying after the given number of failed attempts: the base delay,
+// doubled for every further failure, capped at the maximum.
+pub fn retry_delay(failed_attempts: u32, config: &WebhookConfig) -> Duration {
+    let exponent = failed_attempts.saturating_sub(1).min(30);
+    let secs = config
+        .retry_base_secs
+        .saturating_mul(1 << exponent)
+        .min(config.retry_max_secs);
+
+    Duration::seconds(secs)
+}
+
+// Status of a delivery after an attempt, and when to try again if it is still pending.
+pub fn delivery_outcome(
+    attempts_made: u32,
+    succeeded: bool,
+    config: &WebhookConfig,
+    now: DateTime<Utc>,
+) -> (DeliveryStatus, Option<DateTime<Utc>>) {
+    if succeeded {
+        (DeliveryStatus::Delivered, None)
+    } else if attempts_made >= config.max_attempts {
+        (DeliveryStatus::Failed, None)
+    } else {
+        (
+            DeliveryStatus::Pending,
+            Some(now + retry_delay(attempts_made, config)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> WebhookConfig {
+        WebhookConfig {
+            max_attempts: 4,
+            retry_base_secs: 30,
+            retry_max_secs: 100,
+            timeout_secs: 10,
+            poll_interval_secs: 5,
+            max_per_user: 20,
+            allow_private_targets: false,
+        }
+    }
+
+    fn creation(url: &str, events: Vec<WebhookEvent>) -> WebhookCreation {
+        WebhookCreation {
+            url: url.to_string(),
+            events,
+            poll_id: None,
+        }
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        let signature = sign_payload("whsec_test", 1700000000, r#"{"event":"vote_cast"}"#);
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(
+            signature,
+            sign_payload("whsec_test", 1700000000, r#"{"event":"vote_cast"}"#)
+        );
+
+        assert_ne!(
+            signature,
+            sign_payload("whsec_other", 1700000000, r#"{"event":"vote_cast"}"#)
+        );
+        assert_ne!(
+            signature,
+            sign_payload("whsec_test", 1700000001, r#"{"event":"vote_cast"}"#)
+        );
+        assert_ne!(
+            signature,
+            sign_payload("whsec_test", 1700000000, r#"{"event":"poll_reset"}"#)
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_the_maximum() {
+        let config = config();
+
+        assert_eq!(retry_delay(1, &config), Duration::seconds(30));
+        assert_eq!(retry_delay(2, &config), Duration::seconds(60));
+        assert_eq!(retry_delay(3, &config), Duration::seconds(100));
+        assert_eq!(retry_delay(40, &config), Duration::seconds(100));
+    }
+
+    #[test]
+    fn test_delivery_outcome() {
+        let config = config();
+        let now = Utc::now();
+
+        assert_eq!(
+            delivery_outcome(1, true, &config, now),
+            (DeliveryStatus::Delivered, None)
+        );
+        assert_eq!(
+            delivery_outcome(2, false, &config, now),
+            (DeliveryStatus::Pending, Some(now + Duration::seconds(60)))
+        );
+        assert_eq!(
+            delivery_outcome(4, false, &config, now),
+            (DeliveryStatus::Failed, None)
+        );
+    }
+
+    #[test]
+    fn test_validate_webhook_creation() {
+        assert!(validate_webhook_creation(
+            &creation(
+                "https://hooks.example.com:9000/hooks",
+                vec![WebhookEvent::VoteCast]
+            ),
+            false
+        )
+        .is_ok());
+
+        for url in [
+            "ftp://example.com",
+            "not a url",
+            "https://",
+            "http://localhost:9000/hooks",
+            "http://api.localhost/hooks",
+            "http://127.0.0.1/hooks",
+            "http://2130706433/hooks",
+            "http://10.1.2.3/hooks",
+            "http://192.168.0.10/hooks",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/hooks",
+            "http://[fe80::1]/hooks",
+            "http://[fd00::1]/hooks",
+            "http://[::ffff:127.0.0.1]/hooks",
+        ] {
+            assert!(
+                validate_webhook_creation(&creation(url, vec![WebhookEvent::VoteCast]), false)
+                    .is_err()
+            );
+        }
+
+        assert!(
+            validate_webhook_creation(&creation("https://example.com", vec![]), false).is_err()
+        );
+
+        let internal = creation("http://10.1.2.3/hooks", vec![WebhookEvent::VoteCast]);
+        assert!(validate_webhook_creation(&internal, true).is_ok());
+        assert!(validate_webhook_creation(&creation("not a url", vec![]), true).is_err());
+    }
+
+    #[test]
+    fn test_is_public_address() {
+        assert!(is_public_address("93.184.216.34".parse().unwrap()));
+        assert!(is_public_address("2606:2800:220:1::".parse().unwrap()));
+
+        for ip in [
+            "127.0.0.1",
+            "0.0.0.0",
+            "10.0.0.1",
+            "172.16.5.4",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "::1",
+            "::",
+            "fe80::1",
+            "fc00::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_build_webhook_dedupes_events() {
+        let webhook = build_webhook(
+            &creation(
+                " https://example.com/hooks ",
+                vec![
+                    WebhookEvent::VoteCast,
+                    WebhookEvent::PollClosed,
+                    WebhookEvent::VoteCast,
+                ],
+            ),
+            "Azeem",
+            Utc::now(),
+        );
+
+        assert_eq!(webhook.owner, "Azeem");
+        assert_eq!(webhook.url, "https://example.com/hooks");
+        assert_eq!(
+            webhook.events,
+            vec![WebhookEvent::VoteCast, WebhookEvent::PollClosed]
+        );
+        assert!(webhook.secret.starts_with("whsec_"));
+        assert!(webhook.subscribes_to(WebhookEvent::PollClosed));
+        assert!(!webhook.subscribes_to(WebhookEvent::PollReset));
+    }
+
+    #[test]
+    fn test_redelivery_keeps_the_body() {
+        let webhook = build_webhook(
+            &creation("https://example.com", vec![WebhookEvent::VoteCast]),
+            "Azeem",
+            Utc::now(),
+        );
+        let mut original =
+            build_delivery(&webhook, WebhookEvent::VoteCast, "poll1", "{}", Utc::now());
+        original.status = DeliveryStatus::Failed;
+
+        let redelivery = build_redelivery(&original, Utc::now());
+
+        assert_ne!(redelivery.delivery_id, original.delivery_id);
+        assert_eq!(redelivery.body, original.body);
+        assert_eq!(redelivery.status, DeliveryStatus::Pending);
+        assert_eq!(
+            redelivery.redelivery_of.as_deref(),
+            Some(original.delivery_id.as_str())
+        );
+    }
+}
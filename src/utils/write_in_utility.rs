@@ -0,0 +1,273 @@
+use crate::config::{config::PollLimits, error_response::FieldError, poll_config::PollError};
+use crate::models::poll_model::{OptionItem, Poll, RevisionChange};
+use crate::utils::poll_edit_utility::PollEditOutcome;
+
+// Trims a write-in and collapses inner runs of whitespace, so "  Go   lang " and
+// "Go lang" end up as the same option.
+pub fn normalize_write_in(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn same_option_text(a: &str, b: &str) -> bool {
+    normalize_write_in(a).to_lowercase() == normalize_write_in(b).to_lowercase()
+}
+
+// Anchored, case-insensitive regex matching an option with exactly this text. Used to
+// insert a write-in only if no equivalent option exists, in a single update.
+pub fn option_text_pattern(text: &str) -> String {
+    let mut pattern = String::from("^");
+
+    for c in text.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+
+    pattern.push('$');
+    pattern
+}
+
+pub fn find_matching_option<'a>(poll: &'a Poll, text: &str) -> Option<&'a OptionItem> {
+    poll.options
+        .iter()
+        .find(|option| same_option_text(&option.text, text))
+}
+
+// Checks that the poll takes write-ins and returns the normalized text to store.
+pub fn validate_write_in(
+    poll: &Poll,
+    text: &str,
+    limits: &PollLimits,
+) -> Result<String, PollError> {
+    if !poll.allow_write_ins {
+        return Err(PollError::InvalidRequest(
+            "This poll does not accept write-in options.".to_string(),
+        ));
+    }
+
+    let text = normalize_write_in(text);
+    let length = text.chars().count();
+
+    let error = if length == 0 {
+        Some("Must not be empty.".to_string())
+    } else if length > limits.option_max_length {
+        Some(format!(
+            "Must be at most {} characters long.",
+            limits.option_max_length
+        ))
+    } else {
+        None
+    };
+
+    match error {
+        Some(message) => Err(PollError::ValidationError(vec![FieldError::new(
+            "writeIn", message,
+        )])),
+        None => Ok(text),
+    }
+}
+
+fn find_write_in<'a>(
+    options: &'a mut [OptionItem],
+    option_id: &str,
+) -> Result<&'a mut OptionItem, PollError> {
+    options
+        .iter_mut()
+        .find(|option| option.option_id == option_id && option.write_in)
+        .ok_or_else(|| {
+            PollError::InvalidRequest(format!(
+                "Write-in option '{}' does not exist in this poll.",
+                option_id
+            ))
+        })
+}
+
+fn unchanged_outcome(poll: &Poll) -> PollEditOutcome {
+    PollEditOutcome {
+        title: poll.title.clone(),
+        description: poll.description.clone(),
+        visibility: poll.visibility,
+        results_visibility: poll.results_visibility,
+        lock_votes: poll.lock_votes,
+        options: poll.options.clone(),
+        voters: poll.voters.clone(),
+        clear_participation: false,
+        clear_guest_ballots: false,
+        changes: vec![],
+    }
+}
+
+pub fn apply_write_in_visibility(
+    poll: &Poll,
+    option_id: &str,
+    hidden: bool,
+) -> Result<PollEditOutcome, PollError> {
+    let mut outcome = unchanged_outcome(poll);
+
+    let option = find_write_in(&mut outcome.options, option_id)?;
+
+    if option.hidden == hidden {
+        return Err(PollError::InvalidRequest(
+            "The edit does not change anything.".to_string(),
+        ));
+    }
+
+    option.hidden = hidden;
+
+    outcome.changes.push(RevisionChange {
+        field: format!("options.{}.hidden", option_id),
+        before: Some((!hidden).to_string()),
+        after: Some(hidden.to_string()),
+    });
+
+    Ok(outcome)
+}
+
+// Folds a write-in into another option: its votes and voters move to the target and the
+// write-in itself is removed.
+pub fn apply_write_in_merge(
+    poll: &Poll,
+    option_id: &str,
+    target_option_id: &str,
+) -> Result<PollEditOutcome, PollError> {
+    if option_id == target_option_id {
+        return Err(PollError::InvalidRequest(
+            "A write-in cannot be merged into itself.".to_string(),
+        ));
+    }
+
+    let mut outcome = unchanged_outcome(poll);
+
+    let source = find_write_in(&mut outcome.options, option_id)?.clone();
+
+    let target = outcome
+        .options
+        .iter_mut()
+        .find(|option| option.option_id == target_option_id && !option.hidden)
+        .ok_or_else(|| {
+            PollError::InvalidRequest(format!(
+                "Option '{}' does not exist in this poll.",
+                target_option_id
+            ))
+        })?;
+
+    target.votes += source.votes;
+    target.guest_votes += source.guest_votes;
+
+    outcome.changes.push(RevisionChange {
+        field: format!("options.{}", source.option_id),
+        before: Some(source.text.clone()),
+        after: Some(format!("merged into {}", target.text)),
+    });
+
+    outcome
+        .options
+        .retain(|option| option.option_id != source.option_id);
+
+    for vote in &mut outcome.voters {
+        if vote.option_id == source.option_id {
+            vote.option_id = target_option_id.to_string();
+        }
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::poll_model::VoteHistory;
+
+    fn sample_poll() -> Poll {
+        Poll {
+            allow_write_ins: true,
+            options: vec![
+                OptionItem {
+                    option_id: "1".to_string(),
+                    text: "Rust".to_string(),
+                    votes: 1,
+                    ..Default::default()
+                },
+                OptionItem {
+                    option_id: "2".to_string(),
+                    text: "Go lang".to_string(),
+                    votes: 2,
+                    write_in: true,
+                    ..Default::default()
+                },
+            ],
+            voters: vec![
+                VoteHistory {
+                    username: "a".to_string(),
+                    option_id: "1".to_string(),
+                },
+                VoteHistory {
+                    username: "b".to_string(),
+                    option_id: "2".to_string(),
+                },
+                VoteHistory {
+                    username: "c".to_string(),
+                    option_id: "2".to_string(),
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_write_ins_are_normalized_and_deduplicated() {
+        let poll = sample_poll();
+        let limits = PollLimits {
+            title_max_length: 20,
+            description_max_length: 50,
+            min_options: 2,
+            max_options: 3,
+            option_max_length: 10,
+            answer_max_length: 20,
+        };
+
+        assert_eq!(
+            validate_write_in(&poll, "  GO \t  Lang ", &limits).unwrap(),
+            "GO Lang"
+        );
+        assert_eq!(
+            find_matching_option(&poll, "go LANG").map(|option| option.option_id.as_str()),
+            Some("2")
+        );
+        assert!(find_matching_option(&poll, "Python").is_none());
+        assert!(matches!(
+            validate_write_in(&poll, "   ", &limits),
+            Err(PollError::ValidationError(_))
+        ));
+
+        let closed = Poll {
+            allow_write_ins: false,
+            ..sample_poll()
+        };
+        assert!(matches!(
+            validate_write_in(&closed, "Python", &limits),
+            Err(PollError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_option_text_pattern_escapes_regex() {
+        assert_eq!(option_text_pattern("C++ (v2)"), "^C\\+\\+ \\(v2\\)$");
+    }
+
+    #[test]
+    fn test_apply_write_in_merge_moves_votes() {
+        let outcome = apply_write_in_merge(&sample_poll(), "2", "1").unwrap();
+
+        assert_eq!(outcome.options.len(), 1);
+        assert_eq!(outcome.options[0].votes, 3);
+        assert!(outcome.voters.iter().all(|vote| vote.option_id == "1"));
+        assert_eq!(outcome.changes.len(), 1);
+
+        assert!(matches!(
+            apply_write_in_merge(&sample_poll(), "1", "2"),
+            Err(PollError::InvalidRequest(_))
+        ));
+    }
+}